@@ -0,0 +1,116 @@
+use std::io::{Read, Write};
+
+use audiopus::{coder::Encoder, softclip::SoftClip, Application, Channels, SampleRate};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use rubato::{FftFixedOut, Resampler};
+use spoticord_audio::{opus::OpusStream, stream::Stream};
+
+/// One second of stereo f32 audio at 44.1 kHz, like librespot produces
+const SECOND: usize = 44100 * 2 * 4;
+
+/// The amount of 20ms frames in one second of audio
+const FRAMES: usize = 50;
+
+fn audio() -> Vec<u8> {
+    (0..44100)
+        .flat_map(|i| {
+            let sample = (i as f32 * 440.0 * std::f32::consts::TAU / 44100.0).sin() * 0.5;
+
+            [sample.to_ne_bytes(), sample.to_ne_bytes()]
+        })
+        .flatten()
+        .collect()
+}
+
+fn filled_stream(audio: &[u8]) -> Stream {
+    let stream = Stream::with_capacity(SECOND);
+    stream.clone().write_all(audio).expect("write failed");
+
+    stream
+}
+
+/// The work songbird does in its mixer for every call that is fed raw PCM: resampling to 48 kHz, mixing,
+/// soft clipping and encoding. With Opus passthrough, the mixer forwards our frames without touching them.
+fn songbird_mixer(stream: &mut Stream) {
+    let mut resampler = FftFixedOut::<f32>::new(44100, 48000, 960, 1, 2).expect("resampler");
+    let encoder =
+        Encoder::new(SampleRate::Hz48000, Channels::Stereo, Application::Audio).expect("encoder");
+    let mut soft_clip = SoftClip::new(Channels::Stereo);
+
+    let mut pcm = Vec::new();
+    let mut input = vec![Vec::new(); 2];
+    let mut output = resampler.output_buffer_allocate(true);
+    let mut mix = vec![0f32; 960 * 2];
+    let mut packet = [0u8; 4000];
+
+    for _ in 0..FRAMES {
+        pcm.resize(resampler.input_frames_next() * 8, 0);
+        stream.read_exact(&mut pcm).expect("read failed");
+
+        for channel in &mut input {
+            channel.clear();
+        }
+
+        for sample in pcm.chunks_exact(8) {
+            input[0].push(f32::from_ne_bytes([
+                sample[0], sample[1], sample[2], sample[3],
+            ]));
+            input[1].push(f32::from_ne_bytes([
+                sample[4], sample[5], sample[6], sample[7],
+            ]));
+        }
+
+        resampler
+            .process_into_buffer(&input, &mut output, None)
+            .expect("resample failed");
+
+        for (i, sample) in mix.chunks_exact_mut(2).enumerate() {
+            sample[0] = output[0][i];
+            sample[1] = output[1][i];
+        }
+
+        soft_clip
+            .apply((&mut mix[..]).try_into().expect("frame"))
+            .expect("clip failed");
+        encoder
+            .encode_float(&mix, &mut packet)
+            .expect("encode failed");
+    }
+}
+
+fn opus(c: &mut Criterion) {
+    let audio = audio();
+
+    let mut group = c.benchmark_group("opus");
+    group.throughput(Throughput::Bytes(SECOND as u64));
+
+    // Cost per second of audio per session, when songbird has to encode
+    group.bench_function("songbird_mixer", |b| {
+        b.iter_batched(
+            || filled_stream(&audio),
+            |mut stream| songbird_mixer(&mut stream),
+            BatchSize::LargeInput,
+        )
+    });
+
+    // Cost per second of audio per session, when Spoticord encodes and songbird passes the frames through
+    group.bench_function("passthrough", |b| {
+        b.iter_batched(
+            || OpusStream::new(filled_stream(&audio)).expect("opus stream"),
+            |mut stream| {
+                let mut frame = [0u8; 4096];
+
+                // The header plus one frame per 20ms
+                for _ in 0..=FRAMES {
+                    _ = stream.read(&mut frame).expect("read failed");
+                }
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, opus);
+criterion_main!(benches);
@@ -0,0 +1,144 @@
+use std::{
+    io::{Read, Write},
+    sync::{Arc, Condvar, Mutex},
+    thread,
+};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use spoticord_audio::stream::Stream;
+
+/// The amount of bytes every session streams per iteration (about one second of stereo f32 audio at 48 kHz)
+const BYTES_PER_SESSION: usize = 48_000 * 2 * 4;
+
+/// The size of the packets librespot writes into the sink
+const PACKET_SIZE: usize = 4096;
+
+/// The size of the frames songbird reads from the stream (20 ms of stereo f32 audio at 48 kHz)
+const FRAME_SIZE: usize = 960 * 2 * 4;
+
+/// The stream implementation that was used before the ring buffer, kept around as a baseline
+#[derive(Clone, Default)]
+struct MutexStream {
+    inner: Arc<(Mutex<Vec<u8>>, Condvar)>,
+}
+
+impl Read for MutexStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let (mutex, condvar) = &*self.inner;
+        let mut buffer = mutex.lock().expect("Mutex was poisoned");
+
+        if buffer.is_empty() {
+            buf.fill(0);
+            condvar.notify_all();
+
+            return Ok(buf.len());
+        }
+
+        let max_read = usize::min(buf.len(), buffer.len());
+
+        buf[0..max_read].copy_from_slice(&buffer[0..max_read]);
+        buffer.drain(0..max_read);
+        condvar.notify_all();
+
+        Ok(max_read)
+    }
+}
+
+impl Write for MutexStream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let (mutex, condvar) = &*self.inner;
+        let mut buffer = mutex.lock().expect("Mutex was poisoned");
+
+        while buffer.len() + buf.len() > 64 * 1024 {
+            buffer = condvar.wait(buffer).expect("Mutex was poisoned");
+        }
+
+        buffer.extend_from_slice(buf);
+        condvar.notify_all();
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Stream audio through `sessions` streams at once, with a writer thread per stream (like librespot) and a
+/// single thread reading frames from every stream in turn (like the songbird mixer).
+fn run<S>(sessions: usize, create: impl Fn() -> S)
+where
+    S: Read + Write + Clone + Send + 'static,
+{
+    let streams: Vec<S> = (0..sessions).map(|_| create()).collect();
+
+    let writers: Vec<_> = streams
+        .iter()
+        .cloned()
+        .map(|mut stream| {
+            thread::spawn(move || {
+                let packet = [1u8; PACKET_SIZE];
+                let mut written = 0;
+
+                while written < BYTES_PER_SESSION {
+                    let len = PACKET_SIZE.min(BYTES_PER_SESSION - written);
+
+                    stream.write_all(&packet[..len]).expect("write failed");
+                    written += len;
+                }
+            })
+        })
+        .collect();
+
+    let mut readers = streams;
+    let mut received = vec![0; sessions];
+    let mut frame = [0u8; FRAME_SIZE];
+
+    while received
+        .iter()
+        .any(|received| *received < BYTES_PER_SESSION)
+    {
+        for (stream, received) in readers.iter_mut().zip(received.iter_mut()) {
+            if *received >= BYTES_PER_SESSION {
+                continue;
+            }
+
+            let len = stream.read(&mut frame).expect("read failed");
+
+            // Silence is inserted while the writer hasn't caught up yet
+            if frame[0] != 0 {
+                *received += len;
+            }
+        }
+    }
+
+    for writer in writers {
+        writer.join().expect("writer panicked");
+    }
+}
+
+fn stream(c: &mut Criterion) {
+    let mut group = c.benchmark_group("stream");
+    group.sample_size(20);
+
+    for sessions in [1, 16, 64] {
+        group.throughput(Throughput::Bytes((sessions * BYTES_PER_SESSION) as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("ring", sessions),
+            &sessions,
+            |b, &sessions| b.iter(|| run(sessions, Stream::new)),
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("mutex", sessions),
+            &sessions,
+            |b, &sessions| b.iter(|| run(sessions, MutexStream::default)),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, stream);
+criterion_main!(benches);
@@ -0,0 +1,406 @@
+use std::{collections::VecDeque, f32::consts::FRAC_PI_2, time::Duration};
+
+use librespot::playback::player::{PlayerEvent, PlayerEventChannel};
+use tokio::sync::{oneshot, watch};
+
+/// The sample rate of the audio that librespot produces
+const SAMPLE_RATE: u64 = 44100;
+
+const CHANNELS: usize = 2;
+
+/// The longest fade that is used when a track is skipped or seeked before its end
+const SKIP_FADE: Duration = Duration::from_millis(250);
+
+/// Blends the end of the outgoing track with the start of the next one.
+///
+/// Track boundaries are detected using the events of the librespot player, which are emitted on the same thread
+/// that writes to the sink, so they are always in sync with the audio.
+///
+/// Near the end of a track (if a next track has been preloaded), the outgoing audio is held back instead of being
+/// played, and is mixed into the start of the next track once it arrives. Outside of that window only a short tail is
+/// held back, which is faded out when a track is skipped or seeked, so those only get delayed by a fraction of a second.
+pub struct Crossfader {
+    duration: watch::Receiver<Duration>,
+
+    subscription: Option<oneshot::Receiver<PlayerEventChannel>>,
+    events: Option<PlayerEventChannel>,
+
+    /// Processed samples that have not been released yet
+    held: VecDeque<f32>,
+
+    /// The outgoing audio that is being faded out
+    tail: Vec<f32>,
+    tail_position: usize,
+
+    /// The position (in frames) of the librespot decoder within the current track
+    position: u64,
+
+    /// The length (in frames) of the current track
+    track_length: Option<u64>,
+
+    /// Whether the next track has been preloaded, which means it will start as soon as the current track ends
+    next_track: bool,
+
+    /// Whether the current track ended, and the held audio should be faded out
+    boundary: bool,
+}
+
+impl Crossfader {
+    /// Create a new crossfader, which starts detecting tracks once a librespot event channel is provided
+    pub fn new(
+        duration: watch::Receiver<Duration>,
+        subscription: oneshot::Receiver<PlayerEventChannel>,
+    ) -> Self {
+        Self {
+            duration,
+            subscription: Some(subscription),
+            events: None,
+            held: VecDeque::new(),
+            tail: Vec::new(),
+            tail_position: 0,
+            position: 0,
+            track_length: None,
+            next_track: false,
+            boundary: false,
+        }
+    }
+
+    /// Crossfade a buffer of interleaved stereo samples.
+    ///
+    /// `frames` is the amount of frames librespot produced for this buffer, which might differ from the size of
+    /// `samples` if effects altered the playback rate.
+    pub fn process(&mut self, samples: &mut Vec<f32>, frames: usize) {
+        self.handle_events();
+        self.position += frames as u64;
+
+        let fade = to_frames(*self.duration.borrow());
+
+        if fade == 0 && self.held.is_empty() && self.tail.is_empty() {
+            self.boundary = false;
+            return;
+        }
+
+        if std::mem::take(&mut self.boundary) {
+            self.tail = self.held.drain(..).collect();
+            self.tail_position = 0;
+        }
+
+        if !self.tail.is_empty() {
+            self.mix(samples);
+        }
+
+        self.held.extend(samples.drain(..));
+
+        // Hold back the entire fade when the track is about to end, but only if another track follows
+        let remaining = self
+            .track_length
+            .map(|length| length.saturating_sub(self.position));
+
+        let skip_fade = fade.min(to_frames(SKIP_FADE));
+
+        let hold = match remaining {
+            Some(remaining) if remaining <= fade && self.next_track => fade,
+
+            // Nothing is mixed into the end of the last track, so that is played in full
+            Some(remaining) if remaining <= skip_fade => 0,
+            _ => skip_fade,
+        };
+
+        let release = self.held.len().saturating_sub(hold as usize * CHANNELS);
+
+        samples.extend(self.held.drain(..release));
+    }
+
+    /// Mix the outgoing audio with the start of the incoming track using an equal power curve
+    fn mix(&mut self, samples: &mut [f32]) {
+        let length = (self.tail.len() / CHANNELS) as f32;
+
+        for frame in samples.chunks_exact_mut(CHANNELS) {
+            if self.tail_position >= self.tail.len() {
+                break;
+            }
+
+            let (fade_in, fade_out) = equal_power((self.tail_position / CHANNELS) as f32 / length);
+
+            for (sample, outgoing) in frame
+                .iter_mut()
+                .zip(&self.tail[self.tail_position..][..CHANNELS])
+            {
+                *sample = *sample * fade_in + outgoing * fade_out;
+            }
+
+            self.tail_position += CHANNELS;
+        }
+
+        if self.tail_position >= self.tail.len() {
+            self.tail.clear();
+            self.tail_position = 0;
+        }
+    }
+
+    fn handle_events(&mut self) {
+        if let Some(subscription) = &mut self.subscription {
+            if let Ok(events) = subscription.try_recv() {
+                self.events = Some(events);
+                self.subscription = None;
+            }
+        }
+
+        let Some(events) = &mut self.events else {
+            return;
+        };
+
+        while let Ok(event) = events.try_recv() {
+            match event {
+                PlayerEvent::TrackChanged { audio_item } => {
+                    self.track_length = Some(to_frames(Duration::from_millis(
+                        audio_item.duration_ms as u64,
+                    )));
+                    self.next_track = false;
+                    self.position = 0;
+                    self.boundary = true;
+                }
+                PlayerEvent::EndOfTrack { .. } => {
+                    self.boundary = true;
+                }
+                // A track is loaded when it is skipped to, after which the outgoing track stops playing
+                PlayerEvent::Loading { .. } => {
+                    self.next_track = false;
+                    self.boundary = true;
+                }
+                PlayerEvent::Preloading { .. } => {
+                    self.next_track = true;
+                }
+                PlayerEvent::Seeked { position_ms, .. } => {
+                    self.position = to_frames(Duration::from_millis(position_ms as u64));
+                    self.boundary = true;
+                }
+                PlayerEvent::Playing { position_ms, .. }
+                | PlayerEvent::PositionCorrection { position_ms, .. } => {
+                    self.position = to_frames(Duration::from_millis(position_ms as u64));
+                }
+                PlayerEvent::Stopped { .. } => {
+                    self.held.clear();
+                    self.tail.clear();
+                    self.tail_position = 0;
+                    self.track_length = None;
+                    self.next_track = false;
+                    self.boundary = false;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// The gains of the incoming and outgoing track at `progress` (0.0 to 1.0) through the fade
+fn equal_power(progress: f32) -> (f32, f32) {
+    (progress * FRAC_PI_2).sin_cos()
+}
+
+fn to_frames(duration: Duration) -> u64 {
+    duration.as_millis() as u64 * SAMPLE_RATE / 1000
+}
+
+#[cfg(test)]
+mod tests {
+    use librespot::{
+        core::SpotifyId,
+        metadata::{
+            artist::ArtistsWithRole,
+            audio::{AudioFiles, AudioItem, UniqueFields},
+        },
+    };
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    const BUFFER: usize = 4410;
+    const TRACK: &str = "spotify:track:4uLU6hMCjMI75M1A2tKUQC";
+
+    /// Create a crossfader, along with the channel that the librespot player would send its events on
+    fn crossfader(fade: Duration) -> (Crossfader, mpsc::UnboundedSender<PlayerEvent>) {
+        let (_, duration) = watch::channel(fade);
+        let (subscribe, subscription) = oneshot::channel();
+        let (events, rx) = mpsc::unbounded_channel();
+
+        subscribe.send(rx).expect("subscription was dropped");
+
+        (Crossfader::new(duration, subscription), events)
+    }
+
+    fn id() -> SpotifyId {
+        SpotifyId::from_uri(TRACK).expect("invalid uri")
+    }
+
+    fn track_changed(length: Duration) -> PlayerEvent {
+        PlayerEvent::TrackChanged {
+            audio_item: Box::new(AudioItem {
+                track_id: id(),
+                uri: TRACK.to_string(),
+                files: AudioFiles::default(),
+                name: "Name".to_string(),
+                covers: vec![],
+                language: vec![],
+                duration_ms: length.as_millis() as u32,
+                is_explicit: false,
+                availability: Ok(()),
+                alternatives: None,
+                unique_fields: UniqueFields::Track {
+                    artists: ArtistsWithRole::default(),
+                    album: "Album".to_string(),
+                    album_artists: vec![],
+                    popularity: 50,
+                    number: 3,
+                    disc_number: 1,
+                },
+            }),
+        }
+    }
+
+    fn loading() -> PlayerEvent {
+        PlayerEvent::Loading {
+            play_request_id: 0,
+            track_id: id(),
+            position_ms: 0,
+        }
+    }
+
+    fn preloading() -> PlayerEvent {
+        PlayerEvent::Preloading { track_id: id() }
+    }
+
+    fn end_of_track() -> PlayerEvent {
+        PlayerEvent::EndOfTrack {
+            play_request_id: 0,
+            track_id: id(),
+        }
+    }
+
+    /// Play `frames` frames of a constant value, returning the frames that were released
+    fn play(crossfader: &mut Crossfader, value: f32, frames: usize) -> Vec<f32> {
+        let mut output = vec![];
+
+        for _ in 0..frames / BUFFER {
+            let mut samples = vec![value; BUFFER * CHANNELS];
+            crossfader.process(&mut samples, BUFFER);
+
+            output.extend(samples);
+        }
+
+        output
+    }
+
+    /// Check that `output` fades from `outgoing` into `incoming` over `fade` frames, after which only `incoming` plays
+    fn assert_fade(output: &[f32], outgoing: f32, incoming: f32, fade: usize) {
+        for (frame, samples) in output.chunks_exact(CHANNELS).enumerate() {
+            let expected = if frame < fade {
+                let (fade_in, fade_out) = equal_power(frame as f32 / fade as f32);
+                incoming * fade_in + outgoing * fade_out
+            } else {
+                incoming
+            };
+
+            for sample in samples {
+                assert!(
+                    (sample - expected).abs() < 1e-5,
+                    "frame {frame} is {sample} instead of {expected}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn equal_power_curve() {
+        assert_eq!(equal_power(0.0), (0.0, 1.0));
+
+        let (fade_in, fade_out) = equal_power(1.0);
+        assert!((fade_in - 1.0).abs() < 1e-6 && fade_out.abs() < 1e-6);
+
+        let mut previous = 0.0;
+
+        for step in 0..=100 {
+            let (fade_in, fade_out) = equal_power(step as f32 / 100.0);
+
+            assert!((fade_in * fade_in + fade_out * fade_out - 1.0).abs() < 1e-6);
+            assert!(fade_in >= previous);
+
+            previous = fade_in;
+        }
+    }
+
+    #[test]
+    fn nothing_is_held_without_crossfade() {
+        let (mut crossfader, events) = crossfader(Duration::ZERO);
+        events.send(track_changed(Duration::from_secs(2))).unwrap();
+        events.send(preloading()).unwrap();
+
+        assert_eq!(
+            play(&mut crossfader, 0.5, BUFFER * 20),
+            vec![0.5; BUFFER * 20 * CHANNELS]
+        );
+    }
+
+    #[test]
+    fn only_a_short_tail_is_held_outside_the_window() {
+        let skip_fade = to_frames(SKIP_FADE) as usize;
+
+        let (mut crossfader, events) = crossfader(Duration::from_secs(1));
+        events.send(track_changed(Duration::from_secs(10))).unwrap();
+        events.send(preloading()).unwrap();
+
+        let output = play(&mut crossfader, 0.5, BUFFER * 10);
+        assert_eq!(output, vec![0.5; (BUFFER * 10 - skip_fade) * CHANNELS]);
+    }
+
+    #[test]
+    fn last_track_is_played_in_full() {
+        let (mut crossfader, events) = crossfader(Duration::from_secs(1));
+        events.send(track_changed(Duration::from_secs(2))).unwrap();
+
+        // Without a next track, the end of the track is not held back
+        let output = play(&mut crossfader, 0.5, BUFFER * 20);
+        assert_eq!(output, vec![0.5; BUFFER * 20 * CHANNELS]);
+    }
+
+    #[test]
+    fn tracks_overlap() {
+        let fade = to_frames(Duration::from_secs(1)) as usize;
+        let skip_fade = to_frames(SKIP_FADE) as usize;
+
+        let (mut crossfader, events) = crossfader(Duration::from_secs(1));
+        events.send(track_changed(Duration::from_secs(2))).unwrap();
+        events.send(preloading()).unwrap();
+
+        // The last second of the outgoing track is held back
+        let outgoing = play(&mut crossfader, 1.0, fade * 2);
+        assert_eq!(outgoing, vec![1.0; fade * CHANNELS]);
+
+        events.send(end_of_track()).unwrap();
+        events.send(track_changed(Duration::from_secs(10))).unwrap();
+
+        // Once the fade is over, the incoming track plays as is
+        let incoming = play(&mut crossfader, 0.5, fade * 2);
+        assert_eq!(incoming.len(), (fade * 2 - skip_fade) * CHANNELS);
+        assert_fade(&incoming, 1.0, 0.5, fade);
+    }
+
+    #[test]
+    fn skips_are_faded() {
+        let skip_fade = to_frames(SKIP_FADE) as usize;
+
+        let (mut crossfader, events) = crossfader(Duration::from_secs(1));
+        events.send(track_changed(Duration::from_secs(10))).unwrap();
+
+        let outgoing = play(&mut crossfader, 1.0, BUFFER * 10);
+        assert_eq!(outgoing, vec![1.0; (BUFFER * 10 - skip_fade) * CHANNELS]);
+
+        // Skipping halfway through the track fades out the tail that was held back
+        events.send(loading()).unwrap();
+        events.send(track_changed(Duration::from_secs(10))).unwrap();
+
+        let incoming = play(&mut crossfader, 0.5, BUFFER * 10);
+        assert_eq!(incoming.len(), (BUFFER * 10 - skip_fade) * CHANNELS);
+        assert_fade(&incoming, 1.0, 0.5, skip_fade);
+    }
+}
@@ -0,0 +1,742 @@
+use std::f32::consts::{FRAC_PI_4, SQRT_2, TAU};
+
+use tokio::sync::watch;
+
+/// The sample rate of the audio that librespot produces
+const SAMPLE_RATE: f32 = 44100.0;
+
+const CHANNELS: usize = 2;
+
+/// How long (in samples) it takes to fade between two sets of filters, or to apply any other change
+const TRANSITION: usize = 2205;
+
+/// The playback rate that is used for the nightcore effect
+const NIGHTCORE_RATE: f32 = 1.25;
+
+/// The amount of time (in seconds) it takes the 8D effect to make a full circle around the listener
+const ROTATION_PERIOD: f32 = 8.0;
+
+/// How far the 8D effect pans to either side, where 1.0 is hard panning
+const ROTATION_WIDTH: f32 = 0.8;
+
+/// The effects that should be applied to the audio
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Effects {
+    pub equalizer: Equalizer,
+    pub bass_boost: BassBoost,
+
+    /// Speed up the audio, which also raises the pitch
+    pub nightcore: bool,
+
+    /// Slowly rotate the audio around the listener
+    pub rotate: bool,
+
+    /// Downmix the audio to mono
+    pub mono: bool,
+}
+
+impl Effects {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Equalizer {
+    #[default]
+    Flat,
+    Pop,
+    Rock,
+    Jazz,
+    Classical,
+    Electronic,
+    Vocal,
+}
+
+impl Equalizer {
+    fn bands(&self) -> &'static [Band] {
+        use Band::*;
+
+        match self {
+            Self::Flat => &[],
+            Self::Pop => &[
+                LowShelf(100.0, -1.0),
+                Peak(1000.0, 2.0, 1.0),
+                Peak(3000.0, 3.0, 1.0),
+                HighShelf(8000.0, 1.0),
+            ],
+            Self::Rock => &[
+                LowShelf(100.0, 4.0),
+                Peak(800.0, -2.0, 1.0),
+                Peak(3000.0, 2.0, 1.0),
+                HighShelf(8000.0, 4.0),
+            ],
+            Self::Jazz => &[
+                LowShelf(100.0, 2.0),
+                Peak(1000.0, -1.0, 1.0),
+                HighShelf(8000.0, 2.0),
+            ],
+            Self::Classical => &[LowShelf(100.0, 1.0), HighShelf(8000.0, 3.0)],
+            Self::Electronic => &[
+                LowShelf(80.0, 5.0),
+                Peak(500.0, -2.0, 1.0),
+                HighShelf(10000.0, 3.0),
+            ],
+            Self::Vocal => &[
+                LowShelf(100.0, -3.0),
+                Peak(2500.0, 4.0, 0.8),
+                HighShelf(10000.0, -1.0),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BassBoost {
+    #[default]
+    Off,
+    Low,
+    Medium,
+    High,
+}
+
+impl BassBoost {
+    fn band(&self) -> Option<Band> {
+        let gain = match self {
+            Self::Off => return None,
+            Self::Low => 4.0,
+            Self::Medium => 8.0,
+            Self::High => 12.0,
+        };
+
+        Some(Band::LowShelf(120.0, gain))
+    }
+}
+
+/// A single filter of an equalizer
+#[derive(Debug, Clone, Copy)]
+enum Band {
+    /// Frequency, gain (dB)
+    LowShelf(f32, f32),
+
+    /// Frequency, gain (dB)
+    HighShelf(f32, f32),
+
+    /// Frequency, gain (dB), Q
+    Peak(f32, f32, f32),
+}
+
+impl Band {
+    fn gain(&self) -> f32 {
+        match *self {
+            Self::LowShelf(_, gain) | Self::HighShelf(_, gain) | Self::Peak(_, gain, _) => gain,
+        }
+    }
+}
+
+/// A stereo biquad filter, see the Audio EQ Cookbook by Robert Bristow-Johnson
+#[derive(Debug, Clone)]
+struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+
+    state: [[f32; 2]; CHANNELS],
+}
+
+impl Biquad {
+    fn new(band: Band) -> Self {
+        let (frequency, gain, q) = match band {
+            Band::LowShelf(frequency, gain) | Band::HighShelf(frequency, gain) => {
+                (frequency, gain, std::f32::consts::FRAC_1_SQRT_2)
+            }
+            Band::Peak(frequency, gain, q) => (frequency, gain, q),
+        };
+
+        let a = 10f32.powf(gain / 40.0);
+        let w0 = TAU * frequency / SAMPLE_RATE;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * q);
+
+        let (b0, b1, b2, a0, a1, a2) = match band {
+            Band::Peak(..) => (
+                1.0 + alpha * a,
+                -2.0 * cos,
+                1.0 - alpha * a,
+                1.0 + alpha / a,
+                -2.0 * cos,
+                1.0 - alpha / a,
+            ),
+            Band::LowShelf(..) => {
+                let sqrt = 2.0 * a.sqrt() * alpha;
+
+                (
+                    a * ((a + 1.0) - (a - 1.0) * cos + sqrt),
+                    2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                    a * ((a + 1.0) - (a - 1.0) * cos - sqrt),
+                    (a + 1.0) + (a - 1.0) * cos + sqrt,
+                    -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                    (a + 1.0) + (a - 1.0) * cos - sqrt,
+                )
+            }
+            Band::HighShelf(..) => {
+                let sqrt = 2.0 * a.sqrt() * alpha;
+
+                (
+                    a * ((a + 1.0) + (a - 1.0) * cos + sqrt),
+                    -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                    a * ((a + 1.0) + (a - 1.0) * cos - sqrt),
+                    (a + 1.0) - (a - 1.0) * cos + sqrt,
+                    2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                    (a + 1.0) - (a - 1.0) * cos - sqrt,
+                )
+            }
+        };
+
+        Self {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+            state: [[0.0; 2]; CHANNELS],
+        }
+    }
+
+    /// Process a single sample using the transposed direct form II
+    fn process(&mut self, channel: usize, input: f32) -> f32 {
+        let [z1, z2] = &mut self.state[channel];
+        let output = self.b0 * input + *z1;
+
+        *z1 = self.b1 * input - self.a1 * output + *z2;
+        *z2 = self.b2 * input - self.a2 * output;
+
+        output
+    }
+}
+
+/// All filters of the current equalizer and bass boost settings
+#[derive(Debug, Clone)]
+struct FilterBank {
+    filters: Vec<Biquad>,
+
+    /// Gain applied before filtering, to leave some headroom for boosted frequencies
+    preamp: f32,
+}
+
+impl FilterBank {
+    fn new(effects: &Effects) -> Self {
+        let bands: Vec<_> = effects
+            .equalizer
+            .bands()
+            .iter()
+            .copied()
+            .chain(effects.bass_boost.band())
+            .collect();
+
+        let boost = bands.iter().map(Band::gain).fold(0.0, f32::max);
+
+        Self {
+            filters: bands.into_iter().map(Biquad::new).collect(),
+            preamp: 10f32.powf(-boost / 2.0 / 20.0),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+
+    fn process(&mut self, frame: [f32; CHANNELS]) -> [f32; CHANNELS] {
+        let mut output = frame;
+
+        for (channel, sample) in output.iter_mut().enumerate() {
+            *sample *= self.preamp;
+
+            for filter in &mut self.filters {
+                *sample = filter.process(channel, *sample);
+            }
+        }
+
+        output
+    }
+}
+
+/// A value that moves towards its target in small steps, to avoid audible jumps
+#[derive(Debug, Clone, Copy)]
+struct Ramp {
+    value: f32,
+    target: f32,
+    step: f32,
+}
+
+impl Ramp {
+    fn new(value: f32) -> Self {
+        Self {
+            value,
+            target: value,
+            step: 0.0,
+        }
+    }
+
+    fn set(&mut self, target: f32) {
+        self.target = target;
+        self.step = (target - self.value).abs() / TRANSITION as f32;
+    }
+
+    fn is_settled(&self) -> bool {
+        self.value == self.target
+    }
+
+    fn next(&mut self) -> f32 {
+        if self.value < self.target {
+            self.value = (self.value + self.step).min(self.target);
+        } else if self.value > self.target {
+            self.value = (self.value - self.step).max(self.target);
+        }
+
+        self.value
+    }
+}
+
+/// Create a pair of handles for changing the effects of an [`EffectsChain`] while it is processing audio
+pub fn channel() -> (watch::Sender<Effects>, EffectsChain) {
+    let (tx, rx) = watch::channel(Effects::default());
+
+    (tx, EffectsChain::new(rx))
+}
+
+/// Applies [`Effects`] to interleaved stereo audio.
+///
+/// Effects can be changed at any time, in which case the chain gradually transitions to the new settings.
+pub struct EffectsChain {
+    effects: watch::Receiver<Effects>,
+
+    filters: FilterBank,
+
+    /// The previous filters, which are faded out after the settings have changed
+    previous: Option<FilterBank>,
+    fade: usize,
+
+    mono: Ramp,
+    rotation: Ramp,
+    phase: f32,
+
+    rate: Ramp,
+    position: f64,
+    last: [f32; CHANNELS],
+
+    output: Vec<f32>,
+}
+
+impl EffectsChain {
+    fn new(effects: watch::Receiver<Effects>) -> Self {
+        Self {
+            effects,
+            filters: FilterBank::new(&Effects::default()),
+            previous: None,
+            fade: 0,
+            mono: Ramp::new(0.0),
+            rotation: Ramp::new(0.0),
+            phase: 0.0,
+            rate: Ramp::new(1.0),
+            position: 0.0,
+            last: [0.0; CHANNELS],
+            output: Vec::new(),
+        }
+    }
+
+    fn update(&mut self) {
+        let effects = *self.effects.borrow_and_update();
+
+        let filters = FilterBank::new(&effects);
+        self.previous = Some(std::mem::replace(&mut self.filters, filters));
+        self.fade = TRANSITION;
+
+        self.mono.set(if effects.mono { 1.0 } else { 0.0 });
+        self.rotation.set(if effects.rotate { 1.0 } else { 0.0 });
+        self.rate.set(if effects.nightcore {
+            NIGHTCORE_RATE
+        } else {
+            1.0
+        });
+    }
+
+    fn is_idle(&self) -> bool {
+        self.filters.is_empty()
+            && self.previous.is_none()
+            && self.mono.value == 0.0
+            && self.rotation.value == 0.0
+            && self.rate.value == 1.0
+            && self.mono.is_settled()
+            && self.rotation.is_settled()
+            && self.rate.is_settled()
+    }
+
+    /// Apply the effects to a buffer of interleaved stereo samples.
+    ///
+    /// The amount of samples in the buffer might change if the playback rate is altered.
+    pub fn process(&mut self, samples: &mut Vec<f32>) {
+        if self.effects.has_changed().unwrap_or(false) {
+            self.update();
+        }
+
+        let Some(last) = samples.chunks_exact(CHANNELS).last() else {
+            return;
+        };
+
+        if self.is_idle() {
+            self.position = 0.0;
+            self.last = [last[0], last[1]];
+
+            return;
+        }
+
+        for frame in samples.chunks_exact_mut(CHANNELS) {
+            let output = self.process_frame([frame[0], frame[1]]);
+            frame.copy_from_slice(&output);
+        }
+
+        self.resample(samples);
+    }
+
+    fn process_frame(&mut self, frame: [f32; CHANNELS]) -> [f32; CHANNELS] {
+        let [mut left, mut right] = if self.filters.is_empty() && self.previous.is_none() {
+            frame
+        } else {
+            let current = self.filters.process(frame);
+
+            match &mut self.previous {
+                Some(previous) => {
+                    let old = previous.process(frame);
+                    let t = 1.0 - self.fade as f32 / TRANSITION as f32;
+
+                    self.fade = self.fade.saturating_sub(1);
+                    if self.fade == 0 {
+                        self.previous = None;
+                    }
+
+                    [
+                        old[0] + (current[0] - old[0]) * t,
+                        old[1] + (current[1] - old[1]) * t,
+                    ]
+                }
+                None => current,
+            }
+        };
+
+        let mono = self.mono.next();
+        if mono > 0.0 {
+            let mid = (left + right) / 2.0;
+
+            left += (mid - left) * mono;
+            right += (mid - right) * mono;
+        }
+
+        let rotation = self.rotation.next();
+        if rotation > 0.0 {
+            let pan = self.phase.sin() * ROTATION_WIDTH * rotation;
+
+            // Equal power panning, scaled so the center is at unity gain and the loudness stays the same
+            let angle = (1.0 + pan) * FRAC_PI_4;
+
+            left *= angle.cos() * SQRT_2;
+            right *= angle.sin() * SQRT_2;
+
+            self.phase = (self.phase + TAU / (ROTATION_PERIOD * SAMPLE_RATE)) % TAU;
+        } else {
+            self.phase = 0.0;
+        }
+
+        [left, right]
+    }
+
+    /// Change the playback rate by linearly interpolating between samples
+    fn resample(&mut self, samples: &mut Vec<f32>) {
+        let frames = samples.len() / CHANNELS;
+
+        if self.rate.is_settled() && self.rate.value == 1.0 {
+            self.position = 0.0;
+            self.last = [samples[samples.len() - 2], samples[samples.len() - 1]];
+
+            return;
+        }
+
+        let frame = |index: isize| -> [f32; CHANNELS] {
+            if index < 0 {
+                self.last
+            } else {
+                let index = index as usize * CHANNELS;
+                [samples[index], samples[index + 1]]
+            }
+        };
+
+        self.output.clear();
+
+        // Position -1 refers to the last frame of the previous buffer
+        while self.position < (frames - 1) as f64 {
+            let index = self.position.floor();
+            let fraction = (self.position - index) as f32;
+
+            let a = frame(index as isize);
+            let b = frame(index as isize + 1);
+
+            self.output.push(a[0] + (b[0] - a[0]) * fraction);
+            self.output.push(a[1] + (b[1] - a[1]) * fraction);
+
+            self.position += self.rate.next() as f64;
+        }
+
+        self.position -= frames as f64;
+        self.last = frame(frames as isize - 1);
+
+        std::mem::swap(samples, &mut self.output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRESETS: [Equalizer; 7] = [
+        Equalizer::Flat,
+        Equalizer::Pop,
+        Equalizer::Rock,
+        Equalizer::Jazz,
+        Equalizer::Classical,
+        Equalizer::Electronic,
+        Equalizer::Vocal,
+    ];
+
+    /// Interleaved stereo sine with the same signal on both channels
+    fn sine(frequency: f32, frames: usize, offset: usize) -> Vec<f32> {
+        (offset..offset + frames)
+            .flat_map(|frame| {
+                let sample = (TAU * frequency * frame as f32 / SAMPLE_RATE).sin() * 0.5;
+                [sample; CHANNELS]
+            })
+            .collect()
+    }
+
+    fn rms(samples: impl Iterator<Item = f32>) -> f32 {
+        let (sum, count) = samples.fold((0.0, 0), |(sum, count), sample| {
+            (sum + sample * sample, count + 1)
+        });
+
+        (sum / count as f32).sqrt()
+    }
+
+    /// Measure the gain (in dB) of a filter at a frequency, after it had some time to settle
+    fn response(band: Band, frequency: f32) -> f32 {
+        let mut filter = Biquad::new(band);
+        let input: Vec<f32> = sine(frequency, SAMPLE_RATE as usize, 0)
+            .into_iter()
+            .step_by(CHANNELS)
+            .collect();
+        let output: Vec<f32> = input
+            .iter()
+            .map(|&sample| filter.process(0, sample))
+            .collect();
+
+        let settled = input.len() / 4;
+        let gain = rms(output[settled..].iter().copied()) / rms(input[settled..].iter().copied());
+
+        20.0 * gain.log10()
+    }
+
+    fn chain(effects: Effects) -> EffectsChain {
+        let (tx, mut chain) = channel();
+        tx.send(effects).unwrap();
+
+        // Let every transition finish
+        chain.process(&mut sine(440.0, SAMPLE_RATE as usize, 0));
+
+        chain
+    }
+
+    #[test]
+    fn equalizer_gain_at_center_frequency() {
+        assert!(Equalizer::Flat.bands().is_empty());
+
+        for preset in PRESETS {
+            for &band in preset.bands() {
+                let (frequency, expected) = match band {
+                    Band::Peak(frequency, gain, _) => (frequency, gain),
+
+                    // Shelves are halfway to their gain at the corner frequency
+                    Band::LowShelf(frequency, gain) | Band::HighShelf(frequency, gain) => {
+                        (frequency, gain / 2.0)
+                    }
+                };
+
+                let gain = response(band, frequency);
+
+                assert!(
+                    (gain - expected).abs() < 0.1,
+                    "{preset:?} {band:?} has a gain of {gain} dB instead of {expected} dB"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn bass_boost_is_a_low_shelf() {
+        assert!(BassBoost::Off.band().is_none());
+
+        let mut previous = 0.0;
+
+        for (boost, expected) in [
+            (BassBoost::Low, 4.0),
+            (BassBoost::Medium, 8.0),
+            (BassBoost::High, 12.0),
+        ] {
+            let band = boost.band().unwrap();
+
+            let low = response(band, 30.0);
+            let corner = response(band, 120.0);
+            let high = response(band, 5000.0);
+
+            assert!(
+                low > expected - 1.0 && low <= expected + 0.1,
+                "{boost:?}: {low} dB"
+            );
+            assert!(
+                (corner - expected / 2.0).abs() < 0.1,
+                "{boost:?}: {corner} dB"
+            );
+            assert!(high.abs() < 0.1, "{boost:?}: {high} dB");
+            assert!(low > previous);
+
+            previous = low;
+        }
+    }
+
+    #[test]
+    fn nightcore_speeds_up() {
+        let mut chain = chain(Effects {
+            nightcore: true,
+            ..Default::default()
+        });
+
+        let frames = 4410;
+        let mut output = 0;
+
+        for buffer in 0..10 {
+            let mut samples = sine(440.0, frames, buffer * frames);
+            chain.process(&mut samples);
+
+            output += samples.len() / CHANNELS;
+        }
+
+        let expected = (10 * frames) as f32 / NIGHTCORE_RATE;
+
+        assert!(
+            (output as f32 - expected).abs() <= 10.0,
+            "{output} frames instead of {expected}"
+        );
+    }
+
+    #[test]
+    fn mono_is_equal_on_both_sides() {
+        let mut chain = chain(Effects {
+            mono: true,
+            ..Default::default()
+        });
+
+        // Only the left channel has a signal
+        let mut samples: Vec<f32> = sine(440.0, 4410, 0)
+            .chunks_exact(CHANNELS)
+            .flat_map(|frame| [frame[0], 0.0])
+            .collect();
+
+        chain.process(&mut samples);
+
+        for frame in samples.chunks_exact(CHANNELS) {
+            assert!((frame[0] - frame[1]).abs() < 1e-6);
+        }
+
+        assert!(rms(samples.iter().copied()) > 0.1);
+    }
+
+    #[test]
+    fn rotation_preserves_energy() {
+        let mut chain = chain(Effects {
+            rotate: true,
+            ..Default::default()
+        });
+
+        // A full rotation, so every pan position is checked
+        let frames = (ROTATION_PERIOD * SAMPLE_RATE) as usize;
+        let input = sine(440.0, frames, 0);
+
+        let mut output = input.clone();
+        chain.process(&mut output);
+
+        let mut panned = false;
+
+        for (input, output) in input
+            .chunks_exact(CHANNELS)
+            .zip(output.chunks_exact(CHANNELS))
+        {
+            let before = input[0] * input[0] + input[1] * input[1];
+            let after = output[0] * output[0] + output[1] * output[1];
+
+            assert!((before - after).abs() < 1e-5, "{before} became {after}");
+
+            panned |= (output[0] - output[1]).abs() > 0.1;
+        }
+
+        assert!(panned, "the audio was not rotated");
+    }
+
+    #[test]
+    fn switching_presets_is_continuous() {
+        let (tx, mut chain) = channel();
+        tx.send(Effects {
+            equalizer: Equalizer::Pop,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let frames = 4410;
+        let mut output = vec![];
+
+        for buffer in 0..20 {
+            if buffer == 10 {
+                tx.send(Effects {
+                    equalizer: Equalizer::Rock,
+                    ..Default::default()
+                })
+                .unwrap();
+            }
+
+            let mut samples = sine(440.0, frames, buffer * frames);
+            chain.process(&mut samples);
+
+            output.extend(samples.into_iter().step_by(CHANNELS));
+        }
+
+        let steps: Vec<f32> = output
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).abs())
+            .collect();
+
+        // The largest change between two samples before the switch, when the filters have settled
+        let steady = steps[frames * 5..frames * 10 - 1]
+            .iter()
+            .copied()
+            .fold(0.0, f32::max);
+
+        let switch = frames * 10;
+        let transition = steps[switch - 2..switch + TRANSITION + frames]
+            .iter()
+            .copied()
+            .fold(0.0, f32::max);
+
+        assert!(
+            transition < steady * 1.5,
+            "a step of {transition} while switching, compared to {steady} before"
+        );
+    }
+}
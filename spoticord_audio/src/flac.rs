@@ -0,0 +1,511 @@
+//! A small FLAC encoder for recordings.
+//!
+//! Every channel is encoded on its own using the fixed linear predictors of FLAC, with a single Rice parameter per
+//! subframe. This compresses worse than the reference encoder, but is fast and simple enough to keep up with the
+//! audio of a player on a single thread.
+
+use std::io::{Seek, SeekFrom, Write};
+
+/// The amount of frames in every FLAC frame, except for the last one
+const BLOCK_SIZE: usize = 4096;
+
+const BITS_PER_SAMPLE: u32 = 16;
+
+/// Rice parameter 15 is used to escape to unencoded residuals, which are never used
+const MAX_RICE_PARAMETER: u32 = 14;
+
+/// The metadata stored in the Vorbis comment block of a file, as field name and value
+pub type Comments<'a> = &'a [(&'a str, &'a str)];
+
+/// Encodes interleaved stereo audio to a FLAC stream, of which the total length is filled in once it is finished
+pub struct FlacEncoder<W: Write + Seek> {
+    writer: W,
+    sample_rate: u32,
+    channels: usize,
+
+    /// Samples that do not fill an entire block yet
+    pending: Vec<i32>,
+    frame_number: u64,
+    total_frames: u64,
+}
+
+impl<W: Write + Seek> FlacEncoder<W> {
+    pub fn new(
+        mut writer: W,
+        sample_rate: u32,
+        channels: usize,
+        comments: Comments,
+    ) -> std::io::Result<Self> {
+        writer.write_all(b"fLaC")?;
+
+        let mut encoder = Self {
+            writer,
+            sample_rate,
+            channels,
+            pending: Vec::with_capacity(BLOCK_SIZE * channels),
+            frame_number: 0,
+            total_frames: 0,
+        };
+
+        encoder.write_stream_info(false)?;
+        encoder.write_comments(comments)?;
+
+        Ok(encoder)
+    }
+
+    /// Encode interleaved samples, which are converted to 16 bits
+    pub fn write(&mut self, samples: &[f32]) -> std::io::Result<()> {
+        let block = BLOCK_SIZE * self.channels;
+
+        for &sample in samples {
+            self.pending
+                .push((sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i32);
+
+            if self.pending.len() == block {
+                self.write_frame()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Encode the remaining audio, and fill in the length of the stream
+    pub fn finish(mut self) -> std::io::Result<W> {
+        if self.pending.len() >= self.channels {
+            self.pending
+                .truncate(self.pending.len() / self.channels * self.channels);
+            self.write_frame()?;
+        }
+
+        self.writer.seek(SeekFrom::Start(4))?;
+        self.write_stream_info(true)?;
+        self.writer.seek(SeekFrom::End(0))?;
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+
+    fn write_stream_info(&mut self, complete: bool) -> std::io::Result<()> {
+        let total_frames = if complete { self.total_frames } else { 0 };
+        let mut block = BitWriter::default();
+
+        block.write(BLOCK_SIZE as u64, 16);
+        block.write(BLOCK_SIZE as u64, 16);
+
+        // Unknown minimum and maximum frame sizes
+        block.write(0, 24);
+        block.write(0, 24);
+
+        block.write(self.sample_rate as u64, 20);
+        block.write(self.channels as u64 - 1, 3);
+        block.write(BITS_PER_SAMPLE as u64 - 1, 5);
+        block.write(total_frames, 36);
+
+        // An empty MD5 signature means that it has not been calculated
+        block.write(0, 64);
+        block.write(0, 64);
+
+        self.write_metadata_block(0, false, &block.into_bytes())
+    }
+
+    fn write_comments(&mut self, comments: Comments) -> std::io::Result<()> {
+        let vendor = format!("Spoticord {}", env!("CARGO_PKG_VERSION"));
+        let comments: Vec<_> = comments
+            .iter()
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect();
+
+        // Unlike everything else in FLAC, the Vorbis comment lengths are little endian
+        let mut block = vec![];
+        block.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+        block.extend_from_slice(vendor.as_bytes());
+        block.extend_from_slice(&(comments.len() as u32).to_le_bytes());
+
+        for comment in comments {
+            block.extend_from_slice(&(comment.len() as u32).to_le_bytes());
+            block.extend_from_slice(comment.as_bytes());
+        }
+
+        self.write_metadata_block(4, true, &block)
+    }
+
+    fn write_metadata_block(&mut self, kind: u8, last: bool, data: &[u8]) -> std::io::Result<()> {
+        let header = ((last as u32) << 31) | ((kind as u32) << 24) | data.len() as u32;
+
+        self.writer.write_all(&header.to_be_bytes())?;
+        self.writer.write_all(data)
+    }
+
+    fn write_frame(&mut self) -> std::io::Result<()> {
+        let frames = self.pending.len() / self.channels;
+        let mut frame = BitWriter::default();
+
+        // Sync code, with a fixed block size
+        frame.write(0b1111_1111_1111_1000, 16);
+
+        // The block size is stored at the end of the header, unless it is the common size
+        let block_size = if frames == BLOCK_SIZE { 0b1100 } else { 0b0111 };
+        frame.write(block_size, 4);
+        frame.write(sample_rate_code(self.sample_rate), 4);
+
+        // Independent channels, 16 bits per sample
+        frame.write(self.channels as u64 - 1, 4);
+        frame.write(0b100, 3);
+        frame.write(0, 1);
+
+        frame.write_utf8(self.frame_number);
+
+        if frames != BLOCK_SIZE {
+            frame.write(frames as u64 - 1, 16);
+        }
+
+        if sample_rate_code(self.sample_rate) == 0b1100 {
+            frame.write(self.sample_rate as u64 / 1000, 8);
+        }
+
+        let crc = crc8(frame.bytes());
+        frame.write(crc as u64, 8);
+
+        for channel in 0..self.channels {
+            let samples: Vec<i32> = self
+                .pending
+                .iter()
+                .skip(channel)
+                .step_by(self.channels)
+                .copied()
+                .collect();
+
+            write_subframe(&mut frame, &samples);
+        }
+
+        frame.align();
+
+        let crc = crc16(frame.bytes());
+        frame.write(crc as u64, 16);
+
+        self.writer.write_all(&frame.into_bytes())?;
+        self.pending.clear();
+        self.frame_number += 1;
+        self.total_frames += frames as u64;
+
+        Ok(())
+    }
+}
+
+fn sample_rate_code(sample_rate: u32) -> u64 {
+    match sample_rate {
+        44100 => 0b1001,
+        48000 => 0b1010,
+        _ => 0b1100,
+    }
+}
+
+fn write_subframe(frame: &mut BitWriter, samples: &[i32]) {
+    if samples.iter().all(|&sample| sample == samples[0]) {
+        frame.write(0b0000_0000, 8);
+        frame.write_signed(samples[0] as i64, BITS_PER_SAMPLE);
+
+        return;
+    }
+
+    // Pick the predictor which leaves the smallest residual
+    let (order, residual) = (0..=4.min(samples.len() - 1))
+        .map(|order| (order, fixed_residual(samples, order)))
+        .min_by_key(|(_, residual)| {
+            residual
+                .iter()
+                .map(|value| value.unsigned_abs())
+                .sum::<u64>()
+        })
+        .expect("there is always a predictor");
+
+    let (parameter, size) = rice_parameter(&residual);
+    let fixed_size = order as u64 * BITS_PER_SAMPLE as u64 + 10 + size;
+
+    if fixed_size >= samples.len() as u64 * BITS_PER_SAMPLE as u64 {
+        frame.write(0b0000_0010, 8);
+
+        for &sample in samples {
+            frame.write_signed(sample as i64, BITS_PER_SAMPLE);
+        }
+
+        return;
+    }
+
+    frame.write(0b0001_0000 | (order as u64) << 1, 8);
+
+    for &sample in &samples[..order] {
+        frame.write_signed(sample as i64, BITS_PER_SAMPLE);
+    }
+
+    // Rice coding with 4-bit parameters, and a single partition
+    frame.write(0b00, 2);
+    frame.write(0, 4);
+    frame.write(parameter as u64, 4);
+
+    for value in residual {
+        let value = zigzag(value);
+
+        frame.write_unary(value >> parameter);
+        frame.write(value, parameter);
+    }
+}
+
+/// The difference between the samples and the prediction of a fixed predictor of the given order
+fn fixed_residual(samples: &[i32], order: usize) -> Vec<i64> {
+    let s = |index: usize| samples[index] as i64;
+
+    (order..samples.len())
+        .map(|i| match order {
+            0 => s(i),
+            1 => s(i) - s(i - 1),
+            2 => s(i) - 2 * s(i - 1) + s(i - 2),
+            3 => s(i) - 3 * s(i - 1) + 3 * s(i - 2) - s(i - 3),
+            _ => s(i) - 4 * s(i - 1) + 6 * s(i - 2) - 4 * s(i - 3) + s(i - 4),
+        })
+        .collect()
+}
+
+/// Find the Rice parameter that results in the smallest encoding, and its size in bits
+fn rice_parameter(residual: &[i64]) -> (u32, u64) {
+    let values: Vec<u64> = residual.iter().map(|&value| zigzag(value)).collect();
+
+    (0..=MAX_RICE_PARAMETER)
+        .map(|parameter| {
+            let size = values
+                .iter()
+                .map(|value| (value >> parameter) + 1 + parameter as u64)
+                .sum();
+
+            (parameter, size)
+        })
+        .min_by_key(|&(_, size)| size)
+        .expect("there is always a parameter")
+}
+
+/// Map signed values to unsigned ones, so that small negative values stay small
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// CRC-8 with polynomial x^8 + x^2 + x + 1, used for frame headers
+fn crc8(data: &[u8]) -> u8 {
+    data.iter().fold(0, |mut crc, &byte| {
+        crc ^= byte;
+
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+
+        crc
+    })
+}
+
+/// CRC-16 with polynomial x^16 + x^15 + x^2 + 1, used for entire frames
+fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0, |mut crc, &byte| {
+        crc ^= (byte as u16) << 8;
+
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x8005
+            } else {
+                crc << 1
+            };
+        }
+
+        crc
+    })
+}
+
+/// Writes values of any amount of bits, most significant bit first
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u64,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u64, bits: u32) {
+        for bit in (0..bits).rev() {
+            self.buffer = (self.buffer << 1) | ((value >> bit) & 1);
+            self.bits += 1;
+
+            if self.bits == 8 {
+                self.bytes.push(self.buffer as u8);
+                self.buffer = 0;
+                self.bits = 0;
+            }
+        }
+    }
+
+    fn write_signed(&mut self, value: i64, bits: u32) {
+        self.write(value as u64 & ((1 << bits) - 1), bits);
+    }
+
+    /// Write `value` zeroes, followed by a one
+    fn write_unary(&mut self, value: u64) {
+        for _ in 0..value / 32 {
+            self.write(0, 32);
+        }
+
+        self.write(1, value as u32 % 32 + 1);
+    }
+
+    /// Write a number using the variable length encoding of UTF-8, extended up to 36 bits
+    fn write_utf8(&mut self, value: u64) {
+        if value < 0x80 {
+            self.write(value, 8);
+            return;
+        }
+
+        let bits = 64 - value.leading_zeros();
+
+        // Every continuation byte holds 6 bits, and the first byte loses a bit for every byte that follows
+        let continuation = (1..=6)
+            .find(|&bytes| bits <= 6 - bytes + 6 * bytes)
+            .expect("frame numbers never exceed 36 bits");
+
+        let prefix = !(0xffu64 >> (continuation + 1)) & 0xff;
+        self.write(prefix | (value >> (6 * continuation)), 8);
+
+        for byte in (0..continuation).rev() {
+            self.write(0x80 | ((value >> (6 * byte)) & 0x3f), 8);
+        }
+    }
+
+    /// Pad the last byte with zeroes
+    fn align(&mut self) {
+        if self.bits > 0 {
+            self.write(0, 8 - self.bits);
+        }
+    }
+
+    /// The complete bytes that have been written so far
+    fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    fn into_bytes(mut self) -> Vec<u8> {
+        self.align();
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{f32::consts::TAU, io::Cursor};
+
+    use claxon::FlacReader;
+
+    use super::*;
+
+    fn encode(samples: &[f32], comments: Comments) -> Vec<u8> {
+        let mut encoder = FlacEncoder::new(Cursor::new(vec![]), 44100, 2, comments).unwrap();
+        encoder.write(samples).unwrap();
+
+        encoder.finish().unwrap().into_inner()
+    }
+
+    /// The samples that the encoder should have stored, after converting them to 16 bits
+    fn quantize(samples: &[f32]) -> Vec<i32> {
+        samples
+            .iter()
+            .map(|sample| (sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i32)
+            .collect()
+    }
+
+    fn decode(data: Vec<u8>) -> (FlacReader<Cursor<Vec<u8>>>, Vec<i32>) {
+        let mut reader = FlacReader::new(Cursor::new(data)).expect("invalid FLAC stream");
+        let samples = reader
+            .samples()
+            .collect::<Result<_, _>>()
+            .expect("invalid FLAC frame");
+
+        (reader, samples)
+    }
+
+    #[test]
+    fn round_trip() {
+        // Two full blocks and a partial one, with a different signal on every channel
+        let samples: Vec<f32> = (0..10_000)
+            .flat_map(|frame| {
+                let t = frame as f32 / 44100.0;
+                [
+                    (TAU * 440.0 * t).sin() * 0.5,
+                    (TAU * 1000.0 * t).cos() * 0.25,
+                ]
+            })
+            .collect();
+
+        let (reader, decoded) = decode(encode(&samples, &[]));
+        let info = reader.streaminfo();
+
+        assert_eq!(info.sample_rate, 44100);
+        assert_eq!(info.channels, 2);
+        assert_eq!(info.bits_per_sample, 16);
+        assert_eq!(info.samples, Some(10_000));
+        assert_eq!(decoded, quantize(&samples));
+    }
+
+    #[test]
+    fn silence_and_noise() {
+        let mut state = 1u32;
+        let mut noise = || {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (state >> 8) as f32 / (1 << 24) as f32 * 2.0 - 1.0
+        };
+
+        // Silence is encoded as a constant, and loud noise has to be stored verbatim
+        let mut samples = vec![0.0; BLOCK_SIZE * 2];
+        samples.extend((0..BLOCK_SIZE * 2).map(|_| noise()));
+
+        let (_, decoded) = decode(encode(&samples, &[]));
+
+        assert_eq!(decoded, quantize(&samples));
+    }
+
+    #[test]
+    fn long_streams() {
+        // More than 127 frames, so the frame numbers take up more than a single byte
+        let frames = BLOCK_SIZE * 130 + 1;
+        let samples: Vec<f32> = (0..frames * 2)
+            .map(|sample| (sample as f32 * 0.001).sin() * 0.1)
+            .collect();
+
+        let (reader, decoded) = decode(encode(&samples, &[]));
+
+        assert_eq!(reader.streaminfo().samples, Some(frames as u64));
+        assert_eq!(decoded, quantize(&samples));
+    }
+
+    #[test]
+    fn comments() {
+        let (reader, _) = decode(encode(
+            &[0.0; 2],
+            &[
+                ("TITLE", "Never Gonna Give You Up"),
+                ("ALBUM", ""),
+                ("ARTIST", "Rick Astley"),
+            ],
+        ));
+
+        assert_eq!(
+            reader.get_tag("TITLE").collect::<Vec<_>>(),
+            ["Never Gonna Give You Up"]
+        );
+        assert_eq!(
+            reader.get_tag("ARTIST").collect::<Vec<_>>(),
+            ["Rick Astley"]
+        );
+
+        // Empty fields are left out
+        assert_eq!(reader.get_tag("ALBUM").count(), 0);
+    }
+}
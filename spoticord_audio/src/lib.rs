@@ -1,2 +1,10 @@
+pub mod crossfade;
+pub mod effects;
+mod flac;
+pub mod limiter;
+pub mod loudness;
+pub mod opus;
+pub mod recorder;
 pub mod sink;
+pub mod speed;
 pub mod stream;
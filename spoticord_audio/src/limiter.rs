@@ -0,0 +1,129 @@
+use std::collections::VecDeque;
+
+/// The sample rate of the audio that librespot produces
+const SAMPLE_RATE: f32 = 44100.0;
+
+const CHANNELS: usize = 2;
+
+/// The highest peak level the limiter lets through (-1 dBFS), which leaves some headroom for the Opus encoder
+const THRESHOLD: f32 = 0.891_250_9;
+
+/// How far ahead the limiter looks for peaks (5ms), which is also the latency it adds
+const LOOKAHEAD: usize = 220;
+
+/// The time constant (in seconds) at which the gain recovers after a peak
+const RELEASE: f32 = 0.1;
+
+/// A lookahead peak limiter that keeps the audio below [`THRESHOLD`].
+///
+/// The audio is delayed by [`LOOKAHEAD`] frames, which allows the gain to be lowered gradually before a peak arrives
+/// instead of abruptly clipping it. Both channels share the same gain, so the stereo image is preserved.
+pub struct Limiter {
+    /// Frames that have been analyzed, but not yet released
+    delay: VecDeque<[f32; CHANNELS]>,
+
+    /// The required gains within the lookahead window, kept in increasing order so the front is the minimum
+    minimum: VecDeque<(u64, f32)>,
+
+    /// The minimum gains of the last [`LOOKAHEAD`] frames, which are averaged to smoothly ramp towards a peak
+    window: VecDeque<f32>,
+    window_sum: f64,
+
+    frame: u64,
+    gain: f32,
+    release: f32,
+
+    /// The lowest gain since the gain reduction was last taken
+    lowest: f32,
+}
+
+impl Limiter {
+    pub fn new() -> Self {
+        Self {
+            delay: VecDeque::from(vec![[0.0; CHANNELS]; LOOKAHEAD - 1]),
+            minimum: VecDeque::with_capacity(LOOKAHEAD + 1),
+            window: VecDeque::from(vec![1.0; LOOKAHEAD]),
+            window_sum: LOOKAHEAD as f64,
+            frame: 0,
+            gain: 1.0,
+            release: 1.0 - (-1.0 / (RELEASE * SAMPLE_RATE)).exp(),
+            lowest: 1.0,
+        }
+    }
+
+    /// Limit a buffer of interleaved stereo samples
+    pub fn process(&mut self, samples: &mut [f32]) {
+        for frame in samples.chunks_exact_mut(CHANNELS) {
+            let peak = frame
+                .iter()
+                .fold(0.0, |peak: f32, sample| peak.max(sample.abs()));
+            let required = if peak > THRESHOLD {
+                THRESHOLD / peak
+            } else {
+                1.0
+            };
+
+            // Sliding minimum of the required gain over the lookahead window
+            while self
+                .minimum
+                .back()
+                .is_some_and(|(_, gain)| *gain >= required)
+            {
+                self.minimum.pop_back();
+            }
+
+            self.minimum.push_back((self.frame, required));
+
+            while self
+                .minimum
+                .front()
+                .is_some_and(|(frame, _)| frame + LOOKAHEAD as u64 <= self.frame)
+            {
+                self.minimum.pop_front();
+            }
+
+            let minimum = self.minimum.front().map_or(1.0, |(_, gain)| *gain);
+
+            // Averaging the minimum over the same window turns the steps into ramps, while never exceeding the
+            // gain that is required for the frame that is about to leave the delay line
+            self.window_sum += minimum as f64 - self.window.pop_front().unwrap_or(1.0) as f64;
+            self.window.push_back(minimum);
+
+            let target = ((self.window_sum / LOOKAHEAD as f64) as f32).min(1.0);
+
+            self.gain = if target < self.gain {
+                target
+            } else {
+                self.gain + (target - self.gain) * self.release
+            };
+
+            self.lowest = self.lowest.min(self.gain);
+            self.frame += 1;
+
+            self.delay.push_back([frame[0], frame[1]]);
+            let delayed = self.delay.pop_front().unwrap_or_default();
+
+            for (sample, delayed) in frame.iter_mut().zip(delayed) {
+                *sample = delayed * self.gain;
+            }
+        }
+    }
+
+    /// Discard the audio in the delay line, used when playback stops
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// The largest gain reduction (in dB) that was applied since the last call to this function
+    pub fn take_gain_reduction(&mut self) -> f32 {
+        let lowest = std::mem::replace(&mut self.lowest, self.gain);
+
+        20.0 * (1.0 / lowest).log10()
+    }
+}
+
+impl Default for Limiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
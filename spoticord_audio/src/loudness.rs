@@ -0,0 +1,246 @@
+use std::{
+    collections::VecDeque,
+    f64::consts::PI,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+};
+
+/// The sample rate of the audio that librespot produces
+const SAMPLE_RATE: f64 = 44100.0;
+
+const CHANNELS: usize = 2;
+
+/// The length of a single measurement block (100ms)
+const BLOCK_FRAMES: usize = 4410;
+
+/// The amount of blocks that make up the short-term loudness window (3s)
+const SHORT_TERM_BLOCKS: usize = 30;
+
+/// Loudness levels of the audio that is sent to Discord
+#[derive(Debug, Clone, Copy)]
+pub struct Loudness {
+    /// The EBU R128 short-term loudness (3 second window) in LUFS
+    pub short_term: f32,
+
+    /// The highest sample peak within the short-term window in dBFS
+    pub peak: f32,
+
+    /// The largest gain reduction applied by the limiter within the short-term window in dB
+    pub gain_reduction: f32,
+}
+
+/// Create a loudness meter, and a handle that can be used to read its measurements from another thread
+pub fn meter() -> (LoudnessMonitor, LoudnessMeter) {
+    let levels = Arc::new(Levels {
+        short_term: AtomicU32::new(f32::NEG_INFINITY.to_bits()),
+        peak: AtomicU32::new(f32::NEG_INFINITY.to_bits()),
+        gain_reduction: AtomicU32::new(0f32.to_bits()),
+    });
+
+    (
+        LoudnessMonitor {
+            levels: levels.clone(),
+        },
+        LoudnessMeter::new(levels),
+    )
+}
+
+struct Levels {
+    short_term: AtomicU32,
+    peak: AtomicU32,
+    gain_reduction: AtomicU32,
+}
+
+impl Levels {
+    fn store(&self, loudness: Loudness) {
+        self.short_term
+            .store(loudness.short_term.to_bits(), Ordering::Relaxed);
+        self.peak.store(loudness.peak.to_bits(), Ordering::Relaxed);
+        self.gain_reduction
+            .store(loudness.gain_reduction.to_bits(), Ordering::Relaxed);
+    }
+}
+
+/// Provides the most recent measurements of a [`LoudnessMeter`]
+#[derive(Clone)]
+pub struct LoudnessMonitor {
+    levels: Arc<Levels>,
+}
+
+impl LoudnessMonitor {
+    pub fn get(&self) -> Loudness {
+        Loudness {
+            short_term: f32::from_bits(self.levels.short_term.load(Ordering::Relaxed)),
+            peak: f32::from_bits(self.levels.peak.load(Ordering::Relaxed)),
+            gain_reduction: f32::from_bits(self.levels.gain_reduction.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// A second order IIR filter, used for the K-weighting curve of ITU-R BS.1770
+#[derive(Clone, Copy)]
+struct Filter {
+    b: [f64; 3],
+    a: [f64; 2],
+    state: [[f64; 2]; CHANNELS],
+}
+
+impl Filter {
+    /// The high shelf that models the acoustic effect of the head
+    fn shelf() -> Self {
+        let gain: f64 = 3.999843853973347;
+        let q = 0.7071752369554196;
+
+        let k = (PI * 1681.974450955533 / SAMPLE_RATE).tan();
+        let vh = 10f64.powf(gain / 20.0);
+        let vb = vh.powf(0.4996667741545416);
+        let a0 = 1.0 + k / q + k * k;
+
+        Self {
+            b: [
+                (vh + vb * k / q + k * k) / a0,
+                2.0 * (k * k - vh) / a0,
+                (vh - vb * k / q + k * k) / a0,
+            ],
+            a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+            state: [[0.0; 2]; CHANNELS],
+        }
+    }
+
+    /// The high pass that removes the lowest frequencies, which barely contribute to perceived loudness
+    fn high_pass() -> Self {
+        let q = 0.5003270373238773;
+
+        let k = (PI * 38.13547087602444 / SAMPLE_RATE).tan();
+        let a0 = 1.0 + k / q + k * k;
+
+        Self {
+            b: [1.0, -2.0, 1.0],
+            a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+            state: [[0.0; 2]; CHANNELS],
+        }
+    }
+
+    fn process(&mut self, channel: usize, input: f64) -> f64 {
+        let [s1, s2] = &mut self.state[channel];
+
+        // Transposed direct form II
+        let output = self.b[0] * input + *s1;
+        *s1 = self.b[1] * input - self.a[0] * output + *s2;
+        *s2 = self.b[2] * input - self.a[1] * output;
+
+        output
+    }
+
+    fn reset(&mut self) {
+        self.state = [[0.0; 2]; CHANNELS];
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct Block {
+    /// The sum of the squared K-weighted samples of all channels
+    energy: f64,
+    peak: f32,
+    gain_reduction: f32,
+}
+
+/// Measures the loudness of the audio in accordance with EBU R128.
+///
+/// Measurements are made in blocks of 100ms, the results are published to the [`LoudnessMonitor`] after every
+/// completed block.
+pub struct LoudnessMeter {
+    levels: Arc<Levels>,
+
+    shelf: Filter,
+    high_pass: Filter,
+
+    current: Block,
+    frames: usize,
+    blocks: VecDeque<Block>,
+}
+
+impl LoudnessMeter {
+    fn new(levels: Arc<Levels>) -> Self {
+        Self {
+            levels,
+            shelf: Filter::shelf(),
+            high_pass: Filter::high_pass(),
+            current: Block::default(),
+            frames: 0,
+            blocks: VecDeque::with_capacity(SHORT_TERM_BLOCKS + 1),
+        }
+    }
+
+    /// Measure a buffer of interleaved stereo samples
+    pub fn process(&mut self, samples: &[f32]) {
+        for frame in samples.chunks_exact(CHANNELS) {
+            for (channel, sample) in frame.iter().enumerate() {
+                let weighted = self
+                    .high_pass
+                    .process(channel, self.shelf.process(channel, *sample as f64));
+
+                self.current.energy += weighted * weighted;
+                self.current.peak = self.current.peak.max(sample.abs());
+            }
+
+            self.frames += 1;
+
+            if self.frames == BLOCK_FRAMES {
+                self.finish_block();
+            }
+        }
+    }
+
+    /// Record the gain reduction (in dB) that a limiter applied to the audio that was last measured
+    pub fn record_gain_reduction(&mut self, gain_reduction: f32) {
+        self.current.gain_reduction = self.current.gain_reduction.max(gain_reduction);
+    }
+
+    /// Discard all measurements, used when playback stops
+    pub fn reset(&mut self) {
+        self.shelf.reset();
+        self.high_pass.reset();
+        self.current = Block::default();
+        self.frames = 0;
+        self.blocks.clear();
+
+        self.levels.store(Loudness {
+            short_term: f32::NEG_INFINITY,
+            peak: f32::NEG_INFINITY,
+            gain_reduction: 0.0,
+        });
+    }
+
+    fn finish_block(&mut self) {
+        self.blocks.push_back(std::mem::take(&mut self.current));
+        self.frames = 0;
+
+        if self.blocks.len() > SHORT_TERM_BLOCKS {
+            self.blocks.pop_front();
+        }
+
+        let energy = self.blocks.iter().map(|block| block.energy).sum::<f64>();
+        let mean = energy / (self.blocks.len() * BLOCK_FRAMES) as f64;
+
+        let peak = self
+            .blocks
+            .iter()
+            .map(|block| block.peak)
+            .fold(0.0, f32::max);
+
+        let gain_reduction = self
+            .blocks
+            .iter()
+            .map(|block| block.gain_reduction)
+            .fold(0.0, f32::max);
+
+        self.levels.store(Loudness {
+            short_term: (-0.691 + 10.0 * mean.log10()) as f32,
+            peak: 20.0 * peak.log10(),
+            gain_reduction,
+        });
+    }
+}
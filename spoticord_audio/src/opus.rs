@@ -0,0 +1,206 @@
+use std::{
+    io::{Read, Seek},
+    sync::Mutex,
+};
+
+use audiopus::{coder::Encoder, Application, Bitrate, Channels, SampleRate};
+use rubato::{FftFixedOut, Resampler};
+use songbird::input::{
+    core::{io::MediaSource, probe::Hint},
+    AudioStream, Input, LiveInput,
+};
+
+use crate::stream::Stream;
+
+/// The sample rate of the audio that librespot writes into the stream
+const INPUT_SAMPLE_RATE: usize = 44100;
+
+/// The sample rate Discord expects Opus audio to be in
+const OUTPUT_SAMPLE_RATE: usize = 48000;
+
+const CHANNELS: usize = 2;
+
+/// The amount of samples (per channel) in a single 20ms Opus frame
+const FRAME_SAMPLES: usize = 960;
+
+/// The size (in bytes) of a single stereo f32 sample in the stream
+const SAMPLE_SIZE: usize = CHANNELS * std::mem::size_of::<f32>();
+
+/// The bitrate at which audio is encoded, matching the default of songbird's own encoder
+const BITRATE: i32 = 128_000;
+
+/// The maximum size of a single Opus packet
+const MAX_PACKET_SIZE: usize = 4000;
+
+/// An Opus packet that decodes to 20ms of silence
+const SILENT_FRAME: [u8; 3] = [0xf8, 0xff, 0xfe];
+
+/// The DCA1 metadata block that songbird uses to detect the format of the stream
+const DCA_METADATA: &str = concat!(
+    r#"{"dca":{"version":1,"tool":{"name":"spoticord","version":""#,
+    env!("CARGO_PKG_VERSION"),
+    r#""}},"opus":{"mode":"music","sample_rate":48000,"frame_size":960,"abr":128000,"vbr":true,"channels":2}}"#
+);
+
+/// Resamples and encodes the audio in a [`Stream`] to Opus, so songbird can pass the frames straight through to
+/// Discord instead of having to resample and encode the audio in its mixer.
+///
+/// The encoded frames are wrapped in the DCA format, which songbird natively understands.
+pub struct OpusStream {
+    stream: Stream,
+
+    // Neither of these are Sync, but are only used through `&mut self`
+    resampler: Mutex<FftFixedOut<f32>>,
+    encoder: Mutex<Encoder>,
+
+    pcm: Vec<u8>,
+    input: Vec<Vec<f32>>,
+    output: Vec<Vec<f32>>,
+    interleaved: Vec<f32>,
+    packet: Box<[u8; MAX_PACKET_SIZE]>,
+
+    /// DCA data that has not been handed to songbird yet
+    pending: Vec<u8>,
+    offset: usize,
+}
+
+impl OpusStream {
+    pub fn new(stream: Stream) -> std::io::Result<Self> {
+        let resampler = FftFixedOut::new(
+            INPUT_SAMPLE_RATE,
+            OUTPUT_SAMPLE_RATE,
+            FRAME_SAMPLES,
+            1,
+            CHANNELS,
+        )
+        .map_err(std::io::Error::other)?;
+
+        let mut encoder = Encoder::new(SampleRate::Hz48000, Channels::Stereo, Application::Audio)
+            .map_err(std::io::Error::other)?;
+        encoder
+            .set_bitrate(Bitrate::BitsPerSecond(BITRATE))
+            .map_err(std::io::Error::other)?;
+
+        let mut pending = Vec::with_capacity(8 + DCA_METADATA.len());
+        pending.extend_from_slice(b"DCA1");
+        pending.extend_from_slice(&(DCA_METADATA.len() as u32).to_le_bytes());
+        pending.extend_from_slice(DCA_METADATA.as_bytes());
+
+        Ok(Self {
+            stream,
+            input: vec![Vec::new(); CHANNELS],
+            output: resampler.output_buffer_allocate(true),
+            resampler: Mutex::new(resampler),
+            encoder: Mutex::new(encoder),
+            pcm: Vec::new(),
+            interleaved: vec![0.0; FRAME_SAMPLES * CHANNELS],
+            packet: Box::new([0; MAX_PACKET_SIZE]),
+            pending,
+            offset: 0,
+        })
+    }
+
+    /// Encode the next 20ms of audio into a DCA frame
+    fn next_frame(&mut self) -> std::io::Result<()> {
+        let resampler = self.resampler.get_mut().expect("Mutex was poisoned");
+
+        // The stream never blocks, it provides silence if it runs out of audio
+        self.pcm
+            .resize(resampler.input_frames_next() * SAMPLE_SIZE, 0);
+        self.stream.read_exact(&mut self.pcm)?;
+
+        // Skip encoding entirely when there is nothing to be heard
+        let packet = if self.pcm.iter().all(|byte| *byte == 0) {
+            resampler.reset();
+
+            &SILENT_FRAME[..]
+        } else {
+            for channel in &mut self.input {
+                channel.clear();
+            }
+
+            for sample in self.pcm.chunks_exact(SAMPLE_SIZE) {
+                for (channel, bytes) in self.input.iter_mut().zip(sample.chunks_exact(4)) {
+                    channel.push(f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+                }
+            }
+
+            let (_, frames) = resampler
+                .process_into_buffer(&self.input, &mut self.output, None)
+                .map_err(std::io::Error::other)?;
+
+            for (i, sample) in self.interleaved.chunks_exact_mut(CHANNELS).enumerate() {
+                for (channel, value) in sample.iter_mut().enumerate() {
+                    *value = if i < frames {
+                        self.output[channel][i]
+                    } else {
+                        0.0
+                    };
+                }
+            }
+
+            let size = self
+                .encoder
+                .get_mut()
+                .expect("Mutex was poisoned")
+                .encode_float(&self.interleaved, &mut self.packet[..])
+                .map_err(std::io::Error::other)?;
+
+            &self.packet[..size]
+        };
+
+        self.pending.clear();
+        self.pending
+            .extend_from_slice(&(packet.len() as u16).to_le_bytes());
+        self.pending.extend_from_slice(packet);
+        self.offset = 0;
+
+        Ok(())
+    }
+}
+
+impl Read for OpusStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.offset >= self.pending.len() {
+            self.next_frame()?;
+        }
+
+        let max_read = usize::min(buf.len(), self.pending.len() - self.offset);
+
+        buf[..max_read].copy_from_slice(&self.pending[self.offset..][..max_read]);
+        self.offset += max_read;
+
+        Ok(max_read)
+    }
+}
+
+impl Seek for OpusStream {
+    fn seek(&mut self, _: std::io::SeekFrom) -> std::io::Result<u64> {
+        Ok(0)
+    }
+}
+
+impl MediaSource for OpusStream {
+    fn byte_len(&self) -> Option<u64> {
+        None
+    }
+
+    fn is_seekable(&self) -> bool {
+        false
+    }
+}
+
+impl From<OpusStream> for Input {
+    fn from(value: OpusStream) -> Self {
+        let mut hint = Hint::new();
+        hint.with_extension("dca");
+
+        Input::Live(
+            LiveInput::Raw(AudioStream {
+                input: Box::new(value),
+                hint: Some(hint),
+            }),
+            None,
+        )
+    }
+}
@@ -0,0 +1,560 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::mpsc,
+};
+
+use log::error;
+use tokio::sync::watch;
+
+use crate::flac::FlacEncoder;
+
+/// The sample rate of the audio that librespot produces
+const SAMPLE_RATE: u32 = 44100;
+
+const CHANNELS: u16 = 2;
+
+/// The size (in bytes) of a single stereo f32 sample
+const FRAME_SIZE: u32 = CHANNELS as u32 * 4;
+
+/// WAV files cannot be larger than 4 GiB, a new file is started well before that limit is reached
+const MAX_DATA_SIZE: u64 = u32::MAX as u64 - 16 * 1024 * 1024;
+
+/// The file format of recordings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RecordingFormat {
+    /// 32-bit float WAV, which contains exactly the audio that was sent to Discord
+    #[default]
+    Wav,
+
+    /// 16-bit FLAC, which is lossless at CD quality and a lot smaller
+    Flac,
+}
+
+impl RecordingFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Wav => "wav",
+            Self::Flac => "flac",
+        }
+    }
+}
+
+/// Information about a track that is stored in the recording
+#[derive(Debug, Clone, Default)]
+pub struct TrackMetadata {
+    pub title: String,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub url: Option<String>,
+}
+
+enum Message {
+    Audio(Vec<f32>),
+    Track(TrackMetadata),
+    Stop,
+}
+
+/// Create a recorder, and the tap that feeds it with the audio that is sent to Discord
+pub fn channel() -> (Recorder, RecordingTap) {
+    let (sender, receiver) = watch::channel(None);
+
+    (
+        Recorder { sender },
+        RecordingTap {
+            recording: receiver,
+            sender: None,
+        },
+    )
+}
+
+/// Controls the recording of a player.
+///
+/// While recording, every track is written to a separate file in the recording directory. The files are written on a
+/// separate thread, so a slow disk never holds up the audio.
+pub struct Recorder {
+    sender: watch::Sender<Option<mpsc::Sender<Message>>>,
+}
+
+impl Recorder {
+    /// Start recording into `directory`, with `track` being the track that is currently playing
+    pub fn start(
+        &self,
+        directory: impl Into<PathBuf>,
+        format: RecordingFormat,
+        track: Option<TrackMetadata>,
+    ) -> std::io::Result<()> {
+        let directory = directory.into();
+        std::fs::create_dir_all(&directory)?;
+
+        let (sender, receiver) = mpsc::channel();
+        let writer = Writer {
+            directory,
+            format,
+            track: track.unwrap_or_default(),
+            index: 0,
+            file: None,
+        };
+
+        std::thread::Builder::new()
+            .name("spoticord-recorder".into())
+            .spawn(move || writer.run(receiver))?;
+
+        if let Some(previous) = self.sender.send_replace(Some(sender)) {
+            _ = previous.send(Message::Stop);
+        }
+
+        Ok(())
+    }
+
+    /// Stop recording, returns whether a recording was in progress
+    pub fn stop(&self) -> bool {
+        match self.sender.send_replace(None) {
+            Some(sender) => {
+                _ = sender.send(Message::Stop);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.sender.borrow().is_some()
+    }
+
+    /// Start a new file for the next track
+    pub fn set_track(&self, track: TrackMetadata) {
+        if let Some(sender) = &*self.sender.borrow() {
+            _ = sender.send(Message::Track(track));
+        }
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Copies the audio that passes through the sink to the [`Recorder`]
+pub struct RecordingTap {
+    recording: watch::Receiver<Option<mpsc::Sender<Message>>>,
+    sender: Option<mpsc::Sender<Message>>,
+}
+
+impl RecordingTap {
+    /// Record a buffer of interleaved stereo samples, if a recording is in progress
+    pub fn process(&mut self, samples: &[f32]) {
+        if self.recording.has_changed().unwrap_or(false) {
+            self.sender = self.recording.borrow_and_update().clone();
+        }
+
+        let Some(sender) = &self.sender else {
+            return;
+        };
+
+        if samples.is_empty() {
+            return;
+        }
+
+        // The writer only stops after it failed, there is no point in sending it more audio
+        if sender.send(Message::Audio(samples.to_vec())).is_err() {
+            self.sender = None;
+        }
+    }
+}
+
+struct Writer {
+    directory: PathBuf,
+    format: RecordingFormat,
+    track: TrackMetadata,
+    index: usize,
+    file: Option<TrackFile>,
+}
+
+impl Writer {
+    fn run(mut self, receiver: mpsc::Receiver<Message>) {
+        while let Ok(message) = receiver.recv() {
+            let result = match message {
+                Message::Audio(samples) => self.write(&samples),
+                Message::Track(track) => {
+                    self.track = track;
+                    self.finish()
+                }
+                Message::Stop => break,
+            };
+
+            if let Err(why) = result {
+                error!("Failed to write recording: {why}");
+                self.file = None;
+
+                return;
+            }
+        }
+
+        if let Err(why) = self.finish() {
+            error!("Failed to finish recording: {why}");
+        }
+    }
+
+    fn write(&mut self, samples: &[f32]) -> std::io::Result<()> {
+        let size = samples.len() as u64 * 4;
+
+        if self.file.as_ref().is_some_and(|file| file.is_full(size)) {
+            self.finish()?;
+        }
+
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => {
+                self.index += 1;
+
+                let path = self
+                    .directory
+                    .join(file_name(self.index, &self.track, self.format));
+                self.file
+                    .insert(TrackFile::create(self.format, &path, &self.track)?)
+            }
+        };
+
+        file.write(samples)
+    }
+
+    fn finish(&mut self) -> std::io::Result<()> {
+        match self.file.take() {
+            Some(file) => file.finish(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The file of a single track
+enum TrackFile {
+    Wav(WavFile),
+    Flac(FlacEncoder<BufWriter<File>>),
+}
+
+impl TrackFile {
+    fn create(
+        format: RecordingFormat,
+        path: &Path,
+        track: &TrackMetadata,
+    ) -> std::io::Result<Self> {
+        match format {
+            RecordingFormat::Wav => Ok(Self::Wav(WavFile::create(path, track)?)),
+            RecordingFormat::Flac => {
+                let comments = [
+                    ("TITLE", track.title.as_str()),
+                    ("ARTIST", track.artist.as_deref().unwrap_or_default()),
+                    ("ALBUM", track.album.as_deref().unwrap_or_default()),
+                    ("COMMENT", track.url.as_deref().unwrap_or_default()),
+                ];
+
+                Ok(Self::Flac(FlacEncoder::new(
+                    BufWriter::new(File::create(path)?),
+                    SAMPLE_RATE,
+                    CHANNELS as usize,
+                    &comments,
+                )?))
+            }
+        }
+    }
+
+    /// Whether writing another `size` bytes of samples would exceed the limits of the format
+    fn is_full(&self, size: u64) -> bool {
+        match self {
+            Self::Wav(file) => file.data_size + size > MAX_DATA_SIZE,
+            Self::Flac(_) => false,
+        }
+    }
+
+    fn write(&mut self, samples: &[f32]) -> std::io::Result<()> {
+        match self {
+            Self::Wav(file) => file.write(samples),
+            Self::Flac(encoder) => encoder.write(samples),
+        }
+    }
+
+    fn finish(self) -> std::io::Result<()> {
+        match self {
+            Self::Wav(file) => file.finish(),
+            Self::Flac(encoder) => encoder.finish().map(|_| ()),
+        }
+    }
+}
+
+/// A 32-bit float WAV file, of which the sizes are filled in once it is finished
+struct WavFile {
+    writer: BufWriter<File>,
+    data_size: u64,
+
+    /// The position of the size field of the data chunk
+    data_offset: u64,
+}
+
+impl WavFile {
+    fn create(path: &Path, track: &TrackMetadata) -> std::io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        writer.write_all(b"RIFF")?;
+        writer.write_all(&0u32.to_le_bytes())?;
+        writer.write_all(b"WAVE")?;
+
+        // WAVE_FORMAT_IEEE_FLOAT
+        writer.write_all(b"fmt ")?;
+        writer.write_all(&16u32.to_le_bytes())?;
+        writer.write_all(&3u16.to_le_bytes())?;
+        writer.write_all(&CHANNELS.to_le_bytes())?;
+        writer.write_all(&SAMPLE_RATE.to_le_bytes())?;
+        writer.write_all(&(SAMPLE_RATE * FRAME_SIZE).to_le_bytes())?;
+        writer.write_all(&(FRAME_SIZE as u16).to_le_bytes())?;
+        writer.write_all(&32u16.to_le_bytes())?;
+
+        let info = info_chunk(track);
+        writer.write_all(b"LIST")?;
+        writer.write_all(&(info.len() as u32).to_le_bytes())?;
+        writer.write_all(&info)?;
+
+        writer.write_all(b"data")?;
+        let data_offset = writer.stream_position()?;
+        writer.write_all(&0u32.to_le_bytes())?;
+
+        Ok(Self {
+            writer,
+            data_size: 0,
+            data_offset,
+        })
+    }
+
+    fn write(&mut self, samples: &[f32]) -> std::io::Result<()> {
+        for sample in samples {
+            self.writer.write_all(&sample.to_le_bytes())?;
+        }
+
+        self.data_size += samples.len() as u64 * 4;
+
+        Ok(())
+    }
+
+    fn finish(mut self) -> std::io::Result<()> {
+        let riff_size = self.data_offset + 4 + self.data_size - 8;
+
+        self.writer.seek(SeekFrom::Start(4))?;
+        self.writer.write_all(&(riff_size as u32).to_le_bytes())?;
+        self.writer.seek(SeekFrom::Start(self.data_offset))?;
+        self.writer
+            .write_all(&(self.data_size as u32).to_le_bytes())?;
+        self.writer.flush()
+    }
+}
+
+/// Build a RIFF INFO list containing the track metadata
+fn info_chunk(track: &TrackMetadata) -> Vec<u8> {
+    let mut chunk = b"INFO".to_vec();
+    let software = format!("Spoticord {}", env!("CARGO_PKG_VERSION"));
+
+    let fields = [
+        (b"INAM", Some(&track.title)),
+        (b"IART", track.artist.as_ref()),
+        (b"IPRD", track.album.as_ref()),
+        (b"ICMT", track.url.as_ref()),
+        (b"ISFT", Some(&software)),
+    ];
+
+    for (id, value) in fields {
+        let Some(value) = value.filter(|value| !value.is_empty()) else {
+            continue;
+        };
+
+        // Values are null terminated, and chunks are padded to an even size
+        let size = value.len() + 1;
+
+        chunk.extend_from_slice(id);
+        chunk.extend_from_slice(&(size as u32).to_le_bytes());
+        chunk.extend_from_slice(value.as_bytes());
+        chunk.push(0);
+
+        if size % 2 == 1 {
+            chunk.push(0);
+        }
+    }
+
+    chunk
+}
+
+fn file_name(index: usize, track: &TrackMetadata, format: RecordingFormat) -> String {
+    let name = match &track.artist {
+        Some(artist) => format!("{artist} - {}", track.title),
+        None => track.title.clone(),
+    };
+
+    // Only keep characters that are safe to use in file names on every platform
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .take(100)
+        .collect();
+
+    let extension = format.extension();
+
+    if name.trim().is_empty() {
+        format!("{index:03}.{extension}")
+    } else {
+        format!("{index:03} - {}.{extension}", name.trim())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(title: &str) -> TrackMetadata {
+        TrackMetadata {
+            title: title.to_string(),
+            artist: Some("Artist".to_string()),
+            album: Some("Album".to_string()),
+            url: Some(format!("https://open.spotify.com/track/{title}")),
+        }
+    }
+
+    /// Record two tracks on the current thread, returning the files that were written
+    fn record(name: &str, format: RecordingFormat, frames: [usize; 2]) -> Vec<PathBuf> {
+        let directory =
+            std::env::temp_dir().join(format!("spoticord-recorder-{}-{name}", std::process::id()));
+        _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        sender
+            .send(Message::Audio(vec![0.5; frames[0] * 2]))
+            .unwrap();
+        sender.send(Message::Track(track("Second"))).unwrap();
+        sender
+            .send(Message::Audio(vec![-0.5; frames[1] * 2]))
+            .unwrap();
+        sender.send(Message::Stop).unwrap();
+
+        Writer {
+            directory: directory.clone(),
+            format,
+            track: track("First"),
+            index: 0,
+            file: None,
+        }
+        .run(receiver);
+
+        let mut files: Vec<_> = std::fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        files.sort();
+
+        files
+    }
+
+    fn u32_at(data: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+    }
+
+    /// Find a RIFF chunk, returning the offset of its data and its size
+    fn find_chunk(data: &[u8], id: &[u8; 4]) -> (usize, usize) {
+        let mut offset = 12;
+
+        while offset + 8 <= data.len() {
+            let size = u32_at(data, offset + 4) as usize;
+
+            if &data[offset..offset + 4] == id {
+                return (offset + 8, size);
+            }
+
+            offset += 8 + size + size % 2;
+        }
+
+        panic!("missing {} chunk", String::from_utf8_lossy(id));
+    }
+
+    fn info_field(data: &[u8], id: &[u8; 4]) -> Option<String> {
+        let (offset, size) = find_chunk(data, b"LIST");
+        let info = &data[offset..offset + size];
+        let mut position = 4;
+
+        while position + 8 <= info.len() {
+            let field_size = u32_at(info, position + 4) as usize;
+            let value = &info[position + 8..position + 8 + field_size];
+
+            if &info[position..position + 4] == id {
+                return Some(String::from_utf8_lossy(&value[..field_size - 1]).to_string());
+            }
+
+            position += 8 + field_size + field_size % 2;
+        }
+
+        None
+    }
+
+    #[test]
+    fn wav_file_per_track() {
+        let files = record("wav", RecordingFormat::Wav, [1000, 2500]);
+
+        let names: Vec<_> = files
+            .iter()
+            .map(|file| file.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            ["001 - Artist - First.wav", "002 - Artist - Second.wav"]
+        );
+
+        for (file, (title, frames)) in files.iter().zip([("First", 1000), ("Second", 2500)]) {
+            let data = std::fs::read(file).unwrap();
+
+            assert_eq!(&data[..4], b"RIFF");
+            assert_eq!(&data[8..12], b"WAVE");
+            assert_eq!(u32_at(&data, 4) as usize, data.len() - 8);
+
+            let (offset, size) = find_chunk(&data, b"data");
+            assert_eq!(size, frames * FRAME_SIZE as usize);
+            assert_eq!(offset + size, data.len());
+
+            let (offset, size) = find_chunk(&data, b"fmt ");
+            assert_eq!(size, 16);
+            assert_eq!(u32_at(&data, offset + 4), SAMPLE_RATE);
+
+            assert_eq!(info_field(&data, b"INAM").as_deref(), Some(title));
+            assert_eq!(info_field(&data, b"IART").as_deref(), Some("Artist"));
+            assert_eq!(info_field(&data, b"IPRD").as_deref(), Some("Album"));
+            assert_eq!(
+                info_field(&data, b"ICMT"),
+                Some(format!("https://open.spotify.com/track/{title}"))
+            );
+        }
+    }
+
+    #[test]
+    fn flac_file_per_track() {
+        let files = record("flac", RecordingFormat::Flac, [1000, 5000]);
+
+        assert_eq!(files.len(), 2);
+
+        for (file, (title, frames, value)) in files
+            .iter()
+            .zip([("First", 1000, 0.5), ("Second", 5000, -0.5)])
+        {
+            assert_eq!(file.extension().unwrap(), "flac");
+
+            let mut reader = claxon::FlacReader::open(file).unwrap();
+
+            assert_eq!(reader.streaminfo().samples, Some(frames));
+            assert_eq!(reader.get_tag("TITLE").collect::<Vec<_>>(), [title]);
+            assert_eq!(reader.get_tag("ARTIST").collect::<Vec<_>>(), ["Artist"]);
+
+            let expected = (value * i16::MAX as f32).round() as i32;
+            assert!(reader.samples().all(|sample| sample.unwrap() == expected));
+        }
+    }
+}
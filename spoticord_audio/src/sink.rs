@@ -1,3 +1,9 @@
+use crate::crossfade::Crossfader;
+use crate::effects::EffectsChain;
+use crate::limiter::Limiter;
+use crate::loudness::LoudnessMeter;
+use crate::recorder::RecordingTap;
+use crate::speed::TimeStretch;
 use crate::stream::Stream;
 use librespot::playback::audio_backend::{Sink, SinkAsBytes, SinkError, SinkResult};
 use librespot::playback::convert::Converter;
@@ -13,11 +19,36 @@ pub enum SinkEvent {
 pub struct StreamSink {
     stream: Stream,
     sender: UnboundedSender<SinkEvent>,
+    effects: EffectsChain,
+    crossfade: Crossfader,
+    speed: TimeStretch,
+    meter: LoudnessMeter,
+    limiter: Option<Limiter>,
+    recording: RecordingTap,
 }
 
 impl StreamSink {
-    pub fn new(stream: Stream, sender: UnboundedSender<SinkEvent>) -> Self {
-        Self { stream, sender }
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        stream: Stream,
+        sender: UnboundedSender<SinkEvent>,
+        effects: EffectsChain,
+        crossfade: Crossfader,
+        speed: TimeStretch,
+        meter: LoudnessMeter,
+        limiter: Option<Limiter>,
+        recording: RecordingTap,
+    ) -> Self {
+        Self {
+            stream,
+            sender,
+            effects,
+            crossfade,
+            speed,
+            meter,
+            limiter,
+            recording,
+        }
     }
 }
 
@@ -40,6 +71,12 @@ impl Sink for StreamSink {
         }
 
         self.stream.flush().ok();
+        self.speed.reset();
+        self.meter.reset();
+
+        if let Some(limiter) = &mut self.limiter {
+            limiter.reset();
+        }
 
         Ok(())
     }
@@ -51,7 +88,25 @@ impl Sink for StreamSink {
             return Ok(());
         };
 
-        self.write_bytes(converter.f64_to_f32(&samples).as_bytes())?;
+        let mut samples = converter.f64_to_f32(&samples);
+        let frames = samples.len() / 2;
+
+        self.effects.process(&mut samples);
+        self.crossfade.process(&mut samples, frames);
+
+        // The crossfade works in track time, so the speed can only be changed afterwards
+        self.speed.process(&mut samples);
+        self.meter.process(&samples);
+
+        if let Some(limiter) = &mut self.limiter {
+            limiter.process(&mut samples);
+            self.meter
+                .record_gain_reduction(limiter.take_gain_reduction());
+        }
+
+        self.recording.process(&samples);
+
+        self.write_bytes(samples.as_bytes())?;
 
         Ok(())
     }
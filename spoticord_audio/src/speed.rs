@@ -0,0 +1,199 @@
+use std::f32::consts::TAU;
+
+use tokio::sync::watch;
+
+const CHANNELS: usize = 2;
+
+/// The slowest playback speed that is supported
+pub const MIN_SPEED: f32 = 0.5;
+
+/// The fastest playback speed that is supported
+pub const MAX_SPEED: f32 = 2.0;
+
+/// The length (in frames) of the segments that are overlapped, about 23 ms
+const SEGMENT: usize = 1024;
+
+/// The distance (in frames) between two segments in the output
+const HOP: usize = SEGMENT / 2;
+
+/// How far (in frames) a segment may be moved to line up with the previous one, which covers the pitch of most voices
+const SEARCH: usize = 256;
+
+/// Only every n-th frame is compared when lining up segments, which is accurate enough and a lot cheaper
+const SEARCH_STRIDE: usize = 4;
+
+/// Changes the playback speed of interleaved stereo audio without changing its pitch.
+///
+/// This uses WSOLA: the audio is cut into overlapping segments which are spread out or pushed together, where every
+/// segment is moved slightly so its waveform lines up with the segment before it.
+pub struct TimeStretch {
+    speed: watch::Receiver<f32>,
+    window: Vec<f32>,
+
+    /// Audio that has not been fully used yet
+    input: Vec<f32>,
+
+    /// The position (in frames, within `input`) the next segment would start at without any alignment
+    position: f64,
+
+    /// Where the audio continues after the previous segment, which the next segment has to line up with
+    continuation: Option<usize>,
+
+    /// The faded out second half of the previous segment, which the next segment is added to
+    overlap: Vec<f32>,
+
+    active: bool,
+}
+
+impl TimeStretch {
+    pub fn new(speed: watch::Receiver<f32>) -> Self {
+        Self {
+            speed,
+            window: (0..SEGMENT)
+                .map(|i| 0.5 - 0.5 * (TAU * i as f32 / SEGMENT as f32).cos())
+                .collect(),
+            input: Vec::new(),
+            position: 0.0,
+            continuation: None,
+            overlap: Vec::new(),
+            active: false,
+        }
+    }
+
+    /// Change the speed of a buffer of interleaved stereo samples.
+    ///
+    /// The amount of samples in the buffer changes when the speed is not 1, and a small amount of audio is held back.
+    pub fn process(&mut self, samples: &mut Vec<f32>) {
+        let speed = self.speed.borrow().clamp(MIN_SPEED, MAX_SPEED);
+
+        if speed == 1.0 {
+            if self.active {
+                self.finish(samples);
+            }
+
+            return;
+        }
+
+        if !self.active {
+            self.active = true;
+
+            // Leave room for moving the first segment back
+            self.input.resize(SEARCH * CHANNELS, 0.0);
+            self.position = SEARCH as f64;
+            self.overlap.resize(HOP * CHANNELS, 0.0);
+        }
+
+        self.input.append(samples);
+
+        loop {
+            let position = self.position.round() as usize;
+            let needed = match self.continuation {
+                Some(continuation) => (position + SEARCH + SEGMENT).max(continuation + SEGMENT),
+                None => position + SEARCH + SEGMENT,
+            };
+
+            if self.input.len() < needed * CHANNELS {
+                break;
+            }
+
+            let start = match self.continuation {
+                Some(continuation) => self.align(position, continuation),
+                None => position,
+            };
+
+            for i in 0..HOP {
+                for channel in 0..CHANNELS {
+                    let index = i * CHANNELS + channel;
+
+                    samples.push(
+                        self.overlap[index] + self.input[start * CHANNELS + index] * self.window[i],
+                    );
+                    self.overlap[index] =
+                        self.input[(start + HOP) * CHANNELS + index] * self.window[HOP + i];
+                }
+            }
+
+            self.position += HOP as f64 * speed as f64;
+
+            // Drop the audio that can no longer be used by any segment
+            let consumed = (start + HOP).min(self.position.floor() as usize - SEARCH);
+
+            self.input.drain(..consumed * CHANNELS);
+            self.continuation = Some(start + HOP - consumed);
+            self.position -= consumed as f64;
+        }
+    }
+
+    /// Forget all audio that is being held back, which should be done whenever the audio is interrupted
+    pub fn reset(&mut self) {
+        self.input.clear();
+        self.overlap.clear();
+        self.position = 0.0;
+        self.continuation = None;
+        self.active = false;
+    }
+
+    /// Go back to normal speed by fading from the last segment into the audio that follows it
+    fn finish(&mut self, samples: &mut Vec<f32>) {
+        let mut output = Vec::with_capacity(self.input.len() + samples.len());
+
+        if let Some(continuation) = self.continuation {
+            let remaining = &self.input[continuation * CHANNELS..];
+            let fade = HOP.min(remaining.len() / CHANNELS);
+
+            output.extend(
+                remaining[..fade * CHANNELS]
+                    .iter()
+                    .zip(&self.overlap)
+                    .enumerate()
+                    .map(|(i, (sample, overlap))| overlap + sample * self.window[i / CHANNELS]),
+            );
+
+            output.extend_from_slice(&remaining[fade * CHANNELS..]);
+        }
+
+        output.append(samples);
+        *samples = output;
+
+        self.reset();
+    }
+
+    /// Find the segment near `position` that lines up best with the audio at `continuation`
+    fn align(&self, position: usize, continuation: usize) -> usize {
+        let mono =
+            |frame: usize| (self.input[frame * CHANNELS] + self.input[frame * CHANNELS + 1]) / 2.0;
+
+        let score = |candidate: usize| {
+            let mut correlation = 0.0;
+            let mut energy = 0.0;
+
+            for i in (0..SEGMENT).step_by(SEARCH_STRIDE) {
+                let sample = mono(candidate + i);
+
+                correlation += mono(continuation + i) * sample;
+                energy += sample * sample;
+            }
+
+            if energy > 0.0 {
+                correlation / energy.sqrt()
+            } else {
+                0.0
+            }
+        };
+
+        // Stay at the nominal position unless another one is actually better, like in silence
+        let mut best = position;
+        let mut best_score = score(position);
+
+        for candidate in position - SEARCH..=position + SEARCH {
+            let score = score(candidate);
+
+            if score > best_score {
+                best = candidate;
+                best_score = score;
+            }
+        }
+
+        best
+    }
+}
@@ -1,45 +1,221 @@
 use std::{
+    cell::UnsafeCell,
     io::{Read, Seek, Write},
-    sync::{Arc, Condvar, Mutex},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread::Thread,
+    time::Duration,
 };
 
 use songbird::input::core::io::MediaSource;
 
-/// The lower the value, the less latency
+/// The default buffer size, used when no explicit capacity is provided
 ///
-/// Too low of a value results in jittery audio
-const BUFFER_SIZE: usize = 64 * 1024;
+/// The lower the value, the less latency. Too low of a value results in jittery audio
+pub const BUFFER_SIZE: usize = 64 * 1024;
 
-#[derive(Clone, Default)]
+/// The maximum amount of time a blocked writer sleeps before checking for free space again
+const PARK_TIMEOUT: Duration = Duration::from_millis(10);
+
+/// A fixed-capacity single-producer single-consumer audio buffer.
+///
+/// The librespot sink is the only writer, and songbird is the only reader. Reads and writes never take a lock,
+/// the writer only parks its thread when the buffer is full (which provides backpressure to the decoder).
+#[derive(Clone)]
 pub struct Stream {
-    inner: Arc<(Mutex<Vec<u8>>, Condvar)>,
+    inner: Arc<Inner>,
+}
+
+/// Statistics about the state of a [`Stream`]
+#[derive(Debug, Clone, Copy)]
+pub struct StreamStats {
+    /// The size of the buffer in bytes
+    pub capacity: usize,
+
+    /// The amount of bytes that are currently buffered
+    pub buffered: usize,
+
+    /// How often the reader ran out of audio
+    pub underruns: u64,
+
+    /// The total amount of silence (in bytes) that was inserted because the reader ran out of audio
+    pub silence: u64,
+
+    /// How often the writer had to wait for the reader to make room
+    pub overruns: u64,
+}
+
+struct Inner {
+    buffer: Box<[UnsafeCell<u8>]>,
+
+    /// Total amount of bytes ever written, only modified by the writer
+    head: AtomicUsize,
+
+    /// Total amount of bytes ever read, only modified by the reader
+    tail: AtomicUsize,
+
+    /// Everything before this position has been flushed, and must be skipped by the reader
+    discard: AtomicUsize,
+
+    /// Incremented on every flush, so a blocked writer knows the data it is waiting to write is stale
+    flushes: AtomicUsize,
+
+    writer: Mutex<Option<Thread>>,
+    writer_parked: AtomicBool,
+
+    underrun: AtomicBool,
+    underruns: AtomicU64,
+    silence: AtomicU64,
+    overruns: AtomicU64,
 }
 
+// SAFETY: The buffer is only accessed through the head/tail protocol, where the writer only touches bytes between
+// `head` and `tail + capacity`, and the reader only touches bytes between `tail` and `head`. Both positions are
+// published with release/acquire ordering, so the regions never overlap.
+unsafe impl Sync for Inner {}
+unsafe impl Send for Inner {}
+
 impl Stream {
     pub fn new() -> Self {
-        Self::default()
+        Self::with_capacity(BUFFER_SIZE)
+    }
+
+    /// Create a stream that can buffer up to `capacity` bytes
+    pub fn with_capacity(capacity: usize) -> Self {
+        let buffer = (0..capacity.max(1)).map(|_| UnsafeCell::new(0)).collect();
+
+        Self {
+            inner: Arc::new(Inner {
+                buffer,
+                head: AtomicUsize::new(0),
+                tail: AtomicUsize::new(0),
+                discard: AtomicUsize::new(0),
+                flushes: AtomicUsize::new(0),
+                writer: Mutex::new(None),
+                writer_parked: AtomicBool::new(false),
+                underrun: AtomicBool::new(false),
+                underruns: AtomicU64::new(0),
+                silence: AtomicU64::new(0),
+                overruns: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    pub fn stats(&self) -> StreamStats {
+        let inner = &*self.inner;
+
+        let tail = inner
+            .tail
+            .load(Ordering::Acquire)
+            .max(inner.discard.load(Ordering::Acquire));
+        let head = inner.head.load(Ordering::Acquire);
+
+        StreamStats {
+            capacity: inner.capacity(),
+            buffered: head.saturating_sub(tail),
+            underruns: inner.underruns.load(Ordering::Relaxed),
+            silence: inner.silence.load(Ordering::Relaxed),
+            overruns: inner.overruns.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Default for Stream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Inner {
+    fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    fn ptr(&self) -> *mut u8 {
+        UnsafeCell::raw_get(self.buffer.as_ptr())
+    }
+
+    /// Copy `data` into the ring starting at `position`, wrapping around the end of the buffer
+    ///
+    /// # Safety
+    ///
+    /// The caller must be the writer, and the range must not contain unread data.
+    unsafe fn copy_in(&self, position: usize, data: &[u8]) {
+        let offset = position % self.capacity();
+        let first = data.len().min(self.capacity() - offset);
+
+        std::ptr::copy_nonoverlapping(data.as_ptr(), self.ptr().add(offset), first);
+        std::ptr::copy_nonoverlapping(data[first..].as_ptr(), self.ptr(), data.len() - first);
+    }
+
+    /// Copy bytes out of the ring starting at `position`, wrapping around the end of the buffer
+    ///
+    /// # Safety
+    ///
+    /// The caller must be the reader, and the range must only contain written data.
+    unsafe fn copy_out(&self, position: usize, data: &mut [u8]) {
+        let offset = position % self.capacity();
+        let first = data.len().min(self.capacity() - offset);
+
+        std::ptr::copy_nonoverlapping(self.ptr().add(offset), data.as_mut_ptr(), first);
+        std::ptr::copy_nonoverlapping(self.ptr(), data[first..].as_mut_ptr(), data.len() - first);
+    }
+
+    fn free(&self) -> usize {
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+
+        self.capacity() - head.wrapping_sub(tail)
+    }
+
+    fn wake_writer(&self) {
+        if !self.writer_parked.load(Ordering::SeqCst) {
+            return;
+        }
+
+        if let Some(writer) = &*self.writer.lock().expect("Mutex was poisoned") {
+            writer.unpark();
+        }
     }
 }
 
 impl Read for Stream {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        let (mutex, condvar) = &*self.inner;
-        let mut buffer = mutex.lock().expect("Mutex was poisoned");
+        let inner = &*self.inner;
+
+        let tail = inner
+            .tail
+            .load(Ordering::Relaxed)
+            .max(inner.discard.load(Ordering::Acquire));
+        let head = inner.head.load(Ordering::Acquire);
+        let available = head - tail;
 
         // Prevent Discord jitter by filling buffer with zeroes if we don't have any audio
         // (i.e. when you skip too far ahead in a song which hasn't been downloaded yet)
-        if buffer.is_empty() {
+        if available == 0 {
+            if !inner.underrun.swap(true, Ordering::Relaxed) {
+                inner.underruns.fetch_add(1, Ordering::Relaxed);
+            }
+
             buf.fill(0);
-            condvar.notify_all();
+            inner.silence.fetch_add(buf.len() as u64, Ordering::Relaxed);
+            inner.tail.store(tail, Ordering::Release);
+            inner.wake_writer();
 
             return Ok(buf.len());
         }
 
-        let max_read = usize::min(buf.len(), buffer.len());
+        inner.underrun.store(false, Ordering::Relaxed);
+
+        let max_read = usize::min(buf.len(), available);
 
-        buf[0..max_read].copy_from_slice(&buffer[0..max_read]);
-        buffer.drain(0..max_read);
-        condvar.notify_all();
+        // SAFETY: We are the only reader, and everything between `tail` and `head` has been written
+        unsafe { inner.copy_out(tail, &mut buf[..max_read]) };
+
+        inner.tail.store(tail + max_read, Ordering::Release);
+        inner.wake_writer();
 
         Ok(max_read)
     }
@@ -47,25 +223,55 @@ impl Read for Stream {
 
 impl Write for Stream {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        let (mutex, condvar) = &*self.inner;
-        let mut buffer = mutex.lock().expect("Mutex was poisoned");
+        let inner = &*self.inner;
+        let len = buf.len().min(inner.capacity());
+        let flushes = inner.flushes.load(Ordering::Acquire);
+        let mut waited = false;
+
+        while inner.free() < len {
+            // The stream was flushed while we were waiting, so this data is no longer wanted
+            if inner.flushes.load(Ordering::Acquire) != flushes {
+                return Ok(buf.len());
+            }
+
+            if !waited {
+                inner.overruns.fetch_add(1, Ordering::Relaxed);
+                waited = true;
+            }
+
+            inner
+                .writer
+                .lock()
+                .expect("Mutex was poisoned")
+                .replace(std::thread::current());
+            inner.writer_parked.store(true, Ordering::SeqCst);
+
+            // Check again, the reader might have made room before it could see that we're parked
+            if inner.free() < len {
+                std::thread::park_timeout(PARK_TIMEOUT);
+            }
 
-        while buffer.len() + buf.len() > BUFFER_SIZE {
-            buffer = condvar.wait(buffer).expect("Mutex was poisoned");
+            inner.writer_parked.store(false, Ordering::SeqCst);
         }
 
-        buffer.extend_from_slice(buf);
-        condvar.notify_all();
+        let head = inner.head.load(Ordering::Relaxed);
 
-        Ok(buf.len())
+        // SAFETY: We are the only writer, and there are at least `len` free bytes after `head`
+        unsafe { inner.copy_in(head, &buf[..len]) };
+
+        inner.head.store(head + len, Ordering::Release);
+
+        Ok(len)
     }
 
     fn flush(&mut self) -> std::io::Result<()> {
-        let (mutex, condvar) = &*self.inner;
-        let mut buffer = mutex.lock().expect("Mutex was poisoned");
+        let inner = &*self.inner;
 
-        buffer.clear();
-        condvar.notify_all();
+        inner
+            .discard
+            .fetch_max(inner.head.load(Ordering::Acquire), Ordering::AcqRel);
+        inner.flushes.fetch_add(1, Ordering::AcqRel);
+        inner.wake_writer();
 
         Ok(())
     }
@@ -86,3 +292,171 @@ impl MediaSource for Stream {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc, thread};
+
+    use super::*;
+
+    /// Read exactly `len` bytes, without reading the silence that is inserted when the stream is empty
+    fn read_available(stream: &mut Stream, len: usize) -> Vec<u8> {
+        let mut data = vec![0; len];
+        let mut read = 0;
+
+        while read < len {
+            if stream.stats().buffered == 0 {
+                thread::yield_now();
+                continue;
+            }
+
+            read += stream.read(&mut data[read..]).unwrap();
+        }
+
+        data
+    }
+
+    #[test]
+    fn writes_wrap_around() {
+        let mut stream = Stream::with_capacity(8);
+
+        assert_eq!(stream.write(&[1, 2, 3, 4, 5, 6]).unwrap(), 6);
+        assert_eq!(read_available(&mut stream, 6), [1, 2, 3, 4, 5, 6]);
+
+        // Starts at offset 6, so this wraps around the end of the buffer
+        assert_eq!(stream.write(&[7, 8, 9, 10, 11, 12]).unwrap(), 6);
+        assert_eq!(stream.stats().buffered, 6);
+        assert_eq!(read_available(&mut stream, 6), [7, 8, 9, 10, 11, 12]);
+
+        // Reads can wrap around as well
+        stream.write_all(&[13, 14, 15, 16, 17, 18, 19]).unwrap();
+        assert_eq!(read_available(&mut stream, 7), [13, 14, 15, 16, 17, 18, 19]);
+        assert_eq!(stream.stats().buffered, 0);
+    }
+
+    #[test]
+    fn underrun_fills_silence() {
+        let mut stream = Stream::with_capacity(8);
+        let mut buf = [0xff; 4];
+
+        assert_eq!(stream.read(&mut buf).unwrap(), 4);
+        assert_eq!(buf, [0; 4]);
+
+        // A single underrun can last for multiple reads
+        assert_eq!(stream.read(&mut buf).unwrap(), 4);
+
+        let stats = stream.stats();
+        assert_eq!(stats.underruns, 1);
+        assert_eq!(stats.silence, 8);
+
+        stream.write_all(&[1, 2]).unwrap();
+        assert_eq!(stream.read(&mut buf).unwrap(), 2);
+        assert_eq!(buf[..2], [1, 2]);
+
+        assert_eq!(stream.read(&mut buf).unwrap(), 4);
+
+        let stats = stream.stats();
+        assert_eq!(stats.underruns, 2);
+        assert_eq!(stats.silence, 12);
+    }
+
+    #[test]
+    fn flush_discards_queued_data() {
+        let mut stream = Stream::with_capacity(8);
+
+        stream.write_all(&[1, 2, 3, 4]).unwrap();
+        stream.flush().unwrap();
+
+        assert_eq!(stream.stats().buffered, 0);
+
+        stream.write_all(&[5, 6]).unwrap();
+        assert_eq!(read_available(&mut stream, 2), [5, 6]);
+        assert_eq!(stream.stats().buffered, 0);
+    }
+
+    #[test]
+    fn flush_unblocks_writer() {
+        let mut stream = Stream::with_capacity(4);
+        stream.write_all(&[1, 2, 3, 4]).unwrap();
+
+        let (done_tx, done_rx) = mpsc::channel();
+        let mut writer = stream.clone();
+
+        let handle = thread::spawn(move || {
+            let written = writer.write(&[5, 6, 7, 8]).unwrap();
+            done_tx.send(()).unwrap();
+
+            written
+        });
+
+        // The buffer is full, so the writer has to wait
+        assert!(done_rx.recv_timeout(Duration::from_millis(100)).is_err());
+
+        stream.flush().unwrap();
+
+        done_rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("writer was not unblocked by the flush");
+
+        // The stale data is reported as written, but it is dropped
+        assert_eq!(handle.join().unwrap(), 4);
+        assert_eq!(stream.stats().buffered, 0);
+        assert_eq!(stream.stats().overruns, 1);
+    }
+
+    #[test]
+    fn overruns_are_counted() {
+        let mut stream = Stream::with_capacity(4);
+        stream.write_all(&[1, 2, 3, 4]).unwrap();
+
+        assert_eq!(stream.stats().overruns, 0);
+
+        let mut writer = stream.clone();
+        let handle = thread::spawn(move || writer.write_all(&[5, 6]).unwrap());
+
+        // Wait until the writer is blocked on the full buffer
+        while stream.stats().overruns == 0 {
+            thread::yield_now();
+        }
+
+        assert_eq!(read_available(&mut stream, 2), [1, 2]);
+        handle.join().unwrap();
+
+        assert_eq!(read_available(&mut stream, 4), [3, 4, 5, 6]);
+        assert_eq!(stream.stats().overruns, 1);
+    }
+
+    #[test]
+    fn bytes_arrive_in_order() {
+        const LEN: usize = 1024 * 1024;
+
+        let data: Vec<u8> = (0..LEN).map(|i| (i % 251) as u8).collect();
+        let mut stream = Stream::with_capacity(1000);
+        let mut writer = stream.clone();
+
+        let expected = data.clone();
+        let handle = thread::spawn(move || {
+            // Uneven chunk sizes, so writes end up at every possible offset
+            for chunk in data.chunks(77) {
+                writer.write_all(chunk).unwrap();
+            }
+        });
+
+        let mut received = Vec::with_capacity(LEN);
+        let mut buf = [0; 61];
+
+        while received.len() < LEN {
+            if stream.stats().buffered == 0 {
+                thread::yield_now();
+                continue;
+            }
+
+            let read = stream.read(&mut buf).unwrap();
+            received.extend_from_slice(&buf[..read]);
+        }
+
+        handle.join().unwrap();
+
+        assert!(received == expected, "bytes were reordered or corrupted");
+    }
+}
@@ -0,0 +1,430 @@
+use std::{fmt::Display, path::PathBuf, str::FromStr};
+
+use log::LevelFilter;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::env;
+
+/// The default location of the configuration file, relative to the working directory
+const DEFAULT_CONFIG_PATH: &str = "spoticord.toml";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The Discord bot token used for authenticating with Discord
+    pub discord_token: String,
+
+    /// The URL of the postgres database where spoticord will store user data
+    pub database_url: String,
+
+    /// The base URL of the account-linking frontend
+    pub link_url: String,
+
+    /// The Spotify Client ID used for refreshing tokens
+    pub spotify_client_id: String,
+
+    /// The Spotify Client Secret used for refreshing tokens
+    pub spotify_client_secret: String,
+
+    /// The connection URL of a redis-server instance, only used with the `stats` feature
+    pub kv_url: Option<String>,
+
+    pub bot: BotConfig,
+    pub audio: AudioConfig,
+    pub lyrics: LyricsConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BotConfig {
+    /// The "listening to" message that shows up under the Spoticord bot user
+    pub motd: String,
+
+    /// The time it takes (in seconds) for Spoticord to disconnect when no music is being played
+    pub disconnect_time: u64,
+
+    /// The maximum level of log messages that will be emitted
+    pub log_level: LevelFilter,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AudioConfig {
+    /// The bitrate (in kbps) at which tracks are streamed from Spotify
+    pub bitrate: u16,
+
+    /// The volume (in percent) a new player starts out with
+    pub initial_volume: u8,
+
+    /// How many times Spoticord retries connecting to Spotify before giving up
+    pub connect_retries: u32,
+
+    /// The amount of audio (in milliseconds) that is buffered between Spotify and Discord.
+    ///
+    /// Lower values reduce latency, but too low of a value results in jittery audio on slow hosts.
+    pub buffer_ms: u32,
+
+    /// How audio is handed to Discord
+    pub output: AudioOutput,
+
+    /// Whether a limiter keeps the audio from clipping, which adds a few milliseconds of latency
+    pub limiter: bool,
+
+    /// The directory in which recordings are stored, with a subdirectory for every server
+    pub recording_dir: String,
+
+    /// The directory containing the audio files that can be played using `/announce`
+    pub announcement_dir: String,
+
+    /// The volume (in percent) the music is lowered to while an announcement is playing
+    pub duck_volume: u8,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioOutput {
+    /// Raw PCM audio, which songbird resamples and encodes for every call
+    #[default]
+    Pcm,
+
+    /// Opus frames that are encoded by Spoticord, which songbird passes through to Discord as-is
+    Opus,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LyricsConfig {
+    /// The maximum amount of tracks whose lyrics are kept in memory
+    pub cache_size: usize,
+
+    /// How long (in seconds) lyrics are cached after they have been found
+    pub cache_ttl: u64,
+
+    /// How long (in seconds) it is remembered that a track has no lyrics
+    pub cache_negative_ttl: u64,
+
+    /// Where lyrics are cached besides memory, which keeps them across restarts
+    pub cache_backing: LyricsCacheBacking,
+
+    /// The providers that lyrics are looked up in, in order, until one of them has lyrics for a track
+    pub providers: Vec<LyricsSource>,
+
+    /// The directory with `.lrc` files that is used by the `local` provider
+    pub local_dir: String,
+
+    /// The base URL of the LRCLIB instance that is used by the `lrclib` provider
+    pub lrclib_url: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LyricsCacheBacking {
+    /// Lyrics are only cached in memory
+    #[default]
+    None,
+
+    /// Lyrics are also cached in the redis-server instance at `kv_url`
+    Redis,
+
+    /// Lyrics are also cached in the database
+    Postgres,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LyricsSource {
+    /// The lyrics that Spotify shows in its own apps
+    Spotify,
+
+    /// `.lrc` files in `lyrics.local_dir`, named after the track id or `Artist - Title`
+    Local,
+
+    /// An LRCLIB instance at `lyrics.lrclib_url`
+    Lrclib,
+}
+
+impl FromStr for LyricsSource {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "spotify" => Ok(Self::Spotify),
+            "local" => Ok(Self::Local),
+            "lrclib" => Ok(Self::Lrclib),
+            _ => Err(format!(
+                "expected either `spotify`, `local` or `lrclib`, got `{value}`"
+            )),
+        }
+    }
+}
+
+impl FromStr for LyricsCacheBacking {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "redis" => Ok(Self::Redis),
+            "postgres" => Ok(Self::Postgres),
+            _ => Err(format!(
+                "expected either `none`, `redis` or `postgres`, got `{value}`"
+            )),
+        }
+    }
+}
+
+impl FromStr for AudioOutput {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "pcm" => Ok(Self::Pcm),
+            "opus" => Ok(Self::Opus),
+            _ => Err(format!("expected either `pcm` or `opus`, got `{value}`")),
+        }
+    }
+}
+
+impl Default for BotConfig {
+    fn default() -> Self {
+        Self {
+            motd: "some good 'ol music".to_string(),
+            disconnect_time: 5 * 60,
+            log_level: if cfg!(debug_assertions) {
+                LevelFilter::Debug
+            } else {
+                LevelFilter::Info
+            },
+        }
+    }
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            // 96kbps causes audio key errors, so enjoy the quality upgrade
+            bitrate: 160,
+            initial_volume: 75,
+            connect_retries: 3,
+            buffer_ms: 185,
+            output: AudioOutput::Pcm,
+            limiter: true,
+            recording_dir: "recordings".to_string(),
+            announcement_dir: "announcements".to_string(),
+            duck_volume: 20,
+        }
+    }
+}
+
+impl Default for LyricsConfig {
+    fn default() -> Self {
+        Self {
+            cache_size: 1000,
+            cache_ttl: 24 * 60 * 60,
+            cache_negative_ttl: 60 * 60,
+            cache_backing: LyricsCacheBacking::None,
+            providers: vec![LyricsSource::Spotify],
+            local_dir: "lyrics".into(),
+            lrclib_url: "https://lrclib.net".into(),
+        }
+    }
+}
+
+impl Config {
+    /// Build the configuration by layering the defaults, the configuration file and the environment.
+    ///
+    /// The configuration file is read from the path in `SPOTICORD_CONFIG`, or `spoticord.toml` if that variable
+    /// is not set. A missing `spoticord.toml` is not an error, an explicitly specified file that is missing is.
+    pub fn load() -> Result<Self, ConfigErrors> {
+        let mut errors = vec![];
+
+        let mut config = match Self::from_file() {
+            Ok(config) => config,
+            Err(why) => {
+                errors.push(why);
+                Self::default()
+            }
+        };
+
+        env::apply_overrides(&mut config, &mut errors);
+        config.validate(&mut errors);
+
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigErrors(errors))
+        }
+    }
+
+    /// The location of the configuration file, and whether that file is required to exist
+    pub fn path() -> (PathBuf, bool) {
+        match std::env::var("SPOTICORD_CONFIG") {
+            Ok(path) => (PathBuf::from(path), true),
+            Err(_) => (PathBuf::from(DEFAULT_CONFIG_PATH), false),
+        }
+    }
+
+    /// Check whether switching to `other` changes values that are only read during startup
+    pub fn requires_restart(&self, other: &Config) -> bool {
+        self.discord_token != other.discord_token
+            || self.database_url != other.database_url
+            || self.link_url != other.link_url
+            || self.spotify_client_id != other.spotify_client_id
+            || self.spotify_client_secret != other.spotify_client_secret
+            || self.kv_url != other.kv_url
+            || self.lyrics.cache_size != other.lyrics.cache_size
+            || self.lyrics.cache_ttl != other.lyrics.cache_ttl
+            || self.lyrics.cache_negative_ttl != other.lyrics.cache_negative_ttl
+            || self.lyrics.cache_backing != other.lyrics.cache_backing
+            || self.lyrics.providers != other.lyrics.providers
+            || self.lyrics.local_dir != other.lyrics.local_dir
+            || self.lyrics.lrclib_url != other.lyrics.lrclib_url
+    }
+
+    fn from_file() -> Result<Self, ConfigError> {
+        let (path, required) = Self::path();
+
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(why) if why.kind() == std::io::ErrorKind::NotFound && !required => {
+                return Ok(Self::default())
+            }
+            Err(source) => return Err(ConfigError::Read { path, source }),
+        };
+
+        toml::from_str(&contents).map_err(|source| ConfigError::Parse { path, source })
+    }
+
+    fn validate(&self, errors: &mut Vec<ConfigError>) {
+        let required = [
+            ("discord_token", "DISCORD_TOKEN", &self.discord_token),
+            ("database_url", "DATABASE_URL", &self.database_url),
+            ("link_url", "LINK_URL", &self.link_url),
+            (
+                "spotify_client_id",
+                "SPOTIFY_CLIENT_ID",
+                &self.spotify_client_id,
+            ),
+            (
+                "spotify_client_secret",
+                "SPOTIFY_CLIENT_SECRET",
+                &self.spotify_client_secret,
+            ),
+        ];
+
+        for (key, env, value) in required {
+            if value.is_empty() {
+                errors.push(ConfigError::Missing { key, env });
+            }
+        }
+
+        // Discord does not allow activity names longer than 128 characters
+        if self.bot.motd.is_empty() || self.bot.motd.chars().count() > 128 {
+            errors.push(ConfigError::Invalid {
+                key: "bot.motd",
+                reason: "must be between 1 and 128 characters long".into(),
+            });
+        }
+
+        if self.bot.disconnect_time == 0 {
+            errors.push(ConfigError::Invalid {
+                key: "bot.disconnect_time",
+                reason: "must be at least 1 second".into(),
+            });
+        }
+
+        if !matches!(self.audio.bitrate, 160 | 320) {
+            errors.push(ConfigError::Invalid {
+                key: "audio.bitrate",
+                reason: format!("must be either 160 or 320, got {}", self.audio.bitrate),
+            });
+        }
+
+        if self.audio.initial_volume > 100 {
+            errors.push(ConfigError::Invalid {
+                key: "audio.initial_volume",
+                reason: format!(
+                    "must be between 0 and 100, got {}",
+                    self.audio.initial_volume
+                ),
+            });
+        }
+
+        if !(20..=5000).contains(&self.audio.buffer_ms) {
+            errors.push(ConfigError::Invalid {
+                key: "audio.buffer_ms",
+                reason: format!("must be between 20 and 5000, got {}", self.audio.buffer_ms),
+            });
+        }
+
+        if self.audio.duck_volume > 100 {
+            errors.push(ConfigError::Invalid {
+                key: "audio.duck_volume",
+                reason: format!("must be between 0 and 100, got {}", self.audio.duck_volume),
+            });
+        }
+
+        if self.lyrics.cache_size == 0 {
+            errors.push(ConfigError::Invalid {
+                key: "lyrics.cache_size",
+                reason: "must be at least 1".into(),
+            });
+        }
+
+        if self.lyrics.cache_backing == LyricsCacheBacking::Redis && self.kv_url.is_none() {
+            errors.push(ConfigError::Invalid {
+                key: "lyrics.cache_backing",
+                reason: "`redis` requires `kv_url` to be set".into(),
+            });
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("Failed to read configuration file {path:?}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to parse configuration file {path:?}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+
+    #[error("Missing required configuration value `{key}` (or environment variable `{env}`)")]
+    Missing {
+        key: &'static str,
+        env: &'static str,
+    },
+
+    #[error("Invalid configuration value for `{key}`: {reason}")]
+    Invalid { key: &'static str, reason: String },
+}
+
+/// All errors that were encountered while loading the configuration
+#[derive(Debug)]
+pub struct ConfigErrors(pub Vec<ConfigError>);
+
+impl ConfigErrors {
+    pub fn iter(&self) -> impl Iterator<Item = &ConfigError> {
+        self.0.iter()
+    }
+}
+
+impl Display for ConfigErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} configuration error(s)", self.0.len())?;
+
+        for error in &self.0 {
+            write!(f, "\n - {error}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigErrors {}
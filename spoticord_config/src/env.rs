@@ -1,21 +1,155 @@
-use std::sync::LazyLock;
-
-pub static DISCORD_TOKEN: LazyLock<String> = LazyLock::new(|| {
-    std::env::var("DISCORD_TOKEN").expect("missing DISCORD_TOKEN environment variable")
-});
-pub static DATABASE_URL: LazyLock<String> = LazyLock::new(|| {
-    std::env::var("DATABASE_URL").expect("missing DATABASE_URL environment variable")
-});
-pub static LINK_URL: LazyLock<String> =
-    LazyLock::new(|| std::env::var("LINK_URL").expect("missing LINK_URL environment variable"));
-pub static SPOTIFY_CLIENT_ID: LazyLock<String> = LazyLock::new(|| {
-    std::env::var("SPOTIFY_CLIENT_ID").expect("missing SPOTIFY_CLIENT_ID environment variable")
-});
-pub static SPOTIFY_CLIENT_SECRET: LazyLock<String> = LazyLock::new(|| {
-    std::env::var("SPOTIFY_CLIENT_SECRET")
-        .expect("missing SPOTIFY_CLIENT_SECRET environment variable")
-});
-
-// Locked behind `stats` feature
-pub static KV_URL: LazyLock<String> =
-    LazyLock::new(|| std::env::var("KV_URL").expect("missing KV_URL environment variable"));
+use std::str::FromStr;
+
+use crate::config::{Config, ConfigError};
+
+/// Override configuration values with those provided through environment variables
+pub fn apply_overrides(config: &mut Config, errors: &mut Vec<ConfigError>) {
+    string("DISCORD_TOKEN", &mut config.discord_token);
+    string("DATABASE_URL", &mut config.database_url);
+    string("LINK_URL", &mut config.link_url);
+    string("SPOTIFY_CLIENT_ID", &mut config.spotify_client_id);
+    string("SPOTIFY_CLIENT_SECRET", &mut config.spotify_client_secret);
+
+    if let Some(value) = var("KV_URL") {
+        config.kv_url = Some(value);
+    }
+
+    string("BOT_MOTD", &mut config.bot.motd);
+    parse(
+        "BOT_DISCONNECT_TIME",
+        "bot.disconnect_time",
+        &mut config.bot.disconnect_time,
+        errors,
+    );
+    parse(
+        "BOT_LOG_LEVEL",
+        "bot.log_level",
+        &mut config.bot.log_level,
+        errors,
+    );
+
+    parse(
+        "AUDIO_BITRATE",
+        "audio.bitrate",
+        &mut config.audio.bitrate,
+        errors,
+    );
+    parse(
+        "AUDIO_INITIAL_VOLUME",
+        "audio.initial_volume",
+        &mut config.audio.initial_volume,
+        errors,
+    );
+    parse(
+        "AUDIO_CONNECT_RETRIES",
+        "audio.connect_retries",
+        &mut config.audio.connect_retries,
+        errors,
+    );
+    parse(
+        "AUDIO_BUFFER_MS",
+        "audio.buffer_ms",
+        &mut config.audio.buffer_ms,
+        errors,
+    );
+    parse(
+        "AUDIO_OUTPUT",
+        "audio.output",
+        &mut config.audio.output,
+        errors,
+    );
+    parse(
+        "AUDIO_LIMITER",
+        "audio.limiter",
+        &mut config.audio.limiter,
+        errors,
+    );
+    string("AUDIO_RECORDING_DIR", &mut config.audio.recording_dir);
+    string("AUDIO_ANNOUNCEMENT_DIR", &mut config.audio.announcement_dir);
+    parse(
+        "AUDIO_DUCK_VOLUME",
+        "audio.duck_volume",
+        &mut config.audio.duck_volume,
+        errors,
+    );
+
+    parse(
+        "LYRICS_CACHE_SIZE",
+        "lyrics.cache_size",
+        &mut config.lyrics.cache_size,
+        errors,
+    );
+    parse(
+        "LYRICS_CACHE_TTL",
+        "lyrics.cache_ttl",
+        &mut config.lyrics.cache_ttl,
+        errors,
+    );
+    parse(
+        "LYRICS_CACHE_NEGATIVE_TTL",
+        "lyrics.cache_negative_ttl",
+        &mut config.lyrics.cache_negative_ttl,
+        errors,
+    );
+    parse(
+        "LYRICS_CACHE_BACKING",
+        "lyrics.cache_backing",
+        &mut config.lyrics.cache_backing,
+        errors,
+    );
+    list(
+        "LYRICS_PROVIDERS",
+        "lyrics.providers",
+        &mut config.lyrics.providers,
+        errors,
+    );
+    string("LYRICS_LOCAL_DIR", &mut config.lyrics.local_dir);
+    string("LYRICS_LRCLIB_URL", &mut config.lyrics.lrclib_url);
+}
+
+fn var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+fn string(name: &str, target: &mut String) {
+    if let Some(value) = var(name) {
+        *target = value;
+    }
+}
+
+fn parse<T>(name: &str, key: &'static str, target: &mut T, errors: &mut Vec<ConfigError>)
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    let Some(value) = var(name) else {
+        return;
+    };
+
+    match value.parse() {
+        Ok(value) => *target = value,
+        Err(why) => errors.push(ConfigError::Invalid {
+            key,
+            reason: format!("environment variable `{name}` could not be parsed: {why}"),
+        }),
+    }
+}
+
+/// Parse a comma separated list of values
+fn list<T>(name: &str, key: &'static str, target: &mut Vec<T>, errors: &mut Vec<ConfigError>)
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    let Some(value) = var(name) else {
+        return;
+    };
+
+    match value.split(',').map(str::parse).collect() {
+        Ok(values) => *target = values,
+        Err(why) => errors.push(ConfigError::Invalid {
+            key,
+            reason: format!("environment variable `{name}` could not be parsed: {why}"),
+        }),
+    }
+}
@@ -1,7 +1,19 @@
+mod config;
 mod env;
+mod watch;
 
-use rspotify::{AuthCodeSpotify, Config, Credentials, OAuth, Token};
+use std::sync::{Arc, OnceLock};
+
+use log::warn;
+use rspotify::{AuthCodeSpotify, Config as SpotifyConfig, Credentials, OAuth, Token};
 use serenity::all::GatewayIntents;
+use tokio::sync::watch::{channel, Receiver, Sender};
+
+pub use config::{
+    AudioConfig, AudioOutput, BotConfig, Config, ConfigError, ConfigErrors, LyricsCacheBacking,
+    LyricsConfig, LyricsSource,
+};
+pub use watch::watch;
 
 #[cfg(not(debug_assertions))]
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -9,14 +21,72 @@ pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 #[cfg(debug_assertions)]
 pub const VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), "-dev");
 
+/// The configuration as it was loaded during startup, used for values that cannot be changed at runtime
+static STARTUP: OnceLock<Arc<Config>> = OnceLock::new();
+
+/// The currently active configuration, updated whenever the configuration is reloaded
+static CURRENT: OnceLock<Sender<Arc<Config>>> = OnceLock::new();
+
+/// Load and validate the configuration, reporting every problem that was found.
+///
+/// This must be called once during startup, before any configuration value is accessed.
+pub fn load() -> Result<(), ConfigErrors> {
+    let config = Arc::new(Config::load()?);
+
+    log::set_max_level(config.bot.log_level);
+
+    _ = STARTUP.set(config.clone());
+    _ = CURRENT.set(channel(config).0);
+
+    Ok(())
+}
+
+/// Load the configuration again and publish it to all subscribers.
+///
+/// If the new configuration is invalid it is rejected, and the current configuration stays active.
+pub fn reload() -> Result<(), ConfigErrors> {
+    let config = Config::load()?;
+
+    if startup().requires_restart(&config) {
+        warn!("Some of the changed configuration values will only take effect after a restart");
+    }
+
+    log::set_max_level(config.bot.log_level);
+    current().send_replace(Arc::new(config));
+
+    Ok(())
+}
+
+/// Retrieve the currently active configuration
+pub fn get() -> Arc<Config> {
+    current().borrow().clone()
+}
+
+/// Subscribe to configuration changes
+pub fn subscribe() -> Receiver<Arc<Config>> {
+    current().subscribe()
+}
+
+fn startup() -> &'static Config {
+    STARTUP.get().expect("configuration has not been loaded")
+}
+
+fn current() -> &'static Sender<Arc<Config>> {
+    CURRENT.get().expect("configuration has not been loaded")
+}
+
 /// The "listening to" message that shows up under the Spoticord bot user
-pub const MOTD: &str = "some good 'ol music";
+pub fn motd() -> String {
+    get().bot.motd.clone()
+}
 
 /// The time it takes (in seconds) for Spoticord to disconnect when no music is being played
-pub const DISCONNECT_TIME: u64 = 5 * 60;
+pub fn disconnect_time() -> u64 {
+    get().bot.disconnect_time
+}
 
 pub fn discord_token() -> &'static str {
-    &env::DISCORD_TOKEN
+    &startup().discord_token
 }
 
 pub fn discord_intents() -> GatewayIntents {
@@ -24,25 +94,27 @@ pub fn discord_intents() -> GatewayIntents {
 }
 
 pub fn database_url() -> &'static str {
-    &env::DATABASE_URL
+    &startup().database_url
 }
 
 pub fn link_url() -> &'static str {
-    &env::LINK_URL
+    &startup().link_url
 }
 
-pub fn kv_url() -> &'static str {
-    &env::KV_URL
+pub fn kv_url() -> Option<&'static str> {
+    startup().kv_url.as_deref()
 }
 
 pub fn get_spotify(token: Token) -> AuthCodeSpotify {
+    let config = startup();
+
     AuthCodeSpotify::from_token_with_config(
         token,
         Credentials {
-            id: env::SPOTIFY_CLIENT_ID.to_string(),
-            secret: Some(env::SPOTIFY_CLIENT_SECRET.to_string()),
+            id: config.spotify_client_id.clone(),
+            secret: Some(config.spotify_client_secret.clone()),
         },
         OAuth::default(),
-        Config::default(),
+        SpotifyConfig::default(),
     )
 }
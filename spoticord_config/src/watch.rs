@@ -0,0 +1,91 @@
+use std::time::{Duration, SystemTime};
+
+use log::{error, info};
+
+use crate::config::Config;
+
+/// How often the configuration file is checked for modifications
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Reload the configuration whenever the configuration file is modified, or when the process receives `SIGHUP`.
+///
+/// Invalid configurations are rejected, in which case the previous configuration stays active.
+pub async fn watch() {
+    let mut hangup = Hangup::new();
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    let mut last_modified = modified();
+
+    loop {
+        let reason = tokio::select! {
+            _ = interval.tick() => {
+                let modified = modified();
+
+                if modified == last_modified {
+                    continue;
+                }
+
+                last_modified = modified;
+                "configuration file was modified"
+            }
+
+            _ = hangup.recv() => "received SIGHUP",
+        };
+
+        info!("Reloading configuration: {reason}");
+
+        match crate::reload() {
+            Ok(()) => info!("Configuration has been reloaded"),
+            Err(why) => {
+                error!("Rejected configuration reload, keeping previous configuration: {why}")
+            }
+        }
+    }
+}
+
+fn modified() -> Option<SystemTime> {
+    let (path, _) = Config::path();
+
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+struct Hangup {
+    #[cfg(unix)]
+    signal: Option<tokio::signal::unix::Signal>,
+}
+
+impl Hangup {
+    fn new() -> Self {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+
+            let signal = match signal(SignalKind::hangup()) {
+                Ok(signal) => Some(signal),
+                Err(why) => {
+                    error!("Failed to listen for SIGHUP, configuration can only be reloaded by modifying the file: {why}");
+                    None
+                }
+            };
+
+            Self { signal }
+        }
+
+        #[cfg(not(unix))]
+        Self {}
+    }
+
+    async fn recv(&mut self) {
+        #[cfg(unix)]
+        if let Some(signal) = &mut self.signal {
+            if signal.recv().await.is_some() {
+                return;
+            }
+
+            self.signal = None;
+        }
+
+        std::future::pending().await
+    }
+}
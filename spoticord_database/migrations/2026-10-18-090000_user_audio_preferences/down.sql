@@ -0,0 +1,5 @@
+ALTER TABLE "user"
+    DROP COLUMN bitrate,
+    DROP COLUMN normalization,
+    DROP COLUMN normalization_type,
+    DROP COLUMN normalization_pregain;
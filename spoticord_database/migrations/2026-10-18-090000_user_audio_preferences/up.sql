@@ -0,0 +1,7 @@
+-- A NULL bitrate means the bitrate configured for the deployment is used
+
+ALTER TABLE "user"
+    ADD COLUMN bitrate SMALLINT,
+    ADD COLUMN normalization BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN normalization_type VARCHAR(8) NOT NULL DEFAULT 'auto',
+    ADD COLUMN normalization_pregain REAL NOT NULL DEFAULT 0;
@@ -0,0 +1,6 @@
+-- Settings that apply to everyone listening in a server
+
+CREATE TABLE "guild" (
+    id VARCHAR PRIMARY KEY,
+    crossfade SMALLINT NOT NULL DEFAULT 0
+);
@@ -0,0 +1,3 @@
+-- Recording has to be explicitly allowed by the moderators of a server
+
+ALTER TABLE "guild" ADD COLUMN recording BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,11 @@
+-- Where users left off in podcast episodes, so they can be resumed in a later session
+
+CREATE TABLE "episode_progress" (
+    user_id VARCHAR NOT NULL,
+    episode_id VARCHAR NOT NULL,
+    position_ms INTEGER NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (user_id, episode_id),
+    CONSTRAINT fk_episode_progress_user_id FOREIGN KEY (user_id) REFERENCES "user" (id) ON DELETE CASCADE
+);
@@ -0,0 +1,7 @@
+-- Lyrics that were fetched from Spotify, shared between all sessions and kept across restarts
+
+CREATE TABLE "lyrics_cache" (
+    track_id VARCHAR PRIMARY KEY,
+    lyrics BYTEA,
+    expires TIMESTAMP NOT NULL
+);
@@ -0,0 +1,3 @@
+-- The language the bot speaks in a server, overriding the language of each user's Discord client
+
+ALTER TABLE "guild" ADD COLUMN locale VARCHAR;
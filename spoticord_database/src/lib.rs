@@ -1,7 +1,10 @@
 pub mod error;
+#[cfg(feature = "testing")]
+pub mod memory;
+pub mod models;
+pub mod store;
 
 mod migrations;
-mod models;
 mod schema;
 
 use std::sync::Arc;
@@ -13,9 +16,16 @@ use diesel_async::{
     AsyncPgConnection, RunQueryDsl,
 };
 use error::*;
-use models::{Account, LinkRequest, User};
+use models::{
+    Account, AudioPreferences, CachedLyrics, EpisodeProgress, Guild, GuildSettings, LinkRequest,
+    User,
+};
 use rand::{distributions::Alphanumeric, Rng};
-use rspotify::{clients::BaseClient, Token};
+use rspotify::{
+    clients::{BaseClient, OAuthClient},
+    model::SubscriptionLevel,
+    Token,
+};
 
 #[derive(Clone)]
 pub struct Database(Arc<Pool<AsyncPgConnection>>);
@@ -97,6 +107,75 @@ impl Database {
         Ok(())
     }
 
+    pub async fn update_audio_preferences(
+        &self,
+        user_id: impl AsRef<str>,
+        preferences: AudioPreferences,
+    ) -> Result<()> {
+        use schema::user::dsl::*;
+
+        let mut connection = self.0.get().await?;
+        diesel::update(user)
+            .filter(id.eq(user_id.as_ref()))
+            .set(&preferences)
+            .execute(&mut connection)
+            .await?;
+
+        Ok(())
+    }
+
+    // Guild operations
+
+    pub async fn get_guild(&self, guild_id: impl AsRef<str>) -> Result<Guild> {
+        use schema::guild::dsl::*;
+
+        let mut connection = self.0.get().await?;
+        let result = guild
+            .filter(id.eq(guild_id.as_ref()))
+            .select(Guild::as_select())
+            .first(&mut connection)
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn create_guild(&self, guild_id: impl AsRef<str>) -> Result<Guild> {
+        use schema::guild::dsl::*;
+
+        let mut connection = self.0.get().await?;
+        let result = diesel::insert_into(guild)
+            .values(id.eq(guild_id.as_ref()))
+            .returning(Guild::as_returning())
+            .get_result(&mut connection)
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn get_or_create_guild(&self, guild_id: impl AsRef<str>) -> Result<Guild> {
+        match self.get_guild(&guild_id).await {
+            Err(DatabaseError::NotFound) => self.create_guild(guild_id).await,
+            result => result,
+        }
+    }
+
+    pub async fn update_guild_settings(
+        &self,
+        guild_id: impl AsRef<str>,
+        settings: GuildSettings,
+    ) -> Result<()> {
+        use schema::guild::dsl::*;
+
+        let mut connection = self.0.get().await?;
+        diesel::update(guild)
+            .filter(id.eq(guild_id.as_ref()))
+            .set(&settings)
+            .execute(&mut connection)
+            .await?;
+
+        Ok(())
+    }
+
     // Account operations
 
     pub async fn get_account(&self, _user_id: impl AsRef<str>) -> Result<Account> {
@@ -161,11 +240,7 @@ impl Database {
         use schema::link_request::dsl::*;
 
         let mut connection = self.0.get().await?;
-        let _token: String = rand::thread_rng()
-            .sample_iter(&Alphanumeric)
-            .take(64)
-            .map(char::from)
-            .collect();
+        let _token = generate_token();
         let _expires = (Utc::now() + Duration::hours(1)).naive_utc();
 
         let request = diesel::insert_into(link_request)
@@ -184,6 +259,128 @@ impl Database {
         Ok(request)
     }
 
+    // Episode operations
+
+    pub async fn get_episode_progress(
+        &self,
+        _user_id: impl AsRef<str>,
+        _episode_id: impl AsRef<str>,
+    ) -> Result<EpisodeProgress> {
+        use schema::episode_progress::dsl::*;
+
+        let mut connection = self.0.get().await?;
+        let result = episode_progress
+            .filter(user_id.eq(_user_id.as_ref()))
+            .filter(episode_id.eq(_episode_id.as_ref()))
+            .select(EpisodeProgress::as_select())
+            .first(&mut connection)
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Remember where a user left off in an episode, replacing the previous position
+    pub async fn save_episode_progress(
+        &self,
+        _user_id: impl AsRef<str>,
+        _episode_id: impl AsRef<str>,
+        position: u32,
+    ) -> Result<()> {
+        use schema::episode_progress::dsl::*;
+
+        let mut connection = self.0.get().await?;
+        let now = Utc::now().naive_utc();
+
+        diesel::insert_into(episode_progress)
+            .values((
+                user_id.eq(_user_id.as_ref()),
+                episode_id.eq(_episode_id.as_ref()),
+                position_ms.eq(position as i32),
+                updated_at.eq(now),
+            ))
+            .on_conflict((user_id, episode_id))
+            .do_update()
+            .set((position_ms.eq(position as i32), updated_at.eq(now)))
+            .execute(&mut connection)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_episode_progress(
+        &self,
+        _user_id: impl AsRef<str>,
+        _episode_id: impl AsRef<str>,
+    ) -> Result<usize> {
+        use schema::episode_progress::dsl::*;
+
+        let mut connection = self.0.get().await?;
+        let affected = diesel::delete(episode_progress)
+            .filter(user_id.eq(_user_id.as_ref()))
+            .filter(episode_id.eq(_episode_id.as_ref()))
+            .execute(&mut connection)
+            .await?;
+
+        Ok(affected)
+    }
+
+    // Lyrics operations
+
+    /// Retrieve the cached lyrics of a track, as long as they have not expired
+    pub async fn get_cached_lyrics(&self, _track_id: impl AsRef<str>) -> Result<CachedLyrics> {
+        use schema::lyrics_cache::dsl::*;
+
+        let mut connection = self.0.get().await?;
+        let result = lyrics_cache
+            .filter(track_id.eq(_track_id.as_ref()))
+            .filter(expires.gt(Utc::now().naive_utc()))
+            .select(CachedLyrics::as_select())
+            .first(&mut connection)
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Cache the lyrics of a track, where `None` marks a track without lyrics
+    pub async fn save_cached_lyrics(
+        &self,
+        _track_id: impl AsRef<str>,
+        _lyrics: Option<&[u8]>,
+        ttl: std::time::Duration,
+    ) -> Result<()> {
+        use schema::lyrics_cache::dsl::*;
+
+        let mut connection = self.0.get().await?;
+        let _expires = (Utc::now() + Duration::seconds(ttl.as_secs() as i64)).naive_utc();
+
+        diesel::insert_into(lyrics_cache)
+            .values((
+                track_id.eq(_track_id.as_ref()),
+                lyrics.eq(_lyrics),
+                expires.eq(_expires),
+            ))
+            .on_conflict(track_id)
+            .do_update()
+            .set((lyrics.eq(_lyrics), expires.eq(_expires)))
+            .execute(&mut connection)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Remove all cached lyrics that have expired
+    pub async fn delete_expired_lyrics(&self) -> Result<usize> {
+        use schema::lyrics_cache::dsl::*;
+
+        let mut connection = self.0.get().await?;
+        let affected = diesel::delete(lyrics_cache)
+            .filter(expires.le(Utc::now().naive_utc()))
+            .execute(&mut connection)
+            .await?;
+
+        Ok(affected)
+    }
+
     // Special operations
 
     /// Retrieve a user's Spotify access token. This token, if expired, will automatically be refreshed
@@ -231,4 +428,32 @@ impl Database {
 
         Ok(result.access_token)
     }
+
+    /// Check whether the Spotify account linked to a user has a Premium subscription.
+    ///
+    /// Returns None if Spotify did not tell us the subscription level of the account.
+    pub async fn is_premium(&self, user_id: impl AsRef<str>) -> Result<Option<bool>> {
+        let access_token = self.get_access_token(user_id).await?;
+        let spotify = spoticord_config::get_spotify(Token {
+            access_token,
+            ..Default::default()
+        });
+
+        let Ok(user) = spotify.current_user().await else {
+            return Ok(None);
+        };
+
+        Ok(user
+            .product
+            .map(|product| matches!(product, SubscriptionLevel::Premium)))
+    }
+}
+
+/// Generate a random token for a link request
+fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(64)
+        .map(char::from)
+        .collect()
 }
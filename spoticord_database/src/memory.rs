@@ -0,0 +1,273 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{Duration, Utc};
+
+use crate::{
+    error::{DatabaseError, Result},
+    generate_token,
+    models::{Account, AudioPreferences, EpisodeProgress, Guild, GuildSettings, LinkRequest, User},
+    store::Store,
+};
+
+/// A database that only exists in memory, using the same defaults as the Postgres schema.
+///
+/// Clones share the same data.
+#[derive(Clone, Default)]
+pub struct MemoryDatabase(Arc<Mutex<Tables>>);
+
+#[derive(Default)]
+struct Tables {
+    users: HashMap<String, User>,
+    guilds: HashMap<String, Guild>,
+    accounts: HashMap<String, Account>,
+    requests: HashMap<String, LinkRequest>,
+    episodes: HashMap<(String, String), EpisodeProgress>,
+}
+
+impl MemoryDatabase {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Link a Spotify account, replacing the account that was linked before
+    pub fn insert_account(&self, account: Account) {
+        self.tables()
+            .accounts
+            .insert(account.user_id.clone(), account);
+    }
+
+    /// Link a Spotify account with a token that is valid for an hour
+    pub fn link_account(&self, user_id: impl Into<String>, username: impl Into<String>) {
+        self.insert_account(Account {
+            user_id: user_id.into(),
+            username: username.into(),
+            access_token: generate_token(),
+            refresh_token: generate_token(),
+            session_token: None,
+            expires: (Utc::now() + Duration::hours(1)).naive_utc(),
+        });
+    }
+
+    fn tables(&self) -> std::sync::MutexGuard<'_, Tables> {
+        self.0.lock().expect("Mutex was poisoned")
+    }
+}
+
+impl Store for MemoryDatabase {
+    async fn get_user(&self, user_id: &str) -> Result<User> {
+        self.tables()
+            .users
+            .get(user_id)
+            .cloned()
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    async fn create_user(&self, user_id: &str) -> Result<User> {
+        let user = User {
+            id: user_id.to_string(),
+            device_name: "Spoticord".into(),
+            bitrate: None,
+            normalization: false,
+            normalization_type: "auto".into(),
+            normalization_pregain: 0.0,
+        };
+
+        self.tables()
+            .users
+            .insert(user_id.to_string(), user.clone());
+
+        Ok(user)
+    }
+
+    async fn delete_user(&self, user_id: &str) -> Result<usize> {
+        let mut tables = self.tables();
+
+        // Deleting a user cascades to everything that belongs to them
+        tables.accounts.remove(user_id);
+        tables.requests.remove(user_id);
+        tables.episodes.retain(|(user, _), _| user != user_id);
+
+        Ok(tables.users.remove(user_id).map_or(0, |_| 1))
+    }
+
+    async fn update_device_name(&self, user_id: &str, device_name: &str) -> Result<()> {
+        if let Some(user) = self.tables().users.get_mut(user_id) {
+            user.device_name = device_name.to_string();
+        }
+
+        Ok(())
+    }
+
+    async fn update_audio_preferences(
+        &self,
+        user_id: &str,
+        preferences: AudioPreferences,
+    ) -> Result<()> {
+        let mut tables = self.tables();
+        let Some(user) = tables.users.get_mut(user_id) else {
+            return Ok(());
+        };
+
+        if let Some(bitrate) = preferences.bitrate {
+            user.bitrate = bitrate;
+        }
+
+        if let Some(normalization) = preferences.normalization {
+            user.normalization = normalization;
+        }
+
+        if let Some(normalization_type) = preferences.normalization_type {
+            user.normalization_type = normalization_type;
+        }
+
+        if let Some(normalization_pregain) = preferences.normalization_pregain {
+            user.normalization_pregain = normalization_pregain;
+        }
+
+        Ok(())
+    }
+
+    async fn get_guild(&self, guild_id: &str) -> Result<Guild> {
+        self.tables()
+            .guilds
+            .get(guild_id)
+            .cloned()
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    async fn create_guild(&self, guild_id: &str) -> Result<Guild> {
+        let guild = Guild {
+            id: guild_id.to_string(),
+            crossfade: 0,
+            recording: false,
+            locale: None,
+        };
+
+        self.tables()
+            .guilds
+            .insert(guild_id.to_string(), guild.clone());
+
+        Ok(guild)
+    }
+
+    async fn update_guild_settings(&self, guild_id: &str, settings: GuildSettings) -> Result<()> {
+        let mut tables = self.tables();
+        let Some(guild) = tables.guilds.get_mut(guild_id) else {
+            return Ok(());
+        };
+
+        if let Some(crossfade) = settings.crossfade {
+            guild.crossfade = crossfade;
+        }
+
+        if let Some(recording) = settings.recording {
+            guild.recording = recording;
+        }
+
+        if let Some(locale) = settings.locale {
+            guild.locale = locale;
+        }
+
+        Ok(())
+    }
+
+    async fn get_account(&self, user_id: &str) -> Result<Account> {
+        self.tables()
+            .accounts
+            .get(user_id)
+            .cloned()
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    async fn delete_account(&self, user_id: &str) -> Result<usize> {
+        Ok(self.tables().accounts.remove(user_id).map_or(0, |_| 1))
+    }
+
+    async fn update_session_token(
+        &self,
+        user_id: &str,
+        session_token: Option<String>,
+    ) -> Result<()> {
+        if let Some(account) = self.tables().accounts.get_mut(user_id) {
+            account.session_token = session_token;
+        }
+
+        Ok(())
+    }
+
+    async fn get_request(&self, user_id: &str) -> Result<LinkRequest> {
+        self.tables()
+            .requests
+            .get(user_id)
+            .cloned()
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    async fn create_request(&self, user_id: &str) -> Result<LinkRequest> {
+        let request = LinkRequest {
+            token: generate_token(),
+            user_id: user_id.to_string(),
+            expires: (Utc::now() + Duration::hours(1)).naive_utc(),
+        };
+
+        self.tables()
+            .requests
+            .insert(user_id.to_string(), request.clone());
+
+        Ok(request)
+    }
+
+    async fn get_episode_progress(
+        &self,
+        user_id: &str,
+        episode_id: &str,
+    ) -> Result<EpisodeProgress> {
+        self.tables()
+            .episodes
+            .get(&(user_id.to_string(), episode_id.to_string()))
+            .cloned()
+            .ok_or(DatabaseError::NotFound)
+    }
+
+    async fn save_episode_progress(
+        &self,
+        user_id: &str,
+        episode_id: &str,
+        position: u32,
+    ) -> Result<()> {
+        let progress = EpisodeProgress {
+            user_id: user_id.to_string(),
+            episode_id: episode_id.to_string(),
+            position_ms: position as i32,
+            updated_at: Utc::now().naive_utc(),
+        };
+
+        self.tables()
+            .episodes
+            .insert((user_id.to_string(), episode_id.to_string()), progress);
+
+        Ok(())
+    }
+
+    async fn delete_episode_progress(&self, user_id: &str, episode_id: &str) -> Result<usize> {
+        Ok(self
+            .tables()
+            .episodes
+            .remove(&(user_id.to_string(), episode_id.to_string()))
+            .map_or(0, |_| 1))
+    }
+
+    /// Tokens are never refreshed, as there is no Spotify to refresh them with
+    async fn get_access_token(&self, user_id: &str) -> Result<String> {
+        let account = self.get_account(user_id).await?;
+
+        if account.expired() {
+            return Err(DatabaseError::RefreshTokenFailure);
+        }
+
+        Ok(account.access_token)
+    }
+}
@@ -1,15 +1,77 @@
 use chrono::Utc;
 use diesel::prelude::*;
 
-#[derive(Queryable, Selectable, Debug)]
+#[derive(Queryable, Selectable, Debug, Clone)]
 #[diesel(table_name = super::schema::user)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct User {
     pub id: String,
     pub device_name: String,
+    pub bitrate: Option<i16>,
+    pub normalization: bool,
+    pub normalization_type: String,
+    pub normalization_pregain: f32,
 }
 
-#[derive(Queryable, Selectable, Debug)]
+/// A partial update of a user's audio preferences, fields that are `None` are left untouched
+#[derive(AsChangeset, Debug, Default)]
+#[diesel(table_name = super::schema::user)]
+pub struct AudioPreferences {
+    pub bitrate: Option<Option<i16>>,
+    pub normalization: Option<bool>,
+    pub normalization_type: Option<String>,
+    pub normalization_pregain: Option<f32>,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = super::schema::guild)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Guild {
+    pub id: String,
+
+    /// The length of the crossfade between tracks in seconds, or 0 if crossfading is disabled
+    pub crossfade: i16,
+
+    /// Whether moderators are allowed to record the music that is played in this guild
+    pub recording: bool,
+
+    /// The language that the bot uses in this guild, or `None` to use the language of each user
+    pub locale: Option<String>,
+}
+
+/// A partial update of a guild's settings, fields that are `None` are left untouched
+#[derive(AsChangeset, Debug, Default)]
+#[diesel(table_name = super::schema::guild)]
+pub struct GuildSettings {
+    pub crossfade: Option<i16>,
+    pub recording: Option<bool>,
+    pub locale: Option<Option<String>>,
+}
+
+/// Where a user left off in a podcast episode
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = super::schema::episode_progress)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct EpisodeProgress {
+    pub user_id: String,
+    pub episode_id: String,
+    pub position_ms: i32,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+/// Lyrics that were found by one of the lyrics providers
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = super::schema::lyrics_cache)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct CachedLyrics {
+    pub track_id: String,
+
+    /// The lyrics serialized as JSON, or `None` if the track has no lyrics
+    pub lyrics: Option<Vec<u8>>,
+    pub expires: chrono::NaiveDateTime,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone)]
 #[diesel(table_name = super::schema::account)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct Account {
@@ -31,7 +93,7 @@ impl Account {
     }
 }
 
-#[derive(Queryable, Selectable, Debug)]
+#[derive(Queryable, Selectable, Debug, Clone)]
 #[diesel(table_name = super::schema::link_request)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct LinkRequest {
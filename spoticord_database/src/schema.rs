@@ -16,6 +16,24 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    episode_progress (user_id, episode_id) {
+        user_id -> Varchar,
+        episode_id -> Varchar,
+        position_ms -> Int4,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    guild (id) {
+        id -> Varchar,
+        crossfade -> Int2,
+        recording -> Bool,
+        locale -> Nullable<Varchar>,
+    }
+}
+
 diesel::table! {
     link_request (token) {
         token -> Text,
@@ -24,19 +42,36 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    lyrics_cache (track_id) {
+        track_id -> Varchar,
+        lyrics -> Nullable<Bytea>,
+        expires -> Timestamp,
+    }
+}
+
 diesel::table! {
     user (id) {
         id -> Varchar,
         #[max_length = 32]
         device_name -> Varchar,
+        bitrate -> Nullable<Int2>,
+        normalization -> Bool,
+        #[max_length = 8]
+        normalization_type -> Varchar,
+        normalization_pregain -> Float4,
     }
 }
 
 diesel::joinable!(account -> user (user_id));
+diesel::joinable!(episode_progress -> user (user_id));
 diesel::joinable!(link_request -> user (user_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     account,
+    episode_progress,
+    guild,
     link_request,
+    lyrics_cache,
     user,
 );
@@ -0,0 +1,196 @@
+use std::future::Future;
+
+use crate::{
+    error::{DatabaseError, Result},
+    models::{Account, AudioPreferences, EpisodeProgress, Guild, GuildSettings, LinkRequest, User},
+    Database,
+};
+
+/// The operations that can be performed on the Spoticord database.
+///
+/// This allows code to be used with a different backend than Postgres, like the in-memory database that is available
+/// with the `testing` feature.
+pub trait Store: Clone + Send + Sync + 'static {
+    // User operations
+
+    fn get_user(&self, user_id: &str) -> impl Future<Output = Result<User>> + Send;
+
+    fn create_user(&self, user_id: &str) -> impl Future<Output = Result<User>> + Send;
+
+    fn delete_user(&self, user_id: &str) -> impl Future<Output = Result<usize>> + Send;
+
+    fn get_or_create_user(&self, user_id: &str) -> impl Future<Output = Result<User>> + Send {
+        async move {
+            match self.get_user(user_id).await {
+                Err(DatabaseError::NotFound) => self.create_user(user_id).await,
+                result => result,
+            }
+        }
+    }
+
+    fn update_device_name(
+        &self,
+        user_id: &str,
+        device_name: &str,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    fn update_audio_preferences(
+        &self,
+        user_id: &str,
+        preferences: AudioPreferences,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    // Guild operations
+
+    fn get_guild(&self, guild_id: &str) -> impl Future<Output = Result<Guild>> + Send;
+
+    fn create_guild(&self, guild_id: &str) -> impl Future<Output = Result<Guild>> + Send;
+
+    fn get_or_create_guild(&self, guild_id: &str) -> impl Future<Output = Result<Guild>> + Send {
+        async move {
+            match self.get_guild(guild_id).await {
+                Err(DatabaseError::NotFound) => self.create_guild(guild_id).await,
+                result => result,
+            }
+        }
+    }
+
+    fn update_guild_settings(
+        &self,
+        guild_id: &str,
+        settings: GuildSettings,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    // Account operations
+
+    fn get_account(&self, user_id: &str) -> impl Future<Output = Result<Account>> + Send;
+
+    fn delete_account(&self, user_id: &str) -> impl Future<Output = Result<usize>> + Send;
+
+    fn update_session_token(
+        &self,
+        user_id: &str,
+        session_token: Option<String>,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    // Request operations
+
+    fn get_request(&self, user_id: &str) -> impl Future<Output = Result<LinkRequest>> + Send;
+
+    /// Create a new link request that expires after an hour
+    fn create_request(&self, user_id: &str) -> impl Future<Output = Result<LinkRequest>> + Send;
+
+    // Episode operations
+
+    fn get_episode_progress(
+        &self,
+        user_id: &str,
+        episode_id: &str,
+    ) -> impl Future<Output = Result<EpisodeProgress>> + Send;
+
+    /// Remember where a user left off in an episode, replacing the previous position
+    fn save_episode_progress(
+        &self,
+        user_id: &str,
+        episode_id: &str,
+        position: u32,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    fn delete_episode_progress(
+        &self,
+        user_id: &str,
+        episode_id: &str,
+    ) -> impl Future<Output = Result<usize>> + Send;
+
+    // Special operations
+
+    /// Retrieve a user's Spotify access token, which is refreshed if it has expired
+    fn get_access_token(&self, user_id: &str) -> impl Future<Output = Result<String>> + Send;
+}
+
+impl Store for Database {
+    async fn get_user(&self, user_id: &str) -> Result<User> {
+        Database::get_user(self, user_id).await
+    }
+
+    async fn create_user(&self, user_id: &str) -> Result<User> {
+        Database::create_user(self, user_id).await
+    }
+
+    async fn delete_user(&self, user_id: &str) -> Result<usize> {
+        Database::delete_user(self, user_id).await
+    }
+
+    async fn update_device_name(&self, user_id: &str, device_name: &str) -> Result<()> {
+        Database::update_device_name(self, user_id, device_name).await
+    }
+
+    async fn update_audio_preferences(
+        &self,
+        user_id: &str,
+        preferences: AudioPreferences,
+    ) -> Result<()> {
+        Database::update_audio_preferences(self, user_id, preferences).await
+    }
+
+    async fn get_guild(&self, guild_id: &str) -> Result<Guild> {
+        Database::get_guild(self, guild_id).await
+    }
+
+    async fn create_guild(&self, guild_id: &str) -> Result<Guild> {
+        Database::create_guild(self, guild_id).await
+    }
+
+    async fn update_guild_settings(&self, guild_id: &str, settings: GuildSettings) -> Result<()> {
+        Database::update_guild_settings(self, guild_id, settings).await
+    }
+
+    async fn get_account(&self, user_id: &str) -> Result<Account> {
+        Database::get_account(self, user_id).await
+    }
+
+    async fn delete_account(&self, user_id: &str) -> Result<usize> {
+        Database::delete_account(self, user_id).await
+    }
+
+    async fn update_session_token(
+        &self,
+        user_id: &str,
+        session_token: Option<String>,
+    ) -> Result<()> {
+        Database::update_session_token(self, user_id, session_token).await
+    }
+
+    async fn get_request(&self, user_id: &str) -> Result<LinkRequest> {
+        Database::get_request(self, user_id).await
+    }
+
+    async fn create_request(&self, user_id: &str) -> Result<LinkRequest> {
+        Database::create_request(self, user_id).await
+    }
+
+    async fn get_episode_progress(
+        &self,
+        user_id: &str,
+        episode_id: &str,
+    ) -> Result<EpisodeProgress> {
+        Database::get_episode_progress(self, user_id, episode_id).await
+    }
+
+    async fn save_episode_progress(
+        &self,
+        user_id: &str,
+        episode_id: &str,
+        position: u32,
+    ) -> Result<()> {
+        Database::save_episode_progress(self, user_id, episode_id, position).await
+    }
+
+    async fn delete_episode_progress(&self, user_id: &str, episode_id: &str) -> Result<usize> {
+        Database::delete_episode_progress(self, user_id, episode_id).await
+    }
+
+    async fn get_access_token(&self, user_id: &str) -> Result<String> {
+        Database::get_access_token(self, user_id).await
+    }
+}
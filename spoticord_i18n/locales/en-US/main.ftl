@@ -0,0 +1,327 @@
+# The name of this language, in this language itself
+language-name = English
+
+## Shared
+
+error-title = An error occured
+not-playing = I'm currently not playing any music in this server.
+not-connected = I'm currently not connected to any voice channel.
+settings-fetch-failed = Something went wrong whilst trying to retrieve the server settings.
+settings-update-failed = Something went wrong while trying to update the server settings.
+
+## Slash commands
+## Names are only used for other locales, as the fallback names are those of the commands themselves.
+
+command-help-name = help
+command-help-description = Displays the help message
+command-version-name = version
+command-version-description = Shows the current active version of Spoticord
+command-rename-name = rename
+command-rename-description = Change the name of your Spoticord device in Spotify
+command-rename-option-name = The new device name
+command-audio-name = audio
+command-audio-description = Change the audio settings of your Spoticord player
+command-audio-option-bitrate = The quality at which music is streamed from Spotify
+command-audio-option-normalization = Play every track at a similar volume
+command-audio-option-pregain = Volume boost (in dB, between -10 and 10) applied when normalizing
+command-server-name = server
+command-server-description = Change the Spoticord settings of this server
+command-server-crossfade-name = crossfade
+command-server-crossfade-description = Blend the end of every track into the start of the next one
+command-server-crossfade-option-seconds = The length of the crossfade in seconds, 0 to turn crossfading off
+command-server-recording-name = recording
+command-server-recording-description = Allow moderators to record the music that is played in this server
+command-server-recording-option-enabled = Whether moderators may use /record in this server
+command-server-language-name = language
+command-server-language-description = Change the language that Spoticord speaks in this server
+command-server-language-option-language = The language to use, or automatic to use the language of every user
+command-link-name = link
+command-link-description = Link your Spotify account to Spoticord
+command-unlink-name = unlink
+command-unlink-description = Unlink your Spotify account from Spoticord
+command-unlink-option-user_data = Also delete Discord account information
+command-join-name = join
+command-join-description = Join the current voice channel
+command-disconnect-name = disconnect
+command-disconnect-description = Disconnect the bot from the voice channel
+command-stop-name = stop
+command-stop-description = Stop playback, while staying in the voice channel
+command-playing-name = playing
+command-playing-description = Show details of the current song that is being played
+command-playing-option-update_behavior = How Spoticord should update this information
+command-lyrics-name = lyrics
+command-lyrics-description = Show the lyrics of the current song that is being played
+command-lyrics-option-karaoke = Follow along with the song, highlighting the line that is being sung
+command-lyrics-option-export = Send the full lyrics as a file only you can see
+command-effects-name = effects
+command-effects-description = Apply audio effects to the music that is being played
+command-effects-option-equalizer = The equalizer preset to use
+command-effects-option-bass_boost = How much to boost the bass
+command-effects-option-nightcore = Speed up the music, which also raises the pitch
+command-effects-option-8d = Slowly rotate the music around the listener
+command-effects-option-mono = Play the music in mono
+command-effects-option-reset = Turn off all effects
+command-record-name = record
+command-record-description = Record the music that is played in this server
+command-record-start-name = start
+command-record-start-description = Start recording the music that is played in this server
+command-record-start-option-format = The file format of the recording, WAV by default
+command-record-stop-name = stop
+command-record-stop-description = Stop recording the music that is played in this server
+command-announce-name = announce
+command-announce-description = Play a sound over the music, which is temporarily turned down
+command-announce-option-file = The announcement to play
+command-speed-name = speed
+command-speed-description = Change how fast podcast episodes are played, without changing the pitch
+command-speed-option-speed = The playback speed, from 0.5x to 2x
+
+## /help and /version
+
+help-title = Spoticord Help
+version-title = Spoticord Version
+version-maintainer = Maintained by: DaXcess (@daxcess)
+version-description =
+    Current version: { $version }
+
+    Spoticord is open source, check it out [on GitHub](https://github.com/SpoticordMusic)
+
+## /rename
+
+rename-fetch-failed = Something went wrong whilst trying to rename your Spoticord device.
+rename-failed = Something went wrong while trying to rename your Spoticord device.
+rename-success = Successfully changed the Spotify device name to **{ $name }**
+rename-reconnect = You must reconnect the player for the new name to show up
+
+## /audio
+
+audio-fetch-failed = Something went wrong whilst trying to retrieve your audio settings.
+audio-update-failed = Something went wrong while trying to update your audio settings.
+audio-title = Audio settings
+audio-updated-title = Audio settings updated
+audio-reconnect = You must reconnect the player for the new settings to apply
+audio-pregain-invalid-title = Cannot change pregain
+audio-pregain-invalid-description = The pregain must be between -10 and 10 dB.
+audio-bitrate-premium-title = Cannot change bitrate
+audio-bitrate-premium-description = Streaming at 320 kbps requires a Spotify Premium account.
+audio-bitrate = { $bitrate } kbps
+audio-bitrate-default = { $bitrate } kbps (default)
+audio-normalization-off = Off
+audio-normalization-auto = Automatic
+audio-normalization-album = Album
+audio-normalization-track = Track
+# Fluent reads lines that start with `*` as variants, so those start with an empty placeable instead
+audio-settings =
+    {""}**Bitrate:** { $bitrate }
+    {""}**Normalization:** { $normalization }
+    {""}**Pregain:** { $pregain } dB
+
+## /server
+
+server-crossfade-title = Crossfade
+server-crossfade-updated-title = Crossfade updated
+server-crossfade-off = Crossfading is turned **off**.
+server-crossfade-seconds =
+    { $seconds ->
+        [one] Tracks are blended together over **{ $seconds } second**.
+       *[other] Tracks are blended together over **{ $seconds } seconds**.
+    }
+server-recording-title = Recording
+server-recording-updated-title = Recording updated
+server-recording-allowed = Moderators are **allowed** to record the music in this server using `/record`.
+server-recording-denied = Recording the music in this server is **not allowed**.
+server-language-title = Language
+server-language-updated-title = Language updated
+server-language-server = Spoticord speaks **{ $language }** in this server.
+server-language-auto = Spoticord speaks the language of every user's Discord client in this server.
+server-language-auto-choice = Automatic (the language of every user)
+server-language-unknown-title = Cannot change language
+server-language-unknown-description = Spoticord does not speak **{ $language }**.
+
+## /link and /unlink
+
+link-already-linked-title = Spotify account already linked
+link-already-linked-description = You already have a Spotify account linked.
+link-already-linked-footer = If you are trying to re-link your account then please use /unlink first.
+link-title = Link your Spotify account
+link-description = Click on the button below to start linking your Spotify account.
+link-button = Link your account
+link-failed = An error occured whilst trying to link your account.
+unlink-not-linked-title = No Spotify account linked
+unlink-not-linked-description = You cannot unlink your Spotify account if you haven't linked one.
+unlink-not-linked-footer = You can use /link to link a new Spotify account.
+unlink-success-title = Account unlinked
+unlink-success-description = You have unlinked your Spotify account from Spoticord.
+unlink-success-footer = Changed your mind? You can use /link to link a new Spotify account.
+unlink-failed = An error occured whilst trying to unlink your account.
+
+## /join
+
+join-not-cached = This server hasn't been cached yet?
+join-denied-title = Cannot join voice channel
+join-not-in-voice-channel = You need to connect to a voice channel before running /join
+join-no-voice-permissions =
+    The voice channel you are in is not available.
+    I might not have the permissions to see this channel.
+join-no-text-permissions = I do not have permissions to send messages / links in this text channel.
+join-no-account-title = No Spotify account
+join-no-account-description =
+    You need to link your Spotify account to Spoticord before being able to use it.
+    Use the `/link` command to link your account.
+join-busy-title = Spoticord is busy
+join-busy-description = Spoticord is already being used in this server.
+join-already-playing-title = You are already using Spoticord
+join-already-playing-description =
+    You are already using Spoticord in `{ $server }`
+
+    Stop playing in that server first before starting a new session.
+join-reactivate-failed-title = Failed to reactivate session
+join-reactivate-failed-description = An error occured whilst trying to reactivate the session. Please try again.
+join-create-failed-title = Failed to create session
+join-create-failed-description = An error occured whilst trying to create a session. Please try again.
+join-authentication-failed =
+    Unable to authenticate with Spotify. Did you change your password?
+
+    The broken credentials used have been deleted.
+
+    You might need to relink your account using `/link`.
+join-connected = Connected to voice channel
+join-listen-along = Come listen along in <#{ $channel }>
+join-select-device = You must manually select your device in Spotify
+
+## /disconnect and /stop
+
+disconnect-failed-title = Cannot disconnect bot
+disconnect-not-host = Only the host may disconnect the bot.
+disconnect-title = Goodbye, for now!
+disconnect-description = I have left the voice channel, goodbye for now.
+stop-failed-title = Cannot stop playback
+stop-not-host = Only the host may stop playback.
+stop-title = Stopped playback
+stop-description = I have stopped playing for now. To resume playback, please run the /join command again.
+
+## /playing
+
+playback-not-playing-title = Cannot display song details
+playback-button-error-title = Cannot perform action
+playback-button-not-host = Only the host may use the media buttons
+playback-author = Currently Playing
+playback-unknown-track = Unknown track
+playback-artists = By { $artists }
+playback-local-file = :file_folder: Local file
+playback-album = Album: **{ $album }**
+playback-album-disc-track = (disc { $disc }, track { $track })
+playback-album-track = (track { $track })
+playback-context =
+    { $kind ->
+        [playlist] :notes: Playing from the playlist **{ $name }**
+        [album] :notes: Playing from the album **{ $name }**
+        [artist] :notes: Playing from the artist **{ $name }**
+       *[other] :notes: Playing from the show **{ $name }**
+    }
+playback-explicit = 🅴 Explicit
+playback-popularity = 🔥 Popularity { $popularity }/100
+playback-show = On { $show }
+playback-published = Published <t:{ $timestamp }:D>
+playback-up-next = :fast_forward: Up next: **{ $track }**
+playback-up-next-by = :fast_forward: Up next: **{ $track }** by { $artists }
+playback-pause = Pause
+playback-play = Play
+
+## /lyrics
+
+lyrics-not-playing-title = Cannot get lyrics
+lyrics-unavailable-title = No lyrics available
+lyrics-unavailable-description = This current track has no lyrics available. Just enjoy the tunes!
+lyrics-file = Here are the lyrics of **{ $track }**
+lyrics-dm-sent = The lyrics have been sent to your DMs.
+lyrics-dm-failed-title = Cannot send lyrics
+lyrics-dm-failed-description = I couldn't send you a DM, make sure you allow DMs from this server.
+lyrics-attribution = Lyrics provided by { $provider }
+lyrics-synced = Synced to song
+lyrics-karaoke = Karaoke
+lyrics-romanize = Romanize
+lyrics-send-to-dms = Send to DMs
+
+## /effects
+
+effects-failed-title = Cannot change effects
+effects-not-host = Only the host may change the audio effects.
+effects-title = Audio effects
+effects-updated-title = Audio effects updated
+effects-none = No effects are being applied.
+effects-on = On
+effects-off = Off
+effects-settings =
+    {""}**Equalizer:** { $equalizer }
+    {""}**Bass boost:** { $bass_boost }
+    {""}**Nightcore:** { $nightcore }
+    {""}**8D:** { $rotate }
+    {""}**Mono:** { $mono }
+
+## /record
+
+record-start-failed-title = Cannot start recording
+record-stop-failed-title = Cannot stop recording
+record-not-allowed =
+    Recording is not allowed in this server.
+    It can be allowed using `/server recording`.
+record-already-recording = The music in this server is already being recorded.
+record-not-recording = The music in this server is not being recorded.
+record-start-failed = Something went wrong whilst trying to start the recording.
+record-started-title = Recording started
+record-started-description =
+    All music that is played in <#{ $channel }> is now being recorded.
+    Use `/record stop` to stop the recording.
+record-stopped-title = Recording stopped
+record-stopped-description = The music in this server is no longer being recorded.
+
+## /announce
+
+announce-failed-title = Cannot play announcement
+announce-not-connected = I'm currently not in a voice channel in this server.
+announce-unknown = There is no announcement called `{ $file }`.
+announce-playing = Playing announcement `{ $file }`.
+
+## /speed
+
+speed-failed-title = Cannot change speed
+speed-not-host = Only the host may change the playback speed.
+speed-title = Playback speed
+speed-updated-title = Playback speed updated
+speed-current =
+    Podcast episodes are played at **{ $speed }x**.
+    Music is always played at normal speed.
+speed-updated =
+    Podcast episodes will now be played at **{ $speed }x**.
+    Music is always played at normal speed.
+
+## /audiostats
+
+audiostats-failed-title = Cannot display audio statistics
+audiostats-failed = Something went wrong whilst trying to retrieve the audio statistics.
+audiostats-title = Audio statistics
+audiostats-description =
+    {""}**Buffer size:** { $capacity }
+    {""}**Fill level:** { $buffered } ({ $fill }%)
+    {""}**Underruns:** { $underruns }
+    {""}**Silence inserted:** { $silence }
+    {""}**Overruns:** { $overruns }
+
+    {""}**Loudness (short-term):** { $loudness }
+    {""}**Peak:** { $peak }
+    {""}**Limiter gain reduction:** { $gain_reduction } dB
+
+## Messages in the text channel of a session
+
+session-timeout-title = It's a little quiet in here
+session-timeout-description = The bot has been inactive for too long, and has been disconnected.
+session-stalled-title = Playback stalled
+session-stalled-description =
+    Spotify stopped sending audio, and the track could not be reloaded.
+    Try skipping to another track, or pausing and resuming playback.
+session-connection-lost-title = Spotify connection lost
+session-connection-lost-description =
+    The bot has lost connection to the Spotify AP servers.
+    This is most likely caused by a connection reset on Spotify's end.
+
+    Use `/join` to resummon the bot to your voice channel.
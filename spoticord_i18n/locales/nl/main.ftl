@@ -0,0 +1,326 @@
+# The name of this language, in this language itself
+language-name = Nederlands
+
+## Shared
+
+error-title = Er is een fout opgetreden
+not-playing = Ik speel momenteel geen muziek af in deze server.
+not-connected = Ik ben momenteel niet verbonden met een spraakkanaal.
+settings-fetch-failed = Er ging iets mis bij het ophalen van de serverinstellingen.
+settings-update-failed = Er ging iets mis bij het bijwerken van de serverinstellingen.
+
+## Slash commands
+## Names are only used for other locales, as the fallback names are those of the commands themselves.
+
+command-help-name = hulp
+command-help-description = Toont het hulpbericht
+command-version-name = versie
+command-version-description = Toont de huidige versie van Spoticord
+command-rename-name = hernoemen
+command-rename-description = Wijzig de naam van je Spoticord-apparaat in Spotify
+command-rename-option-name = De nieuwe naam van het apparaat
+command-audio-name = audio
+command-audio-description = Wijzig de audio-instellingen van je Spoticord-speler
+command-audio-option-bitrate = De kwaliteit waarmee muziek van Spotify wordt gestreamd
+command-audio-option-normalization = Speel elk nummer af op een vergelijkbaar volume
+command-audio-option-pregain = Volumeversterking (in dB, tussen -10 en 10) die wordt toegepast bij het normaliseren
+command-server-name = server
+command-server-description = Wijzig de Spoticord-instellingen van deze server
+command-server-crossfade-name = overvloeien
+command-server-crossfade-description = Laat het einde van elk nummer overvloeien in het begin van het volgende
+command-server-crossfade-option-seconds = De duur van het overvloeien in seconden, 0 om overvloeien uit te zetten
+command-server-recording-name = opnames
+command-server-recording-description = Sta moderators toe om de muziek in deze server op te nemen
+command-server-recording-option-enabled = Of moderators /record mogen gebruiken in deze server
+command-server-language-name = taal
+command-server-language-description = Wijzig de taal die Spoticord spreekt in deze server
+command-server-language-option-language = De taal om te gebruiken, of automatisch om de taal van elke gebruiker te gebruiken
+command-link-name = koppelen
+command-link-description = Koppel je Spotify-account aan Spoticord
+command-unlink-name = ontkoppelen
+command-unlink-description = Ontkoppel je Spotify-account van Spoticord
+command-unlink-option-user_data = Verwijder ook de gegevens van je Discord-account
+command-join-name = verbinden
+command-join-description = Verbind met het huidige spraakkanaal
+command-disconnect-name = verbreken
+command-disconnect-description = Verbreek de verbinding van de bot met het spraakkanaal
+command-stop-name = stoppen
+command-stop-description = Stop het afspelen, maar blijf in het spraakkanaal
+command-playing-name = nu-speelt
+command-playing-description = Toon details van het nummer dat nu wordt afgespeeld
+command-playing-option-update_behavior = Hoe Spoticord deze informatie moet bijwerken
+command-lyrics-name = songtekst
+command-lyrics-description = Toon de songtekst van het nummer dat nu wordt afgespeeld
+command-lyrics-option-karaoke = Zing mee met het nummer, waarbij de regel die wordt gezongen wordt gemarkeerd
+command-lyrics-option-export = Stuur de volledige songtekst als bestand dat alleen jij kunt zien
+command-effects-name = effecten
+command-effects-description = Pas audio-effecten toe op de muziek die wordt afgespeeld
+command-effects-option-equalizer = De equalizer-instelling om te gebruiken
+command-effects-option-bass_boost = Hoeveel de bas wordt versterkt
+command-effects-option-nightcore = Versnel de muziek, wat ook de toonhoogte verhoogt
+command-effects-option-8d = Laat de muziek langzaam rond de luisteraar draaien
+command-effects-option-mono = Speel de muziek af in mono
+command-effects-option-reset = Zet alle effecten uit
+command-record-name = opnemen
+command-record-description = Neem de muziek op die in deze server wordt afgespeeld
+command-record-start-name = starten
+command-record-start-description = Begin met het opnemen van de muziek die in deze server wordt afgespeeld
+command-record-start-option-format = Het bestandsformaat van de opname, standaard WAV
+command-record-stop-name = stoppen
+command-record-stop-description = Stop met het opnemen van de muziek die in deze server wordt afgespeeld
+command-announce-name = omroepen
+command-announce-description = Speel een geluid af over de muziek, die tijdelijk zachter wordt gezet
+command-announce-option-file = De omroep om af te spelen
+command-speed-name = snelheid
+command-speed-description = Wijzig hoe snel podcastafleveringen worden afgespeeld, zonder de toonhoogte te veranderen
+command-speed-option-speed = De afspeelsnelheid, van 0,5x tot 2x
+
+## /help and /version
+
+help-title = Spoticord Hulp
+version-title = Spoticord Versie
+version-maintainer = Onderhouden door: DaXcess (@daxcess)
+version-description =
+    Huidige versie: { $version }
+
+    Spoticord is open source, bekijk het [op GitHub](https://github.com/SpoticordMusic)
+
+## /rename
+
+rename-fetch-failed = Er ging iets mis bij het hernoemen van je Spoticord-apparaat.
+rename-failed = Er ging iets mis bij het hernoemen van je Spoticord-apparaat.
+rename-success = De naam van het Spotify-apparaat is gewijzigd naar **{ $name }**
+rename-reconnect = Je moet de speler opnieuw verbinden om de nieuwe naam te zien
+
+## /audio
+
+audio-fetch-failed = Er ging iets mis bij het ophalen van je audio-instellingen.
+audio-update-failed = Er ging iets mis bij het bijwerken van je audio-instellingen.
+audio-title = Audio-instellingen
+audio-updated-title = Audio-instellingen bijgewerkt
+audio-reconnect = Je moet de speler opnieuw verbinden om de nieuwe instellingen toe te passen
+audio-pregain-invalid-title = Kan pregain niet wijzigen
+audio-pregain-invalid-description = De pregain moet tussen -10 en 10 dB liggen.
+audio-bitrate-premium-title = Kan bitrate niet wijzigen
+audio-bitrate-premium-description = Streamen met 320 kbps vereist een Spotify Premium-account.
+audio-bitrate = { $bitrate } kbps
+audio-bitrate-default = { $bitrate } kbps (standaard)
+audio-normalization-off = Uit
+audio-normalization-auto = Automatisch
+audio-normalization-album = Album
+audio-normalization-track = Nummer
+audio-settings =
+    {""}**Bitrate:** { $bitrate }
+    {""}**Normalisatie:** { $normalization }
+    {""}**Pregain:** { $pregain } dB
+
+## /server
+
+server-crossfade-title = Overvloeien
+server-crossfade-updated-title = Overvloeien bijgewerkt
+server-crossfade-off = Overvloeien staat **uit**.
+server-crossfade-seconds =
+    { $seconds ->
+        [one] Nummers vloeien in elkaar over in **{ $seconds } seconde**.
+       *[other] Nummers vloeien in elkaar over in **{ $seconds } seconden**.
+    }
+server-recording-title = Opnames
+server-recording-updated-title = Opnames bijgewerkt
+server-recording-allowed = Moderators **mogen** de muziek in deze server opnemen met `/record`.
+server-recording-denied = Het opnemen van de muziek in deze server is **niet toegestaan**.
+server-language-title = Taal
+server-language-updated-title = Taal bijgewerkt
+server-language-server = Spoticord spreekt **{ $language }** in deze server.
+server-language-auto = Spoticord spreekt in deze server de taal van de Discord-client van elke gebruiker.
+server-language-auto-choice = Automatisch (de taal van elke gebruiker)
+server-language-unknown-title = Kan taal niet wijzigen
+server-language-unknown-description = Spoticord spreekt geen **{ $language }**.
+
+## /link and /unlink
+
+link-already-linked-title = Spotify-account al gekoppeld
+link-already-linked-description = Je hebt al een Spotify-account gekoppeld.
+link-already-linked-footer = Als je je account opnieuw wilt koppelen, gebruik dan eerst /unlink.
+link-title = Koppel je Spotify-account
+link-description = Klik op de knop hieronder om je Spotify-account te koppelen.
+link-button = Koppel je account
+link-failed = Er is een fout opgetreden bij het koppelen van je account.
+unlink-not-linked-title = Geen Spotify-account gekoppeld
+unlink-not-linked-description = Je kunt je Spotify-account niet ontkoppelen als je er geen hebt gekoppeld.
+unlink-not-linked-footer = Je kunt /link gebruiken om een nieuw Spotify-account te koppelen.
+unlink-success-title = Account ontkoppeld
+unlink-success-description = Je hebt je Spotify-account ontkoppeld van Spoticord.
+unlink-success-footer = Van gedachten veranderd? Je kunt /link gebruiken om een nieuw Spotify-account te koppelen.
+unlink-failed = Er is een fout opgetreden bij het ontkoppelen van je account.
+
+## /join
+
+join-not-cached = Deze server is nog niet in de cache opgeslagen?
+join-denied-title = Kan niet verbinden met het spraakkanaal
+join-not-in-voice-channel = Je moet met een spraakkanaal verbonden zijn voordat je /join gebruikt
+join-no-voice-permissions =
+    Het spraakkanaal waarin je zit is niet beschikbaar.
+    Misschien heb ik geen rechten om dit kanaal te zien.
+join-no-text-permissions = Ik heb geen rechten om berichten / links te sturen in dit tekstkanaal.
+join-no-account-title = Geen Spotify-account
+join-no-account-description =
+    Je moet je Spotify-account aan Spoticord koppelen voordat je het kunt gebruiken.
+    Gebruik het `/link` commando om je account te koppelen.
+join-busy-title = Spoticord is bezet
+join-busy-description = Spoticord wordt al gebruikt in deze server.
+join-already-playing-title = Je gebruikt Spoticord al
+join-already-playing-description =
+    Je gebruikt Spoticord al in `{ $server }`
+
+    Stop eerst met afspelen in die server voordat je een nieuwe sessie start.
+join-reactivate-failed-title = Kan sessie niet heractiveren
+join-reactivate-failed-description = Er is een fout opgetreden bij het heractiveren van de sessie. Probeer het opnieuw.
+join-create-failed-title = Kan sessie niet aanmaken
+join-create-failed-description = Er is een fout opgetreden bij het aanmaken van een sessie. Probeer het opnieuw.
+join-authentication-failed =
+    Kan niet inloggen bij Spotify. Heb je je wachtwoord gewijzigd?
+
+    De ongeldige inloggegevens zijn verwijderd.
+
+    Misschien moet je je account opnieuw koppelen met `/link`.
+join-connected = Verbonden met spraakkanaal
+join-listen-along = Luister mee in <#{ $channel }>
+join-select-device = Je moet je apparaat handmatig selecteren in Spotify
+
+## /disconnect and /stop
+
+disconnect-failed-title = Kan de bot niet loskoppelen
+disconnect-not-host = Alleen de host mag de bot loskoppelen.
+disconnect-title = Tot de volgende keer!
+disconnect-description = Ik heb het spraakkanaal verlaten, tot de volgende keer.
+stop-failed-title = Kan het afspelen niet stoppen
+stop-not-host = Alleen de host mag het afspelen stoppen.
+stop-title = Afspelen gestopt
+stop-description = Ik ben voorlopig gestopt met afspelen. Gebruik het /join commando opnieuw om verder te gaan.
+
+## /playing
+
+playback-not-playing-title = Kan details van het nummer niet tonen
+playback-button-error-title = Kan actie niet uitvoeren
+playback-button-not-host = Alleen de host mag de mediaknoppen gebruiken
+playback-author = Nu aan het afspelen
+playback-unknown-track = Onbekend nummer
+playback-artists = Door { $artists }
+playback-local-file = :file_folder: Lokaal bestand
+playback-album = Album: **{ $album }**
+playback-album-disc-track = (schijf { $disc }, nummer { $track })
+playback-album-track = (nummer { $track })
+playback-context =
+    { $kind ->
+        [playlist] :notes: Afgespeeld vanuit de afspeellijst **{ $name }**
+        [album] :notes: Afgespeeld vanuit het album **{ $name }**
+        [artist] :notes: Afgespeeld vanuit de artiest **{ $name }**
+       *[other] :notes: Afgespeeld vanuit de show **{ $name }**
+    }
+playback-explicit = 🅴 Expliciet
+playback-popularity = 🔥 Populariteit { $popularity }/100
+playback-show = In { $show }
+playback-published = Gepubliceerd op <t:{ $timestamp }:D>
+playback-up-next = :fast_forward: Hierna: **{ $track }**
+playback-up-next-by = :fast_forward: Hierna: **{ $track }** door { $artists }
+playback-pause = Pauzeren
+playback-play = Afspelen
+
+## /lyrics
+
+lyrics-not-playing-title = Kan songtekst niet ophalen
+lyrics-unavailable-title = Geen songtekst beschikbaar
+lyrics-unavailable-description = Voor dit nummer is geen songtekst beschikbaar. Geniet gewoon van de muziek!
+lyrics-file = Hier is de songtekst van **{ $track }**
+lyrics-dm-sent = De songtekst is naar je privéberichten gestuurd.
+lyrics-dm-failed-title = Kan songtekst niet versturen
+lyrics-dm-failed-description = Ik kon je geen privébericht sturen, zorg dat je privéberichten vanuit deze server toestaat.
+lyrics-attribution = Songtekst aangeleverd door { $provider }
+lyrics-synced = Gesynchroniseerd met het nummer
+lyrics-karaoke = Karaoke
+lyrics-romanize = Romaniseren
+lyrics-send-to-dms = Stuur naar privéberichten
+
+## /effects
+
+effects-failed-title = Kan effecten niet wijzigen
+effects-not-host = Alleen de host mag de audio-effecten wijzigen.
+effects-title = Audio-effecten
+effects-updated-title = Audio-effecten bijgewerkt
+effects-none = Er worden geen effecten toegepast.
+effects-on = Aan
+effects-off = Uit
+effects-settings =
+    {""}**Equalizer:** { $equalizer }
+    {""}**Basversterking:** { $bass_boost }
+    {""}**Nightcore:** { $nightcore }
+    {""}**8D:** { $rotate }
+    {""}**Mono:** { $mono }
+
+## /record
+
+record-start-failed-title = Kan opname niet starten
+record-stop-failed-title = Kan opname niet stoppen
+record-not-allowed =
+    Opnemen is niet toegestaan in deze server.
+    Het kan worden toegestaan met `/server recording`.
+record-already-recording = De muziek in deze server wordt al opgenomen.
+record-not-recording = De muziek in deze server wordt niet opgenomen.
+record-start-failed = Er ging iets mis bij het starten van de opname.
+record-started-title = Opname gestart
+record-started-description =
+    Alle muziek die wordt afgespeeld in <#{ $channel }> wordt nu opgenomen.
+    Gebruik `/record stop` om de opname te stoppen.
+record-stopped-title = Opname gestopt
+record-stopped-description = De muziek in deze server wordt niet langer opgenomen.
+
+## /announce
+
+announce-failed-title = Kan omroep niet afspelen
+announce-not-connected = Ik zit momenteel niet in een spraakkanaal in deze server.
+announce-unknown = Er is geen omroep met de naam `{ $file }`.
+announce-playing = Omroep `{ $file }` wordt afgespeeld.
+
+## /speed
+
+speed-failed-title = Kan snelheid niet wijzigen
+speed-not-host = Alleen de host mag de afspeelsnelheid wijzigen.
+speed-title = Afspeelsnelheid
+speed-updated-title = Afspeelsnelheid bijgewerkt
+speed-current =
+    Podcastafleveringen worden afgespeeld op **{ $speed }x**.
+    Muziek wordt altijd op normale snelheid afgespeeld.
+speed-updated =
+    Podcastafleveringen worden nu afgespeeld op **{ $speed }x**.
+    Muziek wordt altijd op normale snelheid afgespeeld.
+
+## /audiostats
+
+audiostats-failed-title = Kan audiostatistieken niet weergeven
+audiostats-failed = Er ging iets mis bij het ophalen van de audiostatistieken.
+audiostats-title = Audiostatistieken
+audiostats-description =
+    {""}**Buffergrootte:** { $capacity }
+    {""}**Vulniveau:** { $buffered } ({ $fill }%)
+    {""}**Buffertekorten:** { $underruns }
+    {""}**Ingevoegde stilte:** { $silence }
+    {""}**Bufferoverlopen:** { $overruns }
+
+    {""}**Luidheid (korte termijn):** { $loudness }
+    {""}**Piek:** { $peak }
+    {""}**Versterkingsreductie van de limiter:** { $gain_reduction } dB
+
+## Messages in the text channel of a session
+
+session-timeout-title = Het is hier een beetje stil
+session-timeout-description = De bot is te lang inactief geweest, en is losgekoppeld.
+session-stalled-title = Afspelen vastgelopen
+session-stalled-description =
+    Spotify stuurt geen audio meer, en het nummer kon niet opnieuw worden geladen.
+    Probeer naar een ander nummer te gaan, of pauzeer het afspelen en hervat het.
+session-connection-lost-title = Verbinding met Spotify verloren
+session-connection-lost-description =
+    De bot heeft de verbinding met de Spotify AP-servers verloren.
+    Dit komt waarschijnlijk doordat Spotify de verbinding heeft verbroken.
+
+    Gebruik `/join` om de bot opnieuw naar je spraakkanaal te halen.
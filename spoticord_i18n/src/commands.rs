@@ -0,0 +1,57 @@
+use poise::Command;
+
+use crate::Locale;
+
+/// Apply the translated names and descriptions of slash commands, their subcommands and their parameters.
+///
+/// Messages are keyed by the command path, so `/record stop` uses `command-record-stop-name`,
+/// `command-record-stop-description` and `command-record-stop-option-{parameter}` for the description of a
+/// parameter. Descriptions of the fallback locale replace the doc comments of the commands, so that they are shown
+/// whenever Discord has no translation for the locale of a user.
+pub fn localize_commands<U, E>(commands: &mut [Command<U, E>]) {
+    for command in commands {
+        localize(command, "command");
+    }
+}
+
+fn localize<U, E>(command: &mut Command<U, E>, prefix: &str) {
+    let key = format!("{prefix}-{}", command.name);
+
+    if let Some(description) = Locale::FALLBACK.try_get(&format!("{key}-description")) {
+        command.description = Some(description);
+    }
+
+    for locale in Locale::all().filter(|locale| *locale != Locale::FALLBACK) {
+        if let Some(name) = locale.try_get(&format!("{key}-name")) {
+            command
+                .name_localizations
+                .insert(locale.code().to_string(), name);
+        }
+
+        if let Some(description) = locale.try_get(&format!("{key}-description")) {
+            command
+                .description_localizations
+                .insert(locale.code().to_string(), description);
+        }
+    }
+
+    for parameter in &mut command.parameters {
+        let key = format!("{key}-option-{}", parameter.name);
+
+        if let Some(description) = Locale::FALLBACK.try_get(&key) {
+            parameter.description = Some(description);
+        }
+
+        for locale in Locale::all().filter(|locale| *locale != Locale::FALLBACK) {
+            if let Some(description) = locale.try_get(&key) {
+                parameter
+                    .description_localizations
+                    .insert(locale.code().to_string(), description);
+            }
+        }
+    }
+
+    for subcommand in &mut command.subcommands {
+        localize(subcommand, &key);
+    }
+}
@@ -0,0 +1,284 @@
+//! Translations of everything that Spoticord says, using [Fluent](https://projectfluent.org).
+//!
+//! Every locale has its own directory in `locales`, named after the Discord locale that it is used for. It contains a
+//! `main.ftl` with all messages and a `help.md` with the message of `/help`. The first locale is the fallback, which is
+//! used for Discord locales that have no translation, and for messages that are missing from a translation.
+
+mod commands;
+
+use std::sync::LazyLock;
+
+use fluent_bundle::{concurrent::FluentBundle, FluentResource};
+use log::{error, warn};
+use unic_langid::LanguageIdentifier;
+
+pub use commands::localize_commands;
+pub use fluent_bundle::FluentArgs;
+
+struct Source {
+    code: &'static str,
+    messages: &'static str,
+    help: &'static str,
+}
+
+macro_rules! locales {
+    ($($code:literal),+ $(,)?) => {
+        &[$(Source {
+            code: $code,
+            messages: include_str!(concat!("../locales/", $code, "/main.ftl")),
+            help: include_str!(concat!("../locales/", $code, "/help.md")),
+        }),+]
+    };
+}
+
+const SOURCES: &[Source] = locales!["en-US", "nl"];
+
+static BUNDLES: LazyLock<Vec<FluentBundle<FluentResource>>> = LazyLock::new(|| {
+    SOURCES
+        .iter()
+        .map(|source| {
+            let language: LanguageIdentifier = source
+                .code
+                .parse()
+                .expect("locale directories must be named after a language");
+
+            let resource = FluentResource::try_new(source.messages.to_string()).unwrap_or_else(
+                |(resource, errors)| {
+                    error!("Failed to parse messages of {}: {errors:?}", source.code);
+
+                    resource
+                },
+            );
+
+            let mut bundle = FluentBundle::new_concurrent(vec![language]);
+
+            // Discord does not render the unicode isolation marks around placeables
+            bundle.set_use_isolating(false);
+
+            if let Err(errors) = bundle.add_resource(resource) {
+                error!("Duplicate messages in {}: {errors:?}", source.code);
+            }
+
+            bundle
+        })
+        .collect()
+});
+
+/// Format a message in a locale, optionally with named arguments.
+///
+/// ```ignore
+/// t!(locale, "not-playing");
+/// t!(locale, "lyrics-file", track = name);
+/// ```
+#[macro_export]
+macro_rules! t {
+    ($locale:expr, $key:expr $(,)?) => {
+        $locale.get($key)
+    };
+    ($locale:expr, $key:expr, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = $crate::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+
+        $locale.format($key, &args)
+    }};
+}
+
+/// One of the languages that Spoticord has been translated to
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Locale(usize);
+
+impl Locale {
+    /// The locale that is used when nothing better is available
+    pub const FALLBACK: Self = Self(0);
+
+    /// Find the best translation for a Discord locale, like `en-GB` or `nl`.
+    ///
+    /// Another variant of the same language is used if the locale itself has not been translated.
+    pub fn new(code: &str) -> Self {
+        if let Some(locale) = Self::exact(code) {
+            return locale;
+        }
+
+        let language = primary_language(code);
+
+        SOURCES
+            .iter()
+            .position(|source| primary_language(source.code).eq_ignore_ascii_case(language))
+            .map(Self)
+            .unwrap_or_default()
+    }
+
+    /// Find the locale with exactly the given code, if it has been translated
+    pub fn exact(code: &str) -> Option<Self> {
+        SOURCES
+            .iter()
+            .position(|source| source.code.eq_ignore_ascii_case(code))
+            .map(Self)
+    }
+
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..SOURCES.len()).map(Self)
+    }
+
+    /// The Discord locale code, like `en-US`
+    pub fn code(self) -> &'static str {
+        SOURCES[self.0].code
+    }
+
+    /// The name of the language, in that language itself
+    pub fn name(self) -> String {
+        self.get("language-name")
+    }
+
+    /// The markdown of the help message
+    pub fn help(self) -> &'static str {
+        SOURCES[self.0].help
+    }
+
+    /// Get a message without any arguments
+    pub fn get(self, key: &str) -> String {
+        self.translate(key, None)
+    }
+
+    /// Get a message, filling in the given arguments
+    pub fn format(self, key: &str, args: &FluentArgs) -> String {
+        self.translate(key, Some(args))
+    }
+
+    /// Get a message only if this locale has a translation for it
+    pub fn try_get(self, key: &str) -> Option<String> {
+        self.lookup(key, None)
+    }
+
+    fn translate(self, key: &str, args: Option<&FluentArgs>) -> String {
+        self.lookup(key, args)
+            .or_else(|| Self::FALLBACK.lookup(key, args))
+            .unwrap_or_else(|| {
+                warn!("Missing message {key}");
+
+                key.to_string()
+            })
+    }
+
+    fn lookup(self, key: &str, args: Option<&FluentArgs>) -> Option<String> {
+        let bundle = &BUNDLES[self.0];
+        let pattern = bundle.get_message(key)?.value()?;
+
+        let mut errors = vec![];
+        let message = bundle.format_pattern(pattern, args, &mut errors);
+
+        if !errors.is_empty() {
+            warn!("Failed to format {key} in {}: {errors:?}", self.code());
+        }
+
+        Some(message.into_owned())
+    }
+}
+
+fn primary_language(code: &str) -> &str {
+    code.split('-').next().unwrap_or(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use fluent_syntax::{ast, parser};
+
+    use super::*;
+
+    fn message_ids(source: &Source) -> BTreeSet<String> {
+        let resource = parser::parse(source.messages)
+            .unwrap_or_else(|(_, errors)| panic!("{} has syntax errors: {errors:?}", source.code));
+
+        resource
+            .body
+            .into_iter()
+            .filter_map(|entry| match entry {
+                ast::Entry::Message(message) => Some(message.id.name.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn every_locale_has_every_message() {
+        let expected = message_ids(&SOURCES[0]);
+
+        for source in &SOURCES[1..] {
+            let ids = message_ids(source);
+
+            let missing: Vec<_> = expected.difference(&ids).collect();
+            let unknown: Vec<_> = ids.difference(&expected).collect();
+
+            assert!(missing.is_empty(), "{} is missing {missing:?}", source.code);
+            assert!(
+                unknown.is_empty(),
+                "{} has unknown {unknown:?}",
+                source.code
+            );
+        }
+    }
+
+    #[test]
+    fn commands_fit_within_discord_limits() {
+        for locale in Locale::all() {
+            let ids = message_ids(&SOURCES[locale.0]);
+
+            for id in ids.iter().filter(|id| id.starts_with("command-")) {
+                let value = locale.get(id);
+
+                if id.ends_with("-name") && !id.contains("-option-") {
+                    assert!(
+                        value.len() <= 32
+                            && value
+                                .chars()
+                                .all(|c| c.is_lowercase() || c.is_numeric() || c == '-'),
+                        "{id} in {} is not a valid command name",
+                        locale.code()
+                    );
+                } else {
+                    assert!(
+                        value.chars().count() <= 100,
+                        "{id} in {} is too long",
+                        locale.code()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_locale_has_help() {
+        for source in SOURCES {
+            assert!(
+                !source.help.trim().is_empty(),
+                "{} has no help",
+                source.code
+            );
+        }
+    }
+
+    #[test]
+    fn locales_are_matched_by_language() {
+        assert_eq!(Locale::new("nl").code(), "nl");
+        assert_eq!(Locale::new("en-GB").code(), "en-US");
+        assert_eq!(Locale::new("pt-BR"), Locale::FALLBACK);
+        assert_eq!(Locale::exact("EN-us"), Some(Locale::FALLBACK));
+        assert_eq!(Locale::exact("en-GB"), None);
+    }
+
+    #[test]
+    fn messages_fall_back() {
+        let locale = Locale::new("nl");
+
+        assert_eq!(
+            t!(locale, "this-message-does-not-exist"),
+            "this-message-does-not-exist"
+        );
+        assert_ne!(
+            t!(locale, "language-name"),
+            t!(Locale::FALLBACK, "language-name")
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use librespot::{
+    connect::spirc::Spirc, core::SpotifyId, playback::player::Player as SpotifyPlayer,
+};
+
+/// Controls the Spotify side of a player
+pub trait Controls: Send {
+    fn play(&self);
+
+    fn pause(&self);
+
+    fn next(&self);
+
+    fn previous(&self);
+
+    /// Load a track, and start playing it at `position_ms`
+    fn load(&self, track_id: SpotifyId, position_ms: u32);
+
+    fn seek(&self, position_ms: u32);
+
+    fn shutdown(&self);
+}
+
+/// Controls a player through Spotify Connect
+pub(crate) struct SpotifyControls {
+    pub spirc: Spirc,
+    pub player: Arc<SpotifyPlayer>,
+}
+
+impl Controls for SpotifyControls {
+    fn play(&self) {
+        _ = self.spirc.play();
+    }
+
+    fn pause(&self) {
+        _ = self.spirc.pause();
+    }
+
+    fn next(&self) {
+        _ = self.spirc.next();
+    }
+
+    fn previous(&self) {
+        _ = self.spirc.prev();
+    }
+
+    fn load(&self, track_id: SpotifyId, position_ms: u32) {
+        self.player.load(track_id, true, position_ms);
+    }
+
+    fn seek(&self, position_ms: u32) {
+        // Seeking through Spirc keeps the position it reports to other Spotify Connect devices in sync
+        _ = self.spirc.set_position_ms(position_ms);
+    }
+
+    fn shutdown(&self) {
+        _ = self.spirc.shutdown();
+    }
+}
@@ -1,30 +1,62 @@
 use std::collections::HashSet;
 
 use librespot::{
-    core::SpotifyId,
+    core::{spotify_id::SpotifyItemType, SpotifyId},
     metadata::{
         artist::ArtistsWithRole,
         audio::{AudioItem, UniqueFields},
     },
 };
+use spoticord_audio::recorder::TrackMetadata;
+
+/// The track or episode that Spotify is going to play after the current one
+#[derive(Debug, Clone)]
+pub struct UpNext {
+    pub name: String,
+
+    /// The artists of a track, or the name of the show of an episode
+    pub artists: Vec<String>,
+}
+
+impl UpNext {
+    pub fn new(audio_item: &AudioItem) -> Self {
+        let artists = match &audio_item.unique_fields {
+            UniqueFields::Track { artists, .. } => {
+                artists.iter().map(|artist| artist.name.clone()).collect()
+            }
+            UniqueFields::Episode { show_name, .. } => vec![show_name.to_string()],
+        };
+
+        Self {
+            name: audio_item.name.clone(),
+            artists,
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct PlaybackInfo {
     audio_item: AudioItem,
+    up_next: Option<UpNext>,
 
     updated_at: u128,
     position: u32,
     playing: bool,
+
+    /// The rate at which the position advances while playing
+    speed: f32,
 }
 
 impl PlaybackInfo {
     pub fn new(audio_item: AudioItem, position: u32, playing: bool) -> Self {
         Self {
             audio_item,
+            up_next: None,
 
             updated_at: spoticord_utils::get_time(),
             position,
             playing,
+            speed: 1.0,
         }
     }
 
@@ -32,11 +64,13 @@ impl PlaybackInfo {
         self.audio_item.track_id
     }
 
-    pub fn track_id_string(&self) -> String {
-        self.audio_item
-            .track_id
-            .to_base62()
-            .expect("invalid spotify id")
+    /// The base62 id of the item, which local files don't have
+    pub fn track_id_string(&self) -> Option<String> {
+        if self.is_local() {
+            return None;
+        }
+
+        self.audio_item.track_id.to_base62().ok()
     }
 
     pub fn name(&self) -> String {
@@ -65,6 +99,24 @@ impl PlaybackInfo {
         }
     }
 
+    /// The description of the episode, as written by the creator of the show
+    pub fn description(&self) -> Option<String> {
+        match &self.audio_item.unique_fields {
+            UniqueFields::Episode { description, .. } => Some(description.to_string()),
+            UniqueFields::Track { .. } => None,
+        }
+    }
+
+    /// The time at which the episode was published, as a unix timestamp in seconds
+    pub fn published_at(&self) -> Option<i64> {
+        match &self.audio_item.unique_fields {
+            UniqueFields::Episode { publish_time, .. } => {
+                Some(publish_time.as_timestamp_ms() / 1000)
+            }
+            UniqueFields::Track { .. } => None,
+        }
+    }
+
     pub fn album_name(&self) -> Option<String> {
         match &self.audio_item.unique_fields {
             UniqueFields::Episode { .. } => None,
@@ -72,29 +124,59 @@ impl PlaybackInfo {
         }
     }
 
-    pub fn thumbnail(&self) -> String {
+    /// The position of the track on its album, as `(disc, track)`
+    pub fn album_position(&self) -> Option<(u32, u32)> {
+        match &self.audio_item.unique_fields {
+            UniqueFields::Track {
+                disc_number,
+                number,
+                ..
+            } => Some((*disc_number, *number)),
+            UniqueFields::Episode { .. } => None,
+        }
+    }
+
+    /// How popular the track is on Spotify, from 0 to 100
+    pub fn popularity(&self) -> Option<u8> {
+        match &self.audio_item.unique_fields {
+            UniqueFields::Track { popularity, .. } => Some(*popularity),
+            UniqueFields::Episode { .. } => None,
+        }
+    }
+
+    pub fn is_explicit(&self) -> bool {
+        self.audio_item.is_explicit
+    }
+
+    pub fn up_next(&self) -> Option<&UpNext> {
+        self.up_next.as_ref()
+    }
+
+    pub fn set_up_next(&mut self, up_next: Option<UpNext>) {
+        self.up_next = up_next;
+    }
+
+    /// The cover art of the item, which local files and some other items don't have
+    pub fn thumbnail(&self) -> Option<String> {
         self.audio_item
             .covers
             .first()
-            .expect("spotify track missing cover image")
-            .url
-            .to_string()
+            .map(|cover| cover.url.to_string())
+            .filter(|url| !url.is_empty())
     }
 
     pub fn duration(&self) -> u32 {
         self.audio_item.duration_ms
     }
 
-    pub fn url(&self) -> String {
-        match &self.audio_item.unique_fields {
-            UniqueFields::Episode { .. } => format!(
-                "https://open.spotify.com/episode/{}",
-                self.track_id_string()
-            ),
-            UniqueFields::Track { .. } => {
-                format!("https://open.spotify.com/track/{}", self.track_id_string())
-            }
-        }
+    /// The link to the item on Spotify, which local files don't have
+    pub fn url(&self) -> Option<String> {
+        let id = self.track_id_string()?;
+
+        Some(match &self.audio_item.unique_fields {
+            UniqueFields::Episode { .. } => format!("https://open.spotify.com/episode/{id}"),
+            UniqueFields::Track { .. } => format!("https://open.spotify.com/track/{id}"),
+        })
     }
 
     /// Get the current playback position, which accounts for time that may have passed since this struct was last updated
@@ -103,7 +185,7 @@ impl PlaybackInfo {
             let now = spoticord_utils::get_time();
             let diff = now - self.updated_at;
 
-            self.position + diff as u32
+            self.position + (diff as f64 * self.speed as f64) as u32
         } else {
             self.position
         }
@@ -119,8 +201,20 @@ impl PlaybackInfo {
         self.updated_at = spoticord_utils::get_time();
     }
 
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    pub fn set_speed(&mut self, speed: f32) {
+        self.update_playback(self.current_position(), self.playing);
+        self.speed = speed;
+    }
+
     pub fn update_track(&mut self, audio_item: AudioItem) {
         self.audio_item = audio_item;
+
+        // The track that was up next is most likely the one that just started
+        self.up_next = None;
     }
 
     pub fn is_episode(&self) -> bool {
@@ -130,4 +224,171 @@ impl PlaybackInfo {
     pub fn is_track(&self) -> bool {
         matches!(self.audio_item.unique_fields, UniqueFields::Track { .. })
     }
+
+    /// Whether this is a file from the device of the listener, which Spotify knows little more about than its name
+    pub fn is_local(&self) -> bool {
+        self.audio_item.track_id.item_type == SpotifyItemType::Local
+    }
+
+    /// The metadata that is stored alongside recordings of this track
+    pub fn recording_metadata(&self) -> TrackMetadata {
+        let artist = match self.artists() {
+            Some(artists) => Some(
+                artists
+                    .iter()
+                    .map(|artist| artist.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+            None => self.show_name(),
+        };
+
+        TrackMetadata {
+            title: self.name(),
+            artist,
+            album: self.album_name(),
+            url: self.url(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use librespot::{
+        core::date::Date,
+        metadata::{
+            audio::AudioFiles,
+            image::{CoverImage, ImageSize},
+        },
+    };
+
+    use super::*;
+
+    const TRACK_ID: &str = "4uLU6hMCjMI75M1A2tKUQC";
+    const EPISODE_ID: &str = "512ojhOuo1ktJprKbVcKyQ";
+
+    fn audio_item(uri: &str, covers: Vec<CoverImage>, unique_fields: UniqueFields) -> AudioItem {
+        AudioItem {
+            track_id: SpotifyId::from_uri(uri).expect("invalid uri"),
+            uri: uri.to_string(),
+            files: AudioFiles::default(),
+            name: "Name".to_string(),
+            covers,
+            language: vec![],
+            duration_ms: 180_000,
+            is_explicit: false,
+            availability: Ok(()),
+            alternatives: None,
+            unique_fields,
+        }
+    }
+
+    fn cover() -> Vec<CoverImage> {
+        vec![CoverImage {
+            url: "https://i.scdn.co/image/cover".to_string(),
+            size: ImageSize::DEFAULT,
+            width: 640,
+            height: 640,
+        }]
+    }
+
+    fn track_fields() -> UniqueFields {
+        UniqueFields::Track {
+            artists: ArtistsWithRole::default(),
+            album: "Album".to_string(),
+            album_artists: vec![],
+            popularity: 50,
+            number: 3,
+            disc_number: 1,
+        }
+    }
+
+    fn episode_fields() -> UniqueFields {
+        UniqueFields::Episode {
+            description: "Description".to_string(),
+            publish_time: Date::from_timestamp_ms(1_700_000_000_000).expect("invalid date"),
+            show_name: "Show".to_string(),
+        }
+    }
+
+    #[test]
+    fn track() {
+        let info = PlaybackInfo::new(
+            audio_item(
+                &format!("spotify:track:{TRACK_ID}"),
+                cover(),
+                track_fields(),
+            ),
+            0,
+            true,
+        );
+
+        assert!(info.is_track());
+        assert!(!info.is_local());
+        assert_eq!(info.track_id_string().as_deref(), Some(TRACK_ID));
+        assert_eq!(
+            info.url(),
+            Some(format!("https://open.spotify.com/track/{TRACK_ID}"))
+        );
+        assert_eq!(
+            info.thumbnail().as_deref(),
+            Some("https://i.scdn.co/image/cover")
+        );
+        assert_eq!(info.album_name().as_deref(), Some("Album"));
+        assert_eq!(info.album_position(), Some((1, 3)));
+    }
+
+    #[test]
+    fn track_without_cover() {
+        let info = PlaybackInfo::new(
+            audio_item(&format!("spotify:track:{TRACK_ID}"), vec![], track_fields()),
+            0,
+            true,
+        );
+
+        assert_eq!(info.thumbnail(), None);
+        assert!(info.url().is_some());
+    }
+
+    #[test]
+    fn episode() {
+        let info = PlaybackInfo::new(
+            audio_item(
+                &format!("spotify:episode:{EPISODE_ID}"),
+                cover(),
+                episode_fields(),
+            ),
+            0,
+            false,
+        );
+
+        assert!(info.is_episode());
+        assert_eq!(
+            info.url(),
+            Some(format!("https://open.spotify.com/episode/{EPISODE_ID}"))
+        );
+        assert_eq!(info.show_name().as_deref(), Some("Show"));
+        assert_eq!(info.published_at(), Some(1_700_000_000));
+        assert!(info.artists().is_none());
+        assert!(info.album_position().is_none());
+    }
+
+    #[test]
+    fn local_file() {
+        let info = PlaybackInfo::new(
+            audio_item(
+                "spotify:local:Artist:Album:Name:180",
+                vec![],
+                track_fields(),
+            ),
+            0,
+            true,
+        );
+
+        assert!(info.is_local());
+        assert_eq!(info.track_id_string(), None);
+        assert_eq!(info.url(), None);
+        assert_eq!(info.thumbnail(), None);
+        assert_eq!(info.recording_metadata().url, None);
+    }
 }
@@ -1,32 +1,59 @@
+pub mod controls;
 pub mod info;
+pub mod lyrics;
+pub mod output;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 use anyhow::Result;
-use info::PlaybackInfo;
+use controls::{Controls, SpotifyControls};
+use info::{PlaybackInfo, UpNext};
 use librespot::{
     connect::{config::ConnectConfig, spirc::Spirc},
     core::{
-        connection::AuthenticationError, http_client::HttpClientError, Session as SpotifySession,
-        SessionConfig,
+        connection::AuthenticationError, spotify_id::SpotifyItemType, Session as SpotifySession,
+        SessionConfig, SpotifyId,
     },
     discovery::Credentials,
-    metadata::Lyrics,
+    metadata::audio::AudioItem,
     playback::{
-        config::{Bitrate, PlayerConfig, VolumeCtrl},
+        config::{Bitrate, NormalisationType, PlayerConfig, VolumeCtrl},
         mixer::{self, MixerConfig},
         player::{Player as SpotifyPlayer, PlayerEvent as SpotifyPlayerEvent},
     },
 };
-use log::{error, trace};
-use songbird::{input::RawAdapter, tracks::TrackHandle, Call};
+use log::{error, info, trace};
+use lyrics::{LyricsRequest, TrackLyrics};
+use output::{Output, SongbirdOutput};
+use songbird::Call;
 use spoticord_audio::{
+    crossfade::Crossfader,
+    effects::{self, Effects},
+    limiter::Limiter,
+    loudness::{self, LoudnessMonitor},
+    recorder::{self, Recorder},
     sink::{SinkEvent, StreamSink},
-    stream::Stream,
+    speed::TimeStretch,
+    stream::{Stream, StreamStats},
 };
 use std::{
     io::Write,
+    path::PathBuf,
     sync::{atomic::AtomicBool, Arc},
+    time::Duration,
+};
+use tokio::{
+    sync::{mpsc, oneshot, watch, Mutex},
+    task::JoinHandle,
+    time::MissedTickBehavior,
+};
+
+pub use spoticord_audio::{
+    effects::{BassBoost, Effects, Equalizer},
+    loudness::Loudness,
+    recorder::RecordingFormat,
+    speed::{MAX_SPEED, MIN_SPEED},
 };
-use tokio::sync::{mpsc, oneshot, Mutex};
 
 #[derive(Debug)]
 enum PlayerCommand {
@@ -34,13 +61,119 @@ enum PlayerCommand {
     PreviousTrack,
     Pause,
     Play,
+    Seek(u32),
 
     GetPlaybackInfo(oneshot::Sender<Option<PlaybackInfo>>),
-    GetLyrics(oneshot::Sender<Option<Lyrics>>),
+    GetLyrics(oneshot::Sender<Option<TrackLyrics>>),
+    GetAudioStats(oneshot::Sender<AudioStats>),
+    GetLoudness(oneshot::Sender<Loudness>),
+
+    SetEffects(Effects),
+    GetEffects(oneshot::Sender<Effects>),
+    SetCrossfade(Duration),
+    StartRecording(
+        PathBuf,
+        RecordingFormat,
+        oneshot::Sender<std::io::Result<()>>,
+    ),
+    StopRecording(oneshot::Sender<bool>),
+    IsRecording(oneshot::Sender<bool>),
+    SetDucked(bool),
+    SetSpeed(f32),
+    GetSpeed(oneshot::Sender<f32>),
 
     Shutdown,
 }
 
+/// Audio preferences of the user that is using the player
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AudioOptions {
+    /// The streaming bitrate in kbps, the configured default bitrate is used if this is not set
+    pub bitrate: Option<u16>,
+
+    /// The type of volume normalization to apply, or None to disable normalization
+    pub normalization: Option<NormalizationType>,
+
+    /// The normalization pregain in dB
+    pub pregain: f64,
+
+    /// The length of the crossfade between tracks, or zero to disable crossfading
+    pub crossfade: Duration,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum NormalizationType {
+    Auto,
+    Album,
+    Track,
+}
+
+impl From<NormalizationType> for NormalisationType {
+    fn from(value: NormalizationType) -> Self {
+        match value {
+            NormalizationType::Auto => Self::Auto,
+            NormalizationType::Album => Self::Album,
+            NormalizationType::Track => Self::Track,
+        }
+    }
+}
+
+/// The sample rate of the audio that librespot produces
+const SAMPLE_RATE: usize = 44100;
+
+/// The size (in bytes) of a single stereo f32 sample
+const FRAME_SIZE: usize = 2 * std::mem::size_of::<f32>();
+
+/// How often the player checks whether librespot is still delivering audio
+const STALL_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long the player may go without receiving audio during playback before it attempts to recover
+const STALL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How many times the player tries to recover from a single stall before giving up
+const MAX_RECOVERY_ATTEMPTS: u32 = 3;
+
+/// How long it takes for the music to fade to or from the ducked volume
+const DUCK_FADE: Duration = Duration::from_millis(200);
+
+/// The amount of volume changes a duck fade consists of
+const DUCK_STEPS: u32 = 10;
+
+/// Diagnostics about the audio buffer between librespot and songbird
+#[derive(Debug, Clone, Copy)]
+pub struct AudioStats {
+    /// The amount of audio the buffer can hold
+    pub capacity: Duration,
+
+    /// The amount of audio that is currently buffered
+    pub buffered: Duration,
+
+    /// How often the buffer ran empty while Discord was requesting audio
+    pub underruns: u64,
+
+    /// The total amount of silence that was sent to Discord because the buffer was empty
+    pub silence: Duration,
+
+    /// How often librespot had to wait because the buffer was full
+    pub overruns: u64,
+}
+
+impl From<StreamStats> for AudioStats {
+    fn from(stats: StreamStats) -> Self {
+        Self {
+            capacity: bytes_to_duration(stats.capacity as u64),
+            buffered: bytes_to_duration(stats.buffered as u64),
+            underruns: stats.underruns,
+            silence: bytes_to_duration(stats.silence),
+            overruns: stats.overruns,
+        }
+    }
+}
+
+fn bytes_to_duration(bytes: u64) -> Duration {
+    Duration::from_millis(bytes * 1000 / (SAMPLE_RATE * FRAME_SIZE) as u64)
+}
+
 #[derive(Debug)]
 pub enum PlayerEvent {
     Pause,
@@ -48,13 +181,71 @@ pub enum PlayerEvent {
     Stopped,
     TrackChanged(Box<PlaybackInfo>),
     ConnectionReset,
+
+    /// Spotify has started loading the track that will be played after the current one
+    UpNextChanged,
+
+    /// No audio has been received from Spotify for a while, even though the track is playing
+    Stalled {
+        /// The position (in milliseconds) at which the audio stopped
+        position: u32,
+
+        /// The recovery attempt, starting at 1
+        attempt: u32,
+
+        /// Whether the track is being reloaded, `false` if the player has given up on recovering
+        recovering: bool,
+    },
+
+    /// An episode was paused, stopped or left, and could be resumed later
+    EpisodeProgress {
+        episode: SpotifyId,
+
+        /// The position (in milliseconds) at which the episode was left
+        position: u32,
+        duration: u32,
+    },
+}
+
+/// Keeps track of how long the player has gone without receiving audio from librespot
+#[derive(Default)]
+struct Stall {
+    /// The total amount of silence the stream had inserted during the previous check
+    silence: u64,
+
+    /// How long the stream has been starved since the last recovery attempt
+    starved: Duration,
+
+    /// The position at which the audio stopped, which is where playback is resumed
+    position: Option<u32>,
+
+    attempts: u32,
+}
+
+impl Stall {
+    fn reset(&mut self) {
+        *self = Self {
+            silence: self.silence,
+            ..Default::default()
+        };
+    }
 }
 
 pub struct Player {
     session: SpotifySession,
-    spirc: Spirc,
-    track: TrackHandle,
+    controls: Box<dyn Controls>,
+    output: Arc<dyn Output>,
     stream: Stream,
+    effects: watch::Sender<Effects>,
+    crossfade: watch::Sender<Duration>,
+    loudness: LoudnessMonitor,
+    recorder: Recorder,
+    ducking: Option<JoinHandle<()>>,
+    stall: Stall,
+
+    /// The playback speed for episodes, music is always played at normal speed
+    speed: f32,
+    stretch: watch::Sender<f32>,
 
     playback_info: Option<PlaybackInfo>,
 
@@ -74,19 +265,29 @@ impl Player {
         credentials: Credentials,
         call: Arc<Mutex<Call>>,
         device_name: impl Into<String>,
+        audio: AudioOptions,
     ) -> Result<(PlayerHandle, mpsc::Receiver<PlayerEvent>, Vec<u8>), librespot::core::Error> {
-        let (event_tx, event_rx) = mpsc::channel(16);
-
-        let mut call_lock = call.lock().await;
-        let stream = Stream::new();
+        let stream = Stream::with_capacity(
+            spoticord_config::get().audio.buffer_ms as usize * SAMPLE_RATE / 1000 * FRAME_SIZE,
+        );
 
         // Create songbird audio track
-        let adapter = RawAdapter::new(stream.clone(), 44100, 2);
-        let track = call_lock.play_only_input(adapter.into());
-        _ = track.pause();
+        let output = SongbirdOutput::new(&call, stream.clone()).await?;
+
+        Self::create_with_output(credentials, stream, Arc::new(output), device_name, audio).await
+    }
+
+    /// Create a player that plays its audio through `output`, which must read from `stream`
+    pub async fn create_with_output(
+        credentials: Credentials,
+        stream: Stream,
+        output: Arc<dyn Output>,
+        device_name: impl Into<String>,
+        audio: AudioOptions,
+    ) -> Result<(PlayerHandle, mpsc::Receiver<PlayerEvent>, Vec<u8>), librespot::core::Error> {
+        let (event_tx, event_rx) = mpsc::channel(16);
 
-        // Free call lock before creating session
-        drop(call_lock);
+        let config = spoticord_config::get();
 
         // Create librespot audio streamer
         let session = SpotifySession::new(SessionConfig::default(), None);
@@ -95,22 +296,53 @@ impl Player {
             ..Default::default()
         });
 
+        let bitrate = match audio.bitrate.unwrap_or(config.audio.bitrate) {
+            320 => Bitrate::Bitrate320,
+            _ => Bitrate::Bitrate160,
+        };
+
         let (tx_sink, rx_sink) = mpsc::unbounded_channel();
+        let (effects, chain) = effects::channel();
+        let (crossfade, crossfade_rx) = watch::channel(audio.crossfade);
+        let (tx_subscription, rx_subscription) = oneshot::channel();
+        let (stretch, stretch_rx) = watch::channel(1.0);
+        let (loudness, meter) = loudness::meter();
+        let limiter = config.audio.limiter.then(Limiter::new);
+        let (recorder, recording) = recorder::channel();
         let player = SpotifyPlayer::new(
             PlayerConfig {
-                // 96kbps causes audio key errors, so enjoy the quality upgrade
-                bitrate: Bitrate::Bitrate160,
+                bitrate,
+                normalisation: audio.normalization.is_some(),
+                normalisation_type: audio
+                    .normalization
+                    .unwrap_or(NormalizationType::Auto)
+                    .into(),
+                normalisation_pregain_db: audio.pregain,
                 ..Default::default()
             },
             session.clone(),
             mixer.get_soft_volume(),
             {
                 let stream = stream.clone();
-                move || Box::new(StreamSink::new(stream, tx_sink))
+                move || {
+                    Box::new(StreamSink::new(
+                        stream,
+                        tx_sink,
+                        chain,
+                        Crossfader::new(crossfade_rx, rx_subscription),
+                        TimeStretch::new(stretch_rx),
+                        meter,
+                        limiter,
+                        recording,
+                    ))
+                }
             },
         );
         let rx_player = player.get_player_event_channel();
 
+        // The crossfader needs its own event channel, as it has to stay in sync with the audio
+        _ = tx_subscription.send(player.get_player_event_channel());
+
         let device_name = device_name.into();
         let mut tries = 0;
 
@@ -118,7 +350,9 @@ impl Player {
             match Spirc::new(
                 ConnectConfig {
                     name: device_name.clone(),
-                    initial_volume: Some((0.75f32 * u16::MAX as f32) as u16),
+                    initial_volume: Some(
+                        (config.audio.initial_volume as f32 / 100.0 * u16::MAX as f32) as u16,
+                    ),
                     ..Default::default()
                 },
                 session.clone(),
@@ -141,7 +375,7 @@ impl Player {
                     }
 
                     tries += 1;
-                    if tries > 3 {
+                    if tries > config.audio.connect_retries {
                         error!("Failed to connect to Spirc: {why}");
 
                         return Err(why);
@@ -159,9 +393,17 @@ impl Player {
         let (tx, rx) = mpsc::channel(16);
         let player = Self {
             session,
-            spirc,
-            track,
+            controls: Box::new(SpotifyControls { spirc, player }),
+            output,
             stream,
+            effects,
+            crossfade,
+            loudness,
+            recorder,
+            ducking: None,
+            stall: Stall::default(),
+            speed: 1.0,
+            stretch,
 
             playback_info: None,
 
@@ -189,6 +431,9 @@ impl Player {
     }
 
     async fn run(mut self) {
+        let mut stall_check = tokio::time::interval(STALL_CHECK_INTERVAL);
+        stall_check.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
         loop {
             tokio::select! {
                 opt_command = self.commands.recv() => {
@@ -208,6 +453,10 @@ impl Player {
                     self.handle_sink_event(event).await;
                 }
 
+                _ = stall_check.tick() => {
+                    self.check_stall().await;
+                }
+
                 else => break,
             }
         }
@@ -220,13 +469,37 @@ impl Player {
 
     async fn handle_command(&mut self, command: PlayerCommand) {
         match command {
-            PlayerCommand::NextTrack => _ = self.spirc.next(),
-            PlayerCommand::PreviousTrack => _ = self.spirc.prev(),
-            PlayerCommand::Pause => _ = self.spirc.pause(),
-            PlayerCommand::Play => _ = self.spirc.play(),
+            PlayerCommand::NextTrack => self.controls.next(),
+            PlayerCommand::PreviousTrack => self.controls.previous(),
+            PlayerCommand::Pause => self.controls.pause(),
+            PlayerCommand::Play => self.controls.play(),
+            PlayerCommand::Seek(position) => self.controls.seek(position),
 
             PlayerCommand::GetPlaybackInfo(tx) => _ = tx.send(self.playback_info.clone()),
-            PlayerCommand::GetLyrics(tx) => self.get_lyrics(tx).await,
+            PlayerCommand::GetLyrics(tx) => self.get_lyrics(tx),
+            PlayerCommand::GetAudioStats(tx) => _ = tx.send(self.stream.stats().into()),
+            PlayerCommand::GetLoudness(tx) => _ = tx.send(self.loudness.get()),
+
+            PlayerCommand::SetEffects(effects) => _ = self.effects.send_replace(effects),
+            PlayerCommand::GetEffects(tx) => _ = tx.send(*self.effects.borrow()),
+            PlayerCommand::SetCrossfade(duration) => _ = self.crossfade.send_replace(duration),
+
+            PlayerCommand::StartRecording(directory, format, tx) => {
+                let track = self
+                    .playback_info
+                    .as_ref()
+                    .map(PlaybackInfo::recording_metadata);
+
+                _ = tx.send(self.recorder.start(directory, format, track));
+            }
+            PlayerCommand::StopRecording(tx) => _ = tx.send(self.recorder.stop()),
+            PlayerCommand::IsRecording(tx) => _ = tx.send(self.recorder.is_recording()),
+            PlayerCommand::SetDucked(ducked) => self.set_ducked(ducked),
+            PlayerCommand::SetSpeed(speed) => {
+                self.speed = speed.clamp(MIN_SPEED, MAX_SPEED);
+                self.apply_speed();
+            }
+            PlayerCommand::GetSpeed(tx) => _ = tx.send(self.speed),
 
             PlayerCommand::Shutdown => self.commands.close(),
         };
@@ -255,23 +528,54 @@ impl Player {
                 if let Some(playback_info) = self.playback_info.as_mut() {
                     playback_info.update_playback(position_ms, false);
                 }
+
+                self.report_progress(position_ms).await;
             }
             SpotifyPlayerEvent::Stopped { .. } | SpotifyPlayerEvent::SessionDisconnected { .. } => {
-                if let Err(why) = self.track.pause() {
-                    error!("Failed to pause songbird track: {why}");
+                if let Err(why) = self.output.pause() {
+                    error!("Failed to pause audio output: {why}");
+                }
+
+                if let Some(playback_info) = &self.playback_info {
+                    self.report_progress(playback_info.current_position()).await;
                 }
 
                 _ = self.events.send(PlayerEvent::Pause).await;
 
                 self.playback_info = None;
             }
+            SpotifyPlayerEvent::EndOfTrack { track_id, .. } => {
+                if let Some(playback_info) = self
+                    .playback_info
+                    .as_ref()
+                    .filter(|info| info.track_id() == track_id)
+                {
+                    self.report_progress(playback_info.duration()).await;
+                }
+            }
             SpotifyPlayerEvent::TrackChanged { audio_item } => {
+                if let Some(playback_info) = self
+                    .playback_info
+                    .as_ref()
+                    .filter(|info| info.track_id() != audio_item.track_id)
+                {
+                    self.report_progress(playback_info.current_position()).await;
+                }
+
                 if let Some(playback_info) = self.playback_info.as_mut() {
                     playback_info.update_track(*audio_item);
                 } else {
                     self.playback_info = Some(PlaybackInfo::new(*audio_item, 0, false));
                 }
 
+                self.apply_speed();
+
+                if let Some(playback_info) = &self.playback_info {
+                    self.recorder.set_track(playback_info.recording_metadata());
+                }
+
+                self.stall.reset();
+
                 _ = self
                     .events
                     .send(PlayerEvent::TrackChanged(Box::new(
@@ -279,48 +583,186 @@ impl Player {
                     )))
                     .await;
             }
+            SpotifyPlayerEvent::Preloading { track_id, .. } => {
+                // There is no metadata to retrieve for local files
+                if self.playback_info.is_none() || track_id.item_type == SpotifyItemType::Local {
+                    return;
+                }
+
+                // The metadata was just fetched by the player to preload the track, so this is usually cached
+                let up_next = match AudioItem::get_file(&self.session, track_id).await {
+                    Ok(audio_item) => Some(UpNext::new(&audio_item)),
+                    Err(why) => {
+                        error!("Failed to retrieve the next track: {why}");
+
+                        None
+                    }
+                };
+
+                if let Some(playback_info) = self.playback_info.as_mut() {
+                    playback_info.set_up_next(up_next);
+
+                    _ = self.events.send(PlayerEvent::UpNextChanged).await;
+                }
+            }
             _ => {}
         }
     }
 
+    /// Detect whether librespot stopped delivering audio mid-track, and try to recover by reloading the track
+    async fn check_stall(&mut self) {
+        let silence = self.stream.stats().silence;
+        let inserted = bytes_to_duration(silence.saturating_sub(self.stall.silence));
+        self.stall.silence = silence;
+
+        let Some(playback_info) = self.playback_info.as_ref().filter(|info| info.playing()) else {
+            self.stall.reset();
+            return;
+        };
+
+        // Silence is only inserted when the stream runs out of audio, so a bit of it is normal after seeking
+        if inserted < STALL_CHECK_INTERVAL * 3 / 4 {
+            if self.stall.attempts > 0 {
+                info!("Playback recovered after stalling");
+            }
+
+            self.stall.reset();
+            return;
+        }
+
+        let track_id = playback_info.track_id();
+        let local = playback_info.is_local();
+        let position = *self.stall.position.get_or_insert_with(|| {
+            playback_info
+                .current_position()
+                .saturating_sub(inserted.as_millis() as u32)
+        });
+
+        self.stall.starved += STALL_CHECK_INTERVAL;
+
+        if self.stall.starved < STALL_TIMEOUT || self.stall.attempts > MAX_RECOVERY_ATTEMPTS {
+            return;
+        }
+
+        self.stall.starved = Duration::ZERO;
+        self.stall.attempts += 1;
+
+        let attempt = self.stall.attempts;
+
+        // Local files cannot be loaded by librespot, so reloading them would only fail again
+        let recovering = attempt <= MAX_RECOVERY_ATTEMPTS && !local;
+
+        if !recovering {
+            self.stall.attempts = MAX_RECOVERY_ATTEMPTS + 1;
+        }
+
+        _ = self
+            .events
+            .send(PlayerEvent::Stalled {
+                position,
+                attempt,
+                recovering,
+            })
+            .await;
+
+        if recovering {
+            // Loading the current track again requests the audio from Spotify from scratch
+            self.controls.load(track_id, position);
+        }
+    }
+
+    /// Fade the music to the configured duck volume, or back to full volume
+    fn set_ducked(&mut self, ducked: bool) {
+        if let Some(fade) = self.ducking.take() {
+            fade.abort();
+        }
+
+        let target = if ducked {
+            spoticord_config::get().audio.duck_volume as f32 / 100.0
+        } else {
+            1.0
+        };
+
+        let output = self.output.clone();
+
+        self.ducking = Some(tokio::spawn(async move {
+            // Start from the current volume, as a previous fade might have been interrupted
+            let start = output.volume();
+
+            for step in 1..=DUCK_STEPS {
+                tokio::time::sleep(DUCK_FADE / DUCK_STEPS).await;
+
+                let volume = start + (target - start) * step as f32 / DUCK_STEPS as f32;
+                if output.set_volume(volume).is_err() {
+                    return;
+                }
+            }
+        }));
+    }
+
+    /// Let the session know where the current episode was left, so it can be resumed later
+    async fn report_progress(&self, position: u32) {
+        let Some(playback_info) = self.playback_info.as_ref().filter(|info| info.is_episode())
+        else {
+            return;
+        };
+
+        _ = self
+            .events
+            .send(PlayerEvent::EpisodeProgress {
+                episode: playback_info.track_id(),
+                position,
+                duration: playback_info.duration(),
+            })
+            .await;
+    }
+
+    /// Play episodes at the requested speed, and everything else at normal speed
+    fn apply_speed(&mut self) {
+        let speed = match &self.playback_info {
+            Some(playback_info) if playback_info.is_episode() => self.speed,
+            _ => 1.0,
+        };
+
+        self.stretch.send_replace(speed);
+
+        if let Some(playback_info) = self.playback_info.as_mut() {
+            playback_info.set_speed(speed);
+        }
+    }
+
     async fn handle_sink_event(&self, event: SinkEvent) {
         if let SinkEvent::Start = event {
-            if let Err(why) = self.track.play() {
-                error!("Failed to resume songbird track: {why}");
+            if let Err(why) = self.output.play() {
+                error!("Failed to resume audio output: {why}");
             }
         }
     }
 
-    /// Grab the lyrics for the current active track from Spotify.
+    /// Grab the lyrics for the current active track from the lyrics cache, or the lyrics providers if they are not cached.
     ///
     /// This might return None if nothing is being played, or the current song does not have any lyrics.
-    async fn get_lyrics(&self, tx: oneshot::Sender<Option<Lyrics>>) {
+    /// The providers can take a while to respond, so the lyrics are looked up next to the player instead of blocking it.
+    fn get_lyrics(&self, tx: oneshot::Sender<Option<TrackLyrics>>) {
         let Some(playback_info) = &self.playback_info else {
             _ = tx.send(None);
             return;
         };
 
-        let lyrics = match Lyrics::get(&self.session, &playback_info.track_id()).await {
-            Ok(lyrics) => lyrics,
-            Err(why) => {
-                // Ignore 404 errors
-                match why.error.downcast_ref::<HttpClientError>() {
-                    Some(HttpClientError::StatusCode(code)) if code.as_u16() == 404 => {}
-                    _ => error!("Failed to get lyrics: {why}"),
-                }
+        let session = self.session.clone();
+        let request = LyricsRequest::new(playback_info);
 
-                _ = tx.send(None);
-                return;
-            }
-        };
+        tokio::spawn(async move {
+            let lyrics = lyrics::cache().get(&session, &request).await;
 
-        _ = tx.send(Some(lyrics));
+            _ = tx.send(lyrics);
+        });
     }
 }
 
 impl Drop for Player {
     fn drop(&mut self) {
-        _ = self.spirc.shutdown();
+        self.controls.shutdown();
         _ = self.stream.flush();
     }
 }
@@ -347,6 +789,11 @@ impl PlayerHandle {
         _ = self.commands.send(PlayerCommand::Pause).await;
     }
 
+    /// Continue playing the current track at `position_ms`
+    pub async fn seek(&self, position_ms: u32) {
+        _ = self.commands.send(PlayerCommand::Seek(position_ms)).await;
+    }
+
     pub async fn play(&self) {
         _ = self.commands.send(PlayerCommand::Play).await;
     }
@@ -360,14 +807,340 @@ impl PlayerHandle {
         Ok(rx.await?)
     }
 
-    pub async fn get_lyrics(&self) -> Result<Option<Lyrics>> {
+    pub async fn get_lyrics(&self) -> Result<Option<TrackLyrics>> {
         let (tx, rx) = oneshot::channel();
         self.commands.send(PlayerCommand::GetLyrics(tx)).await?;
 
         Ok(rx.await?)
     }
 
+    pub async fn audio_stats(&self) -> Result<AudioStats> {
+        let (tx, rx) = oneshot::channel();
+        self.commands.send(PlayerCommand::GetAudioStats(tx)).await?;
+
+        Ok(rx.await?)
+    }
+
+    pub async fn loudness(&self) -> Result<Loudness> {
+        let (tx, rx) = oneshot::channel();
+        self.commands.send(PlayerCommand::GetLoudness(tx)).await?;
+
+        Ok(rx.await?)
+    }
+
+    pub async fn set_effects(&self, effects: Effects) {
+        _ = self.commands.send(PlayerCommand::SetEffects(effects)).await;
+    }
+
+    pub async fn effects(&self) -> Result<Effects> {
+        let (tx, rx) = oneshot::channel();
+        self.commands.send(PlayerCommand::GetEffects(tx)).await?;
+
+        Ok(rx.await?)
+    }
+
+    pub async fn set_crossfade(&self, duration: Duration) {
+        _ = self
+            .commands
+            .send(PlayerCommand::SetCrossfade(duration))
+            .await;
+    }
+
+    /// Start recording the audio of this player into `directory`
+    pub async fn start_recording(
+        &self,
+        directory: impl Into<PathBuf>,
+        format: RecordingFormat,
+    ) -> Result<()> {
+        let (tx, rx) = oneshot::channel();
+        self.commands
+            .send(PlayerCommand::StartRecording(directory.into(), format, tx))
+            .await?;
+
+        Ok(rx.await??)
+    }
+
+    /// Stop recording, returns whether a recording was in progress
+    pub async fn stop_recording(&self) -> Result<bool> {
+        let (tx, rx) = oneshot::channel();
+        self.commands.send(PlayerCommand::StopRecording(tx)).await?;
+
+        Ok(rx.await?)
+    }
+
+    pub async fn is_recording(&self) -> Result<bool> {
+        let (tx, rx) = oneshot::channel();
+        self.commands.send(PlayerCommand::IsRecording(tx)).await?;
+
+        Ok(rx.await?)
+    }
+
+    /// Lower the volume of the music while something else is being played over it
+    pub async fn set_ducked(&self, ducked: bool) {
+        _ = self.commands.send(PlayerCommand::SetDucked(ducked)).await;
+    }
+
+    /// Change the playback speed of episodes, which is clamped between 0.5 and 2
+    pub async fn set_speed(&self, speed: f32) {
+        _ = self.commands.send(PlayerCommand::SetSpeed(speed)).await;
+    }
+
+    pub async fn speed(&self) -> Result<f32> {
+        let (tx, rx) = oneshot::channel();
+        self.commands.send(PlayerCommand::GetSpeed(tx)).await?;
+
+        Ok(rx.await?)
+    }
+
     pub async fn shutdown(&self) {
         _ = self.commands.send(PlayerCommand::Shutdown).await;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use librespot::metadata::{
+        artist::ArtistsWithRole,
+        audio::{AudioFiles, UniqueFields},
+    };
+    use spoticord_audio::sink::SinkEvent;
+    use tokio::time::{sleep, timeout};
+
+    use super::*;
+    use crate::{
+        output::CaptureOutput,
+        testing::{ControlCommand, FakeSpotify},
+    };
+
+    const TRACK: &str = "spotify:track:4uLU6hMCjMI75M1A2tKUQC";
+    const LOCAL: &str = "spotify:local:Artist:Album:Name:180";
+
+    /// One second of audio, in bytes
+    const SECOND: usize = 44100 * 2 * 4;
+
+    fn id(uri: &str) -> SpotifyId {
+        SpotifyId::from_uri(uri).expect("invalid uri")
+    }
+
+    fn track_changed(uri: &str) -> SpotifyPlayerEvent {
+        SpotifyPlayerEvent::TrackChanged {
+            audio_item: Box::new(AudioItem {
+                track_id: id(uri),
+                uri: uri.to_string(),
+                files: AudioFiles::default(),
+                name: "Name".to_string(),
+                covers: vec![],
+                language: vec![],
+                duration_ms: 180_000,
+                is_explicit: false,
+                availability: Ok(()),
+                alternatives: None,
+                unique_fields: UniqueFields::Track {
+                    artists: ArtistsWithRole::default(),
+                    album: "Album".to_string(),
+                    album_artists: vec![],
+                    popularity: 50,
+                    number: 3,
+                    disc_number: 1,
+                },
+            }),
+        }
+    }
+
+    fn playing(uri: &str, position_ms: u32) -> SpotifyPlayerEvent {
+        SpotifyPlayerEvent::Playing {
+            play_request_id: 0,
+            track_id: id(uri),
+            position_ms,
+        }
+    }
+
+    fn paused(uri: &str, position_ms: u32) -> SpotifyPlayerEvent {
+        SpotifyPlayerEvent::Paused {
+            play_request_id: 0,
+            track_id: id(uri),
+            position_ms,
+        }
+    }
+
+    fn stopped(uri: &str) -> SpotifyPlayerEvent {
+        SpotifyPlayerEvent::Stopped {
+            play_request_id: 0,
+            track_id: id(uri),
+        }
+    }
+
+    /// Wait for the player to have handled a sink event, which has no response to wait for
+    async fn until(condition: impl Fn() -> bool) {
+        timeout(Duration::from_secs(1), async {
+            while !condition() {
+                sleep(Duration::from_millis(1)).await;
+            }
+        })
+        .await
+        .expect("condition was never met");
+    }
+
+    fn player(
+        stream: &Stream,
+    ) -> (
+        PlayerHandle,
+        mpsc::Receiver<PlayerEvent>,
+        FakeSpotify,
+        Arc<CaptureOutput>,
+    ) {
+        let (output, _) = CaptureOutput::memory(stream.clone());
+        let output = Arc::new(output);
+
+        let (handle, events, spotify) =
+            Player::fake(stream.clone(), output.clone(), AudioOptions::default());
+
+        (handle, events, spotify, output)
+    }
+
+    /// Let librespot stop delivering audio while `uri` is playing, until the player gives up waiting for it
+    async fn starve(uri: &str) -> (PlayerHandle, mpsc::Receiver<PlayerEvent>, FakeSpotify) {
+        let mut stream = Stream::new();
+        let (handle, mut events, spotify, _output) = player(&stream);
+
+        spotify.send(track_changed(uri));
+        spotify.send(playing(uri, 10_000));
+
+        assert!(matches!(
+            events.recv().await,
+            Some(PlayerEvent::TrackChanged(_))
+        ));
+        assert!(matches!(events.recv().await, Some(PlayerEvent::Play)));
+
+        // Read in between the stall checks, so every check sees exactly one second of silence
+        sleep(STALL_CHECK_INTERVAL / 2).await;
+
+        let mut buffer = vec![0; SECOND];
+        for _ in 0..STALL_TIMEOUT.as_secs() {
+            assert_eq!(stream.read(&mut buffer).unwrap(), SECOND);
+
+            sleep(STALL_CHECK_INTERVAL).await;
+        }
+
+        (handle, events, spotify)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stalled_track_is_reloaded() {
+        let (_handle, mut events, spotify) = starve(TRACK).await;
+
+        let Some(PlayerEvent::Stalled {
+            position,
+            attempt,
+            recovering,
+        }) = events.recv().await
+        else {
+            panic!("expected the player to stall");
+        };
+
+        assert_eq!(attempt, 1);
+        assert!(recovering);
+
+        // Playback resumes where the audio stopped, before the silence was inserted
+        assert!((9_000..10_000).contains(&position), "{position}");
+        assert_eq!(
+            spotify.controls().commands(),
+            [ControlCommand::Load {
+                track_id: id(TRACK),
+                position_ms: position,
+            }]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stalled_local_file_is_not_reloaded() {
+        let (_handle, mut events, spotify) = starve(LOCAL).await;
+
+        assert!(matches!(
+            events.recv().await,
+            Some(PlayerEvent::Stalled {
+                attempt: 1,
+                recovering: false,
+                ..
+            })
+        ));
+        assert!(spotify.controls().commands().is_empty());
+    }
+
+    #[tokio::test]
+    async fn playback_events() {
+        let stream = Stream::new();
+        let (handle, mut events, spotify, output) = player(&stream);
+
+        spotify.send(track_changed(TRACK));
+
+        let Some(PlayerEvent::TrackChanged(info)) = events.recv().await else {
+            panic!("expected the track to change");
+        };
+
+        assert_eq!(info.track_id(), id(TRACK));
+        assert!(!info.playing());
+        assert!(!output.is_playing());
+
+        // The output only starts once librespot starts writing audio
+        spotify.sink(SinkEvent::Start);
+        until(|| output.is_playing()).await;
+
+        spotify.send(playing(TRACK, 1_000));
+        assert!(matches!(events.recv().await, Some(PlayerEvent::Play)));
+
+        let info = handle.playback_info().await.unwrap().unwrap();
+        assert!(info.playing());
+        assert!(info.current_position() >= 1_000);
+
+        spotify.send(paused(TRACK, 2_000));
+        assert!(matches!(events.recv().await, Some(PlayerEvent::Pause)));
+
+        let info = handle.playback_info().await.unwrap().unwrap();
+        assert!(!info.playing());
+        assert_eq!(info.current_position(), 2_000);
+
+        // The sink stopping is not a reason to stop the output, as librespot might only be switching tracks
+        spotify.sink(SinkEvent::Stop);
+        spotify.send(playing(TRACK, 2_000));
+        assert!(matches!(events.recv().await, Some(PlayerEvent::Play)));
+        sleep(Duration::from_millis(50)).await;
+        assert!(output.is_playing());
+
+        spotify.send(stopped(TRACK));
+        assert!(matches!(events.recv().await, Some(PlayerEvent::Pause)));
+        assert!(!output.is_playing());
+        assert!(handle.playback_info().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn track_changes_keep_playback_state() {
+        const NEXT: &str = "spotify:track:6rqhFgbbKwnb9MLmUQDhG6";
+
+        let stream = Stream::new();
+        let (handle, mut events, spotify, _output) = player(&stream);
+
+        spotify.send(track_changed(TRACK));
+        spotify.send(playing(TRACK, 5_000));
+        assert!(matches!(
+            events.recv().await,
+            Some(PlayerEvent::TrackChanged(_))
+        ));
+        assert!(matches!(events.recv().await, Some(PlayerEvent::Play)));
+
+        spotify.send(track_changed(NEXT));
+
+        let Some(PlayerEvent::TrackChanged(info)) = events.recv().await else {
+            panic!("expected the track to change");
+        };
+
+        assert_eq!(info.track_id(), id(NEXT));
+        assert!(info.playing());
+        assert_eq!(
+            handle.playback_info().await.unwrap().unwrap().track_id(),
+            id(NEXT)
+        );
+    }
+}
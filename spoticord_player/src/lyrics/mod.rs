@@ -0,0 +1,736 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    time::Duration,
+};
+
+use librespot::core::{spotify_id::SpotifyItemType, Session as SpotifySession, SpotifyId};
+use log::{error, warn};
+use redis::{aio::ConnectionManager, AsyncCommands, Client};
+use serde::{Deserialize, Serialize};
+use spoticord_config::LyricsSource;
+use tokio::{sync::OnceCell, time::Instant};
+
+use crate::info::PlaybackInfo;
+
+mod provider;
+
+pub use provider::{
+    HttpLyrics, LocalLyrics, Lrclib, LyricsApi, LyricsProvider, ProviderFuture, SpotifyLyrics,
+};
+
+static CACHE: OnceLock<LyricsCache> = OnceLock::new();
+
+/// Set up the lyrics cache that is shared between all players.
+///
+/// This must be called before the first player is created, otherwise a cache without backing is used.
+pub fn init(backing: Option<Box<dyn LyricsBacking>>) {
+    if CACHE.set(LyricsCache::from_config(backing)).is_err() {
+        warn!("The lyrics cache has already been initialized");
+    }
+}
+
+/// Retrieve the lyrics cache that is shared between all players
+pub fn cache() -> &'static LyricsCache {
+    CACHE.get_or_init(|| LyricsCache::from_config(None))
+}
+
+/// The future that is returned by the methods of a [`LyricsBacking`]
+pub type BackingFuture<'a, T> = Pin<Box<dyn Future<Output = anyhow::Result<T>> + Send + 'a>>;
+
+/// The lyrics of a track, as they were found by one of the providers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackLyrics {
+    pub lines: Vec<LyricsLine>,
+
+    /// Whether the lines have timing information
+    pub synced: bool,
+
+    /// Who wrote down the lyrics, as reported by the provider, which may be empty
+    pub provider: String,
+
+    /// The name of the provider that found the lyrics
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LyricsLine {
+    pub start_time_ms: u32,
+    pub words: String,
+
+    /// The syllables of this line, which is empty if the provider has no syllable timing
+    #[serde(default)]
+    pub syllables: Vec<Syllable>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Syllable {
+    pub start_time_ms: u32,
+
+    /// The amount of characters of the line that make up this syllable
+    pub num_chars: usize,
+}
+
+impl TrackLyrics {
+    /// Describe where the lyrics came from, like "Musixmatch via Spotify"
+    pub fn attribution(&self) -> String {
+        if self.provider.is_empty() || self.provider == self.source {
+            self.source.clone()
+        } else {
+            format!("{} via {}", self.provider, self.source)
+        }
+    }
+}
+
+/// The track that lyrics are looked up for
+#[derive(Debug, Clone)]
+pub struct LyricsRequest {
+    pub track_id: SpotifyId,
+    pub name: String,
+    pub artists: Vec<String>,
+    pub album: Option<String>,
+    pub duration_ms: u32,
+}
+
+impl LyricsRequest {
+    pub fn new(playback_info: &PlaybackInfo) -> Self {
+        Self {
+            track_id: playback_info.track_id(),
+            name: playback_info.name(),
+            artists: playback_info
+                .artists()
+                .map(|artists| artists.0.into_iter().map(|artist| artist.name).collect())
+                .unwrap_or_default(),
+            album: playback_info.album_name(),
+            duration_ms: playback_info.duration(),
+        }
+    }
+
+    /// Whether the track is a local file, which only has a name and no Spotify id
+    pub fn is_local(&self) -> bool {
+        self.track_id.item_type == SpotifyItemType::Local
+    }
+}
+
+/// Lyrics as they are kept by a [`LyricsBacking`]
+#[derive(Debug, Clone)]
+pub enum StoredLyrics {
+    /// The lyrics, serialized as JSON
+    Found(Vec<u8>),
+
+    /// The track has no lyrics
+    Missing,
+}
+
+/// Storage outside of the process that lyrics are cached in, which is consulted when lyrics are not cached in memory.
+///
+/// Backings keep lyrics across restarts, and can be shared between multiple instances of Spoticord.
+pub trait LyricsBacking: Send + Sync {
+    /// Retrieve the lyrics of a track, or `None` if they are not cached or have expired
+    fn get<'a>(&'a self, track_id: &'a str) -> BackingFuture<'a, Option<StoredLyrics>>;
+
+    /// Cache the lyrics of a track for the given amount of time
+    fn set<'a>(
+        &'a self,
+        track_id: &'a str,
+        lyrics: &'a StoredLyrics,
+        ttl: Duration,
+    ) -> BackingFuture<'a, ()>;
+}
+
+/// A cache for the lyrics of tracks, which is shared between all players.
+///
+/// Lyrics that are not cached are looked up in the providers in order, until one of them has lyrics for the track.
+///
+/// Both lyrics and the absence of lyrics are cached, so tracks without lyrics aren't requested over and over again.
+/// When the cache is full, the entry that was used the longest time ago is evicted. Players that ask for the lyrics
+/// of the same track while they are being looked up wait for that lookup, instead of asking the providers again.
+///
+/// Clones share the same cache.
+#[derive(Clone)]
+pub struct LyricsCache(Arc<Inner>);
+
+struct Inner {
+    entries: Mutex<Entries>,
+    backing: Option<Box<dyn LyricsBacking>>,
+    providers: Vec<Box<dyn LyricsProvider>>,
+
+    capacity: usize,
+    ttl: Duration,
+    negative_ttl: Duration,
+
+    hits: AtomicU64,
+    negative_hits: AtomicU64,
+    backing_hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+#[derive(Default)]
+struct Entries {
+    map: HashMap<SpotifyId, Entry>,
+
+    /// Incremented on every access, used to find the least recently used entry
+    clock: u64,
+
+    /// Lookups that are in progress, which are shared by everyone asking for the same track
+    pending: HashMap<SpotifyId, Arc<OnceCell<Option<TrackLyrics>>>>,
+}
+
+struct Entry {
+    lyrics: Option<TrackLyrics>,
+    expires: Instant,
+    used: u64,
+}
+
+/// A snapshot of how well the lyrics cache is performing
+#[derive(Debug, Clone, Copy)]
+pub struct LyricsCacheStats {
+    pub entries: usize,
+    pub capacity: usize,
+
+    /// Lookups that were answered with lyrics from memory
+    pub hits: u64,
+
+    /// Lookups that were answered from memory with the knowledge that a track has no lyrics
+    pub negative_hits: u64,
+
+    /// Lookups that were answered by the backing
+    pub backing_hits: u64,
+
+    /// Lookups that had to go to the providers
+    pub misses: u64,
+
+    /// Entries that were removed to make room for new ones
+    pub evictions: u64,
+}
+
+impl LyricsCacheStats {
+    /// The fraction of lookups that did not have to go to the providers
+    pub fn hit_rate(&self) -> f64 {
+        let hits = self.hits + self.negative_hits + self.backing_hits;
+        let total = hits + self.misses;
+
+        if total == 0 {
+            0.0
+        } else {
+            hits as f64 / total as f64
+        }
+    }
+}
+
+impl LyricsCache {
+    pub fn new(
+        capacity: usize,
+        ttl: Duration,
+        negative_ttl: Duration,
+        backing: Option<Box<dyn LyricsBacking>>,
+        providers: Vec<Box<dyn LyricsProvider>>,
+    ) -> Self {
+        Self(Arc::new(Inner {
+            entries: Mutex::new(Entries::default()),
+            backing,
+            providers,
+
+            capacity: capacity.max(1),
+            ttl,
+            negative_ttl,
+
+            hits: AtomicU64::new(0),
+            negative_hits: AtomicU64::new(0),
+            backing_hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }))
+    }
+
+    /// Create a cache using the sizes, TTLs and providers from the configuration
+    pub fn from_config(backing: Option<Box<dyn LyricsBacking>>) -> Self {
+        let config = spoticord_config::get();
+
+        let providers = config
+            .lyrics
+            .providers
+            .iter()
+            .filter_map(|source| -> Option<Box<dyn LyricsProvider>> {
+                match source {
+                    LyricsSource::Spotify => Some(Box::new(SpotifyLyrics)),
+                    LyricsSource::Local => {
+                        Some(Box::new(LocalLyrics::new(&config.lyrics.local_dir)))
+                    }
+                    LyricsSource::Lrclib => {
+                        match HttpLyrics::new(&config.lyrics.lrclib_url, Lrclib) {
+                            Ok(provider) => Some(Box::new(provider)),
+                            Err(why) => {
+                                error!("Failed to set up the LRCLIB lyrics provider: {why}");
+                                None
+                            }
+                        }
+                    }
+                }
+            })
+            .collect();
+
+        Self::new(
+            config.lyrics.cache_size,
+            Duration::from_secs(config.lyrics.cache_ttl),
+            Duration::from_secs(config.lyrics.cache_negative_ttl),
+            backing,
+            providers,
+        )
+    }
+
+    /// Retrieve the lyrics of a track, looking them up in the providers if they are not cached.
+    ///
+    /// This returns None if the track does not have any lyrics, or if the lyrics could not be retrieved.
+    pub async fn get(
+        &self,
+        session: &SpotifySession,
+        track: &LyricsRequest,
+    ) -> Option<TrackLyrics> {
+        let track_id = track.track_id;
+
+        // Local files all share the same empty id, so their lyrics can't be told apart in the cache
+        if track.is_local() {
+            return self.lookup(session, track).await.0;
+        }
+
+        let pending = {
+            let mut entries = self.entries();
+
+            if let Some(lyrics) = self.get_memory(&mut entries, track_id) {
+                return lyrics;
+            }
+
+            entries.pending.entry(track_id).or_default().clone()
+        };
+
+        let lyrics = pending
+            .get_or_init(|| self.fetch(session, track))
+            .await
+            .clone();
+
+        let mut entries = self.entries();
+        if entries
+            .pending
+            .get(&track_id)
+            .is_some_and(|current| Arc::ptr_eq(current, &pending))
+        {
+            entries.pending.remove(&track_id);
+        }
+
+        lyrics
+    }
+
+    /// Retrieve lyrics that are not in memory from the backing or the providers, and cache them
+    async fn fetch(&self, session: &SpotifySession, track: &LyricsRequest) -> Option<TrackLyrics> {
+        let track_id = track.track_id;
+        let key = track_id.to_base62().ok()?;
+
+        if let Some(backing) = &self.0.backing {
+            match backing.get(&key).await {
+                Ok(Some(stored)) => {
+                    if let Ok(lyrics) = parse(&stored) {
+                        self.0.backing_hits.fetch_add(1, Ordering::Relaxed);
+                        self.insert(track_id, lyrics.clone());
+
+                        return lyrics;
+                    }
+                }
+                Ok(None) => {}
+                Err(why) => warn!("Failed to retrieve lyrics from the cache backing: {why}"),
+            }
+        }
+
+        self.0.misses.fetch_add(1, Ordering::Relaxed);
+
+        let (lyrics, failed) = self.lookup(session, track).await;
+
+        // A provider that failed might have had lyrics, so don't remember that the track has none
+        if lyrics.is_none() && failed {
+            return None;
+        }
+
+        self.insert(track_id, lyrics.clone());
+
+        if let Some(backing) = &self.0.backing {
+            let (stored, ttl) = match &lyrics {
+                Some(lyrics) => match serde_json::to_vec(lyrics) {
+                    Ok(data) => (StoredLyrics::Found(data), self.0.ttl),
+                    Err(why) => {
+                        error!("Failed to serialize lyrics: {why}");
+                        return lyrics;
+                    }
+                },
+                None => (StoredLyrics::Missing, self.0.negative_ttl),
+            };
+
+            if let Err(why) = backing.set(&key, &stored, ttl).await {
+                warn!("Failed to store lyrics in the cache backing: {why}");
+            }
+        }
+
+        lyrics
+    }
+
+    pub fn stats(&self) -> LyricsCacheStats {
+        LyricsCacheStats {
+            entries: self.entries().map.len(),
+            capacity: self.0.capacity,
+            hits: self.0.hits.load(Ordering::Relaxed),
+            negative_hits: self.0.negative_hits.load(Ordering::Relaxed),
+            backing_hits: self.0.backing_hits.load(Ordering::Relaxed),
+            misses: self.0.misses.load(Ordering::Relaxed),
+            evictions: self.0.evictions.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Ask the providers for lyrics in order, also returning whether any of them failed
+    async fn lookup(
+        &self,
+        session: &SpotifySession,
+        track: &LyricsRequest,
+    ) -> (Option<TrackLyrics>, bool) {
+        let mut failed = false;
+
+        for provider in &self.0.providers {
+            match provider.get(session, track).await {
+                Ok(Some(lyrics)) => return (Some(lyrics), failed),
+                Ok(None) => {}
+                Err(why) => {
+                    error!("Failed to get lyrics from {}: {why}", provider.name());
+                    failed = true;
+                }
+            }
+        }
+
+        (None, failed)
+    }
+
+    /// Look up lyrics in memory, where `Some(None)` means the track is known to have no lyrics
+    fn get_memory(
+        &self,
+        entries: &mut Entries,
+        track_id: SpotifyId,
+    ) -> Option<Option<TrackLyrics>> {
+        let now = Instant::now();
+
+        entries.clock += 1;
+        let clock = entries.clock;
+
+        let entry = entries.map.get_mut(&track_id)?;
+
+        if entry.expires <= now {
+            entries.map.remove(&track_id);
+            return None;
+        }
+
+        entry.used = clock;
+
+        let counter = match entry.lyrics {
+            Some(_) => &self.0.hits,
+            None => &self.0.negative_hits,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        Some(entry.lyrics.clone())
+    }
+
+    fn insert(&self, track_id: SpotifyId, lyrics: Option<TrackLyrics>) {
+        let mut entries = self.entries();
+        let now = Instant::now();
+
+        if entries.map.len() >= self.0.capacity && !entries.map.contains_key(&track_id) {
+            // Make room by dropping expired entries first, and the least recently used entry otherwise
+            entries.map.retain(|_, entry| entry.expires > now);
+
+            if entries.map.len() >= self.0.capacity {
+                let oldest = entries
+                    .map
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.used)
+                    .map(|(track_id, _)| *track_id);
+
+                if let Some(oldest) = oldest {
+                    entries.map.remove(&oldest);
+                    self.0.evictions.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+
+        let ttl = match lyrics {
+            Some(_) => self.0.ttl,
+            None => self.0.negative_ttl,
+        };
+
+        entries.clock += 1;
+        let used = entries.clock;
+
+        entries.map.insert(
+            track_id,
+            Entry {
+                lyrics,
+                expires: now + ttl,
+                used,
+            },
+        );
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, Entries> {
+        self.0.entries.lock().expect("Mutex was poisoned")
+    }
+}
+
+fn parse(stored: &StoredLyrics) -> serde_json::Result<Option<TrackLyrics>> {
+    match stored {
+        StoredLyrics::Found(data) => serde_json::from_slice(data).map(Some),
+        StoredLyrics::Missing => Ok(None),
+    }
+}
+
+/// Caches lyrics in a redis-server instance
+pub struct RedisLyrics {
+    client: Client,
+
+    /// Connected on first use, after which the connection is shared and reconnects by itself
+    connection: OnceCell<ConnectionManager>,
+}
+
+impl RedisLyrics {
+    pub fn new(url: impl AsRef<str>) -> redis::RedisResult<Self> {
+        Ok(Self {
+            client: Client::open(url.as_ref())?,
+            connection: OnceCell::new(),
+        })
+    }
+
+    fn key(track_id: &str) -> String {
+        format!("spoticord-lyrics:{track_id}")
+    }
+
+    async fn connection(&self) -> redis::RedisResult<ConnectionManager> {
+        self.connection
+            .get_or_try_init(|| ConnectionManager::new(self.client.clone()))
+            .await
+            .cloned()
+    }
+}
+
+impl LyricsBacking for RedisLyrics {
+    fn get<'a>(&'a self, track_id: &'a str) -> BackingFuture<'a, Option<StoredLyrics>> {
+        Box::pin(async move {
+            let mut connection = self.connection().await?;
+            let value: Option<Vec<u8>> = connection.get(Self::key(track_id)).await?;
+
+            // Tracks without lyrics are stored as an empty value
+            Ok(value.map(|value| {
+                if value.is_empty() {
+                    StoredLyrics::Missing
+                } else {
+                    StoredLyrics::Found(value)
+                }
+            }))
+        })
+    }
+
+    fn set<'a>(
+        &'a self,
+        track_id: &'a str,
+        lyrics: &'a StoredLyrics,
+        ttl: Duration,
+    ) -> BackingFuture<'a, ()> {
+        Box::pin(async move {
+            let value = match lyrics {
+                StoredLyrics::Found(data) => data.as_slice(),
+                StoredLyrics::Missing => &[],
+            };
+
+            let mut connection = self.connection().await?;
+            connection
+                .set_ex::<_, _, ()>(Self::key(track_id), value, ttl.as_secs())
+                .await?;
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use librespot::core::SessionConfig;
+    use tokio::time::{advance, sleep};
+
+    use super::*;
+
+    const WITH_LYRICS: &str = "spotify:track:4uLU6hMCjMI75M1A2tKUQC";
+    const WITHOUT_LYRICS: &str = "spotify:track:6rqhFgbbKwnb9MLmUQDhG6";
+    const OTHER: &str = "spotify:track:1301WleyT98MSxVHPZCA6M";
+
+    const TTL: Duration = Duration::from_secs(600);
+    const NEGATIVE_TTL: Duration = Duration::from_secs(60);
+
+    /// Has lyrics for every track except [`WITHOUT_LYRICS`], and counts how often it is asked
+    #[derive(Clone, Default)]
+    struct CountingProvider {
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl CountingProvider {
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::Relaxed)
+        }
+    }
+
+    impl LyricsProvider for CountingProvider {
+        fn name(&self) -> &str {
+            "Counting"
+        }
+
+        fn get<'a>(
+            &'a self,
+            _session: &'a SpotifySession,
+            track: &'a LyricsRequest,
+        ) -> ProviderFuture<'a, Option<TrackLyrics>> {
+            Box::pin(async move {
+                self.calls.fetch_add(1, Ordering::Relaxed);
+
+                // Take a while, like a provider that is reached over the network
+                sleep(Duration::from_millis(100)).await;
+
+                if track.track_id == request(WITHOUT_LYRICS).track_id {
+                    return Ok(None);
+                }
+
+                Ok(Some(TrackLyrics {
+                    lines: vec![],
+                    synced: false,
+                    provider: String::new(),
+                    source: self.name().to_string(),
+                }))
+            })
+        }
+    }
+
+    fn request(uri: &str) -> LyricsRequest {
+        LyricsRequest {
+            track_id: SpotifyId::from_uri(uri).expect("invalid uri"),
+            name: "Name".to_string(),
+            artists: vec!["Artist".to_string()],
+            album: None,
+            duration_ms: 180_000,
+        }
+    }
+
+    fn cache(capacity: usize) -> (LyricsCache, CountingProvider, SpotifySession) {
+        let provider = CountingProvider::default();
+        let cache = LyricsCache::new(
+            capacity,
+            TTL,
+            NEGATIVE_TTL,
+            None,
+            vec![Box::new(provider.clone())],
+        );
+
+        (
+            cache,
+            provider,
+            SpotifySession::new(SessionConfig::default(), None),
+        )
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn hits_and_misses_are_counted() {
+        let (cache, provider, session) = cache(10);
+
+        assert!(cache.get(&session, &request(WITH_LYRICS)).await.is_some());
+        assert!(cache.get(&session, &request(WITH_LYRICS)).await.is_some());
+        assert!(cache
+            .get(&session, &request(WITHOUT_LYRICS))
+            .await
+            .is_none());
+        assert!(cache
+            .get(&session, &request(WITHOUT_LYRICS))
+            .await
+            .is_none());
+
+        let stats = cache.stats();
+
+        assert_eq!(provider.calls(), 2);
+        assert_eq!(stats.entries, 2);
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.negative_hits, 1);
+        assert_eq!(stats.backing_hits, 0);
+        assert_eq!(stats.misses, 2);
+        assert_eq!(stats.hit_rate(), 0.5);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn least_recently_used_entry_is_evicted() {
+        let (cache, provider, session) = cache(2);
+
+        cache.get(&session, &request(WITH_LYRICS)).await;
+        cache.get(&session, &request(WITHOUT_LYRICS)).await;
+
+        // Using the first track again makes the second one the least recently used
+        cache.get(&session, &request(WITH_LYRICS)).await;
+        cache.get(&session, &request(OTHER)).await;
+
+        assert_eq!(cache.stats().evictions, 1);
+        assert_eq!(cache.stats().entries, 2);
+        assert_eq!(provider.calls(), 3);
+
+        cache.get(&session, &request(WITH_LYRICS)).await;
+        cache.get(&session, &request(OTHER)).await;
+        assert_eq!(provider.calls(), 3);
+
+        cache.get(&session, &request(WITHOUT_LYRICS)).await;
+        assert_eq!(provider.calls(), 4);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn entries_expire() {
+        let (cache, provider, session) = cache(10);
+
+        cache.get(&session, &request(WITH_LYRICS)).await;
+        cache.get(&session, &request(WITHOUT_LYRICS)).await;
+
+        // Knowing that a track has no lyrics expires before the lyrics themselves do
+        advance(NEGATIVE_TTL + Duration::from_secs(1)).await;
+
+        assert!(cache.get(&session, &request(WITH_LYRICS)).await.is_some());
+        assert_eq!(provider.calls(), 2);
+
+        assert!(cache
+            .get(&session, &request(WITHOUT_LYRICS))
+            .await
+            .is_none());
+        assert_eq!(provider.calls(), 3);
+
+        advance(TTL).await;
+
+        assert!(cache.get(&session, &request(WITH_LYRICS)).await.is_some());
+        assert_eq!(provider.calls(), 4);
+        assert_eq!(cache.stats().misses, 4);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn concurrent_lookups_are_shared() {
+        let (cache, provider, session) = cache(10);
+        let track = request(WITH_LYRICS);
+
+        let (first, second) =
+            tokio::join!(cache.get(&session, &track), cache.get(&session, &track));
+
+        assert!(first.is_some() && second.is_some());
+        assert_eq!(provider.calls(), 1);
+        assert_eq!(cache.stats().misses, 1);
+        assert!(cache.entries().pending.is_empty());
+    }
+}
@@ -0,0 +1,588 @@
+use std::{future::Future, io::ErrorKind, path::PathBuf, pin::Pin, time::Duration};
+
+use librespot::core::{http_client::HttpClientError, Session as SpotifySession};
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{LyricsLine, LyricsRequest, Syllable, TrackLyrics};
+
+/// The future that is returned by [`LyricsProvider::get`]
+pub type ProviderFuture<'a, T> = Pin<Box<dyn Future<Output = anyhow::Result<T>> + Send + 'a>>;
+
+/// A place where the lyrics of tracks can be looked up
+pub trait LyricsProvider: Send + Sync {
+    /// The name of the provider, which is shown to users as the source of the lyrics
+    fn name(&self) -> &str;
+
+    /// Look up the lyrics of a track, returning `None` if the provider doesn't have any
+    fn get<'a>(
+        &'a self,
+        session: &'a SpotifySession,
+        track: &'a LyricsRequest,
+    ) -> ProviderFuture<'a, Option<TrackLyrics>>;
+}
+
+/// The lyrics that Spotify shows in its own apps
+pub struct SpotifyLyrics;
+
+impl LyricsProvider for SpotifyLyrics {
+    fn name(&self) -> &str {
+        "Spotify"
+    }
+
+    fn get<'a>(
+        &'a self,
+        session: &'a SpotifySession,
+        track: &'a LyricsRequest,
+    ) -> ProviderFuture<'a, Option<TrackLyrics>> {
+        Box::pin(async move {
+            if track.is_local() {
+                return Ok(None);
+            }
+
+            let data = match session.spclient().get_lyrics(&track.track_id).await {
+                Ok(data) => data,
+                Err(why) => match why.error.downcast_ref::<HttpClientError>() {
+                    // Spotify responds with a 404 for tracks without lyrics
+                    Some(HttpClientError::StatusCode(code)) if code.as_u16() == 404 => {
+                        return Ok(None)
+                    }
+                    _ => return Err(why.into()),
+                },
+            };
+
+            let value: Value = serde_json::from_slice(&data)?;
+            let lyrics = &value["lyrics"];
+
+            let lines = lyrics["lines"]
+                .as_array()
+                .map(|lines| {
+                    lines
+                        .iter()
+                        .map(|line| LyricsLine {
+                            start_time_ms: number(&line["startTimeMs"]).unwrap_or(0) as u32,
+                            words: line["words"].as_str().unwrap_or_default().to_string(),
+                            syllables: spotify_syllables(line),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            Ok(Some(TrackLyrics {
+                lines,
+                synced: matches!(
+                    lyrics["syncType"].as_str(),
+                    Some("LINE_SYNCED" | "SYLLABLE_SYNCED")
+                ),
+                provider: lyrics["providerDisplayName"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                source: self.name().to_string(),
+            }))
+        })
+    }
+}
+
+fn spotify_syllables(line: &Value) -> Vec<Syllable> {
+    line["syllables"]
+        .as_array()
+        .map(|syllables| {
+            syllables
+                .iter()
+                .filter_map(|syllable| {
+                    Some(Syllable {
+                        start_time_ms: number(&syllable["startTimeMs"])? as u32,
+                        num_chars: number(&syllable["numChars"])? as usize,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Spotify sends numbers as strings, but accept actual numbers as well
+fn number(value: &Value) -> Option<u64> {
+    match value {
+        Value::String(value) => value.parse().ok(),
+        value => value.as_u64(),
+    }
+}
+
+/// `.lrc` files in a local directory, for self-hosters that keep their own lyrics.
+///
+/// Files are named after either the Spotify track id, or `Artist - Title` using the first artist of the track.
+pub struct LocalLyrics {
+    dir: PathBuf,
+}
+
+impl LocalLyrics {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl LyricsProvider for LocalLyrics {
+    fn name(&self) -> &str {
+        "Local files"
+    }
+
+    fn get<'a>(
+        &'a self,
+        _session: &'a SpotifySession,
+        track: &'a LyricsRequest,
+    ) -> ProviderFuture<'a, Option<TrackLyrics>> {
+        Box::pin(async move {
+            let mut names = vec![];
+
+            // Local files don't have an id of their own
+            if !track.is_local() {
+                if let Ok(id) = track.track_id.to_base62() {
+                    names.push(id);
+                }
+            }
+
+            if let Some(artist) = track.artists.first() {
+                names.push(format!("{artist} - {}", track.name));
+            }
+
+            for name in names {
+                // Keep track metadata from reaching outside of the lyrics directory
+                let name = name.replace(['/', '\\'], "_");
+
+                let content = match tokio::fs::read_to_string(self.dir.join(name + ".lrc")).await {
+                    Ok(content) => content,
+                    Err(why) if why.kind() == ErrorKind::NotFound => continue,
+                    Err(why) => return Err(why.into()),
+                };
+
+                let (lines, synced) = parse_lrc(&content);
+
+                return Ok(Some(TrackLyrics {
+                    lines,
+                    synced,
+                    provider: String::new(),
+                    source: self.name().to_string(),
+                }));
+            }
+
+            Ok(None)
+        })
+    }
+}
+
+/// An HTTP API that lyrics can be looked up in, used together with [`HttpLyrics`]
+pub trait LyricsApi: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Build the request for the lyrics of a track, where `base_url` is the address of the API without trailing slash
+    fn request(&self, client: &Client, base_url: &str, track: &LyricsRequest) -> RequestBuilder;
+
+    /// Parse a successful response, returning `None` if the API has no lyrics for the track
+    fn parse(&self, body: &[u8]) -> anyhow::Result<Option<TrackLyrics>>;
+}
+
+/// Looks up lyrics in an HTTP API, such as [`Lrclib`]
+pub struct HttpLyrics<A> {
+    client: Client,
+    base_url: String,
+    api: A,
+}
+
+impl<A: LyricsApi> HttpLyrics<A> {
+    pub fn new(base_url: impl AsRef<str>, api: A) -> reqwest::Result<Self> {
+        let client = Client::builder()
+            .user_agent(format!(
+                "Spoticord/{} (https://github.com/SpoticordMusic/spoticord)",
+                env!("CARGO_PKG_VERSION")
+            ))
+            .timeout(Duration::from_secs(10))
+            .build()?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.as_ref().trim_end_matches('/').to_string(),
+            api,
+        })
+    }
+}
+
+impl<A: LyricsApi> LyricsProvider for HttpLyrics<A> {
+    fn name(&self) -> &str {
+        self.api.name()
+    }
+
+    fn get<'a>(
+        &'a self,
+        _session: &'a SpotifySession,
+        track: &'a LyricsRequest,
+    ) -> ProviderFuture<'a, Option<TrackLyrics>> {
+        Box::pin(async move {
+            let response = self
+                .api
+                .request(&self.client, &self.base_url, track)
+                .send()
+                .await?;
+
+            if response.status() == StatusCode::NOT_FOUND {
+                return Ok(None);
+            }
+
+            let body = response.error_for_status()?.bytes().await?;
+
+            self.api.parse(&body)
+        })
+    }
+}
+
+/// The API of LRCLIB (https://lrclib.net), which can also be self-hosted
+pub struct Lrclib;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LrclibResponse {
+    #[serde(default)]
+    instrumental: bool,
+    plain_lyrics: Option<String>,
+    synced_lyrics: Option<String>,
+}
+
+impl LyricsApi for Lrclib {
+    fn name(&self) -> &str {
+        "LRCLIB"
+    }
+
+    fn request(&self, client: &Client, base_url: &str, track: &LyricsRequest) -> RequestBuilder {
+        client.get(format!("{base_url}/api/get")).query(&[
+            ("track_name", track.name.clone()),
+            (
+                "artist_name",
+                track.artists.first().cloned().unwrap_or_default(),
+            ),
+            ("album_name", track.album.clone().unwrap_or_default()),
+            ("duration", (track.duration_ms / 1000).to_string()),
+        ])
+    }
+
+    fn parse(&self, body: &[u8]) -> anyhow::Result<Option<TrackLyrics>> {
+        let response: LrclibResponse = serde_json::from_slice(body)?;
+
+        if response.instrumental {
+            return Ok(None);
+        }
+
+        let Some(content) = response.synced_lyrics.or(response.plain_lyrics) else {
+            return Ok(None);
+        };
+
+        let (lines, synced) = parse_lrc(&content);
+
+        Ok(Some(TrackLyrics {
+            lines,
+            synced,
+            provider: String::new(),
+            source: self.name().to_string(),
+        }))
+    }
+}
+
+/// Parse lyrics in the LRC format, returning the lines and whether they have timestamps.
+///
+/// Text without timestamps is treated as unsynced lyrics.
+fn parse_lrc(content: &str) -> (Vec<LyricsLine>, bool) {
+    let mut synced = vec![];
+    let mut unsynced = vec![];
+
+    for line in content.lines() {
+        let mut rest = line.trim();
+        let mut times = vec![];
+
+        // A line can start with multiple timestamps if it is repeated throughout the song
+        while let Some((time, after)) = rest
+            .strip_prefix('[')
+            .and_then(|rest| rest.split_once(']'))
+            .and_then(|(tag, after)| Some((lrc_timestamp(tag)?, after)))
+        {
+            times.push(time);
+            rest = after;
+        }
+
+        if times.is_empty() {
+            // Tags like [ar:Artist] describe the track, they are not part of the lyrics
+            if !(rest.starts_with('[') && rest.ends_with(']')) {
+                unsynced.push(LyricsLine {
+                    start_time_ms: 0,
+                    words: rest.to_string(),
+                    syllables: vec![],
+                });
+            }
+
+            continue;
+        }
+
+        for time in times {
+            synced.push(LyricsLine {
+                start_time_ms: time,
+                words: rest.trim().to_string(),
+                syllables: vec![],
+            });
+        }
+    }
+
+    if synced.is_empty() {
+        return (unsynced, false);
+    }
+
+    synced.sort_by_key(|line| line.start_time_ms);
+
+    (synced, true)
+}
+
+/// Parse an LRC timestamp like `01:23.45` into milliseconds
+fn lrc_timestamp(tag: &str) -> Option<u32> {
+    let (minutes, seconds) = tag.split_once(':')?;
+    let minutes = minutes.trim().parse::<u32>().ok()?;
+    let seconds = seconds.trim().parse::<f64>().ok()?;
+
+    if !seconds.is_finite() || seconds < 0.0 {
+        return None;
+    }
+
+    Some(minutes * 60_000 + (seconds * 1000.0).round() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use librespot::core::{SessionConfig, SpotifyId};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        sync::mpsc,
+    };
+
+    use super::*;
+
+    const TRACK: &str = "spotify:track:4uLU6hMCjMI75M1A2tKUQC";
+
+    fn request(uri: &str) -> LyricsRequest {
+        LyricsRequest {
+            track_id: SpotifyId::from_uri(uri).expect("invalid uri"),
+            name: "Never Gonna Give You Up".to_string(),
+            artists: vec!["Rick Astley".to_string()],
+            album: Some("Whenever You Need Somebody".to_string()),
+            duration_ms: 213_000,
+        }
+    }
+
+    fn session() -> SpotifySession {
+        SpotifySession::new(SessionConfig::default(), None)
+    }
+
+    fn words(lines: &[LyricsLine]) -> Vec<(u32, &str)> {
+        lines
+            .iter()
+            .map(|line| (line.start_time_ms, line.words.as_str()))
+            .collect()
+    }
+
+    /// Answer every HTTP request with the same response, sending the request line of every request that was received
+    async fn serve(
+        status: &'static str,
+        body: &'static str,
+    ) -> (String, mpsc::UnboundedReceiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = vec![];
+                let mut buffer = [0; 1024];
+
+                while !request.ends_with(b"\r\n\r\n") {
+                    match socket.read(&mut buffer).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(read) => request.extend_from_slice(&buffer[..read]),
+                    }
+                }
+
+                let request = String::from_utf8_lossy(&request);
+                _ = tx.send(request.lines().next().unwrap_or_default().to_string());
+
+                let response = format!(
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                _ = socket.write_all(response.as_bytes()).await;
+                _ = socket.shutdown().await;
+            }
+        });
+
+        (url, rx)
+    }
+
+    #[test]
+    fn lrc_lines_are_sorted_by_time() {
+        let (lines, synced) = parse_lrc(
+            "[ar:Rick Astley]\n\
+             [ti:Never Gonna Give You Up]\n\
+             [offset:+500]\n\
+             [00:18.50]We're no strangers to love\n\
+             [00:43.12][01:50.00]Never gonna give you up\n\
+             [00:22.1] You know the rules and so do I \n",
+        );
+
+        assert!(synced);
+        assert_eq!(
+            words(&lines),
+            [
+                (18_500, "We're no strangers to love"),
+                (22_100, "You know the rules and so do I"),
+                (43_120, "Never gonna give you up"),
+                (110_000, "Never gonna give you up"),
+            ]
+        );
+    }
+
+    #[test]
+    fn lrc_without_timestamps_is_unsynced() {
+        let (lines, synced) = parse_lrc(
+            "[ar:Rick Astley]\n\
+             We're no strangers to love\n\
+             \n\
+             You know the rules and so do I",
+        );
+
+        assert!(!synced);
+        assert_eq!(
+            words(&lines),
+            [
+                (0, "We're no strangers to love"),
+                (0, ""),
+                (0, "You know the rules and so do I"),
+            ]
+        );
+    }
+
+    #[test]
+    fn lrc_timestamps() {
+        assert_eq!(lrc_timestamp("01:23.45"), Some(83_450));
+        assert_eq!(lrc_timestamp("00:05"), Some(5_000));
+        assert_eq!(lrc_timestamp("12:00.001"), Some(720_001));
+        assert_eq!(lrc_timestamp("ar:Rick Astley"), None);
+        assert_eq!(lrc_timestamp("00:-1"), None);
+        assert_eq!(lrc_timestamp("00:inf"), None);
+    }
+
+    #[tokio::test]
+    async fn lrclib_lyrics() {
+        let (url, mut requests) = serve(
+            "200 OK",
+            r#"{"instrumental":false,"plainLyrics":"Never gonna give you up","syncedLyrics":"[00:43.12] Never gonna give you up"}"#,
+        )
+        .await;
+
+        let provider = HttpLyrics::new(format!("{url}/"), Lrclib).unwrap();
+        let lyrics = provider
+            .get(&session(), &request(TRACK))
+            .await
+            .unwrap()
+            .expect("expected lyrics");
+
+        assert!(lyrics.synced);
+        assert_eq!(lyrics.source, "LRCLIB");
+        assert_eq!(words(&lyrics.lines), [(43_120, "Never gonna give you up")]);
+
+        let request = requests.recv().await.unwrap();
+        assert!(request.starts_with(
+            "GET /api/get?track_name=Never+Gonna+Give+You+Up&artist_name=Rick+Astley&"
+        ));
+        assert!(request.contains("&duration=213 "), "{request}");
+    }
+
+    #[tokio::test]
+    async fn lrclib_without_lyrics() {
+        let (url, _) = serve("404 Not Found", r#"{"code":404,"name":"TrackNotFound"}"#).await;
+        let provider = HttpLyrics::new(url, Lrclib).unwrap();
+
+        assert!(provider
+            .get(&session(), &request(TRACK))
+            .await
+            .unwrap()
+            .is_none());
+
+        let (url, _) = serve(
+            "200 OK",
+            r#"{"instrumental":true,"plainLyrics":null,"syncedLyrics":null}"#,
+        )
+        .await;
+        let provider = HttpLyrics::new(url, Lrclib).unwrap();
+
+        assert!(provider
+            .get(&session(), &request(TRACK))
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn lrclib_server_errors_are_reported() {
+        let (url, _) = serve("500 Internal Server Error", "{}").await;
+        let provider = HttpLyrics::new(url, Lrclib).unwrap();
+
+        assert!(provider.get(&session(), &request(TRACK)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn local_lyrics_files() {
+        let directory =
+            std::env::temp_dir().join(format!("spoticord-lyrics-{}", std::process::id()));
+        _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+
+        let write = |name: &str, content: &str| {
+            std::fs::write(Path::new(&directory).join(name), content).unwrap();
+        };
+
+        // Files named after the track id take precedence over files named after the artist and title
+        write("4uLU6hMCjMI75M1A2tKUQC.lrc", "[00:18.50]By id");
+        write("Rick Astley - Never Gonna Give You Up.lrc", "By name");
+
+        let provider = LocalLyrics::new(&directory);
+        let session = session();
+
+        let lyrics = provider
+            .get(&session, &request(TRACK))
+            .await
+            .unwrap()
+            .expect("expected lyrics");
+
+        assert!(lyrics.synced);
+        assert_eq!(lyrics.source, "Local files");
+        assert_eq!(words(&lyrics.lines), [(18_500, "By id")]);
+
+        // Local files can only be found by name
+        let lyrics = provider
+            .get(
+                &session,
+                &request("spotify:local:Rick+Astley::Never+Gonna+Give+You+Up:213"),
+            )
+            .await
+            .unwrap()
+            .expect("expected lyrics");
+
+        assert!(!lyrics.synced);
+        assert_eq!(words(&lyrics.lines), [(0, "By name")]);
+
+        let mut unknown = request(TRACK);
+        unknown.track_id = SpotifyId::from_uri("spotify:track:6rqhFgbbKwnb9MLmUQDhG6").unwrap();
+        unknown.name = "Speak to Me".to_string();
+
+        assert!(provider.get(&session, &unknown).await.unwrap().is_none());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
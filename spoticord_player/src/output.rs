@@ -0,0 +1,219 @@
+use std::{
+    io::{Read, Write},
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use anyhow::Result;
+use songbird::{
+    input::{Input, RawAdapter},
+    tracks::TrackHandle,
+    Call,
+};
+use spoticord_audio::{opus::OpusStream, stream::Stream};
+use spoticord_config::AudioOutput;
+use tokio::sync::Mutex;
+
+use crate::SAMPLE_RATE;
+
+/// How often the capture thread checks the stream for new audio
+const CAPTURE_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Where the audio of a player ends up.
+///
+/// The output reads the audio from the [`Stream`] the player writes into, and is told by the player when it should
+/// start or stop doing so.
+pub trait Output: Send + Sync {
+    fn play(&self) -> Result<()>;
+
+    fn pause(&self) -> Result<()>;
+
+    /// Change the volume of the output, where `1.0` is the original volume
+    fn set_volume(&self, volume: f32) -> Result<()>;
+
+    fn volume(&self) -> f32;
+}
+
+/// Plays the audio in a Discord call
+pub struct SongbirdOutput {
+    track: TrackHandle,
+    volume: AtomicU32,
+}
+
+impl SongbirdOutput {
+    /// Add the stream to the call, replacing anything that is currently playing
+    pub async fn new(call: &Mutex<Call>, stream: Stream) -> std::io::Result<Self> {
+        let input: Input = match spoticord_config::get().audio.output {
+            AudioOutput::Pcm => RawAdapter::new(stream, SAMPLE_RATE as u32, 2).into(),
+            AudioOutput::Opus => OpusStream::new(stream)?.into(),
+        };
+
+        let track = call.lock().await.play_only_input(input);
+        _ = track.pause();
+
+        Ok(Self {
+            track,
+            volume: AtomicU32::new(1f32.to_bits()),
+        })
+    }
+}
+
+impl Output for SongbirdOutput {
+    fn play(&self) -> Result<()> {
+        Ok(self.track.play()?)
+    }
+
+    fn pause(&self) -> Result<()> {
+        Ok(self.track.pause()?)
+    }
+
+    fn set_volume(&self, volume: f32) -> Result<()> {
+        self.track.set_volume(volume)?;
+        self.volume.store(volume.to_bits(), Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    fn volume(&self) -> f32 {
+        f32::from_bits(self.volume.load(Ordering::Relaxed))
+    }
+}
+
+/// Captures the audio as raw PCM (interleaved stereo 32-bit floats at 44.1 kHz) into a writer, without involving
+/// Discord at all.
+///
+/// Unlike songbird, only audio that was actually produced is captured, underruns do not result in silence.
+pub struct CaptureOutput {
+    state: Arc<CaptureState>,
+}
+
+struct CaptureState {
+    playing: AtomicBool,
+    volume: AtomicU32,
+    closed: AtomicBool,
+}
+
+impl CaptureOutput {
+    /// Capture the audio into `writer`, which happens on a separate thread
+    pub fn new(mut stream: Stream, mut writer: impl Write + Send + 'static) -> Self {
+        let state = Arc::new(CaptureState {
+            playing: AtomicBool::new(false),
+            volume: AtomicU32::new(1f32.to_bits()),
+            closed: AtomicBool::new(false),
+        });
+
+        let capture = state.clone();
+
+        std::thread::spawn(move || {
+            let mut buffer = vec![];
+
+            while !capture.closed.load(Ordering::Relaxed) {
+                std::thread::sleep(CAPTURE_INTERVAL);
+
+                if !capture.playing.load(Ordering::Relaxed) {
+                    continue;
+                }
+
+                // Only read whole samples, so the volume can be applied
+                let buffered = stream.stats().buffered / 4 * 4;
+                if buffered == 0 {
+                    continue;
+                }
+
+                buffer.resize(buffered, 0);
+
+                match stream.read(&mut buffer) {
+                    Ok(read) => buffer.truncate(read),
+                    Err(_) => break,
+                }
+
+                let volume = f32::from_bits(capture.volume.load(Ordering::Relaxed));
+
+                for sample in buffer.chunks_exact_mut(4) {
+                    let value = f32::from_ne_bytes([sample[0], sample[1], sample[2], sample[3]]);
+                    sample.copy_from_slice(&(value * volume).to_le_bytes());
+                }
+
+                if writer.write_all(&buffer).is_err() {
+                    break;
+                }
+            }
+
+            _ = writer.flush();
+        });
+
+        Self { state }
+    }
+
+    /// Capture the audio into memory, the returned buffer grows while the audio is being played
+    pub fn memory(stream: Stream) -> (Self, Arc<std::sync::Mutex<Vec<u8>>>) {
+        let buffer = Arc::new(std::sync::Mutex::new(vec![]));
+
+        (
+            Self::new(
+                stream,
+                SharedBuffer {
+                    buffer: buffer.clone(),
+                },
+            ),
+            buffer,
+        )
+    }
+
+    /// Whether the audio is currently being captured
+    pub fn is_playing(&self) -> bool {
+        self.state.playing.load(Ordering::Relaxed)
+    }
+}
+
+impl Output for CaptureOutput {
+    fn play(&self) -> Result<()> {
+        self.state.playing.store(true, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    fn pause(&self) -> Result<()> {
+        self.state.playing.store(false, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    fn set_volume(&self, volume: f32) -> Result<()> {
+        self.state.volume.store(volume.to_bits(), Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    fn volume(&self) -> f32 {
+        f32::from_bits(self.state.volume.load(Ordering::Relaxed))
+    }
+}
+
+impl Drop for CaptureOutput {
+    fn drop(&mut self) {
+        self.state.closed.store(true, Ordering::Relaxed);
+    }
+}
+
+struct SharedBuffer {
+    buffer: Arc<std::sync::Mutex<Vec<u8>>>,
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer
+            .lock()
+            .expect("Mutex was poisoned")
+            .extend_from_slice(buf);
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,173 @@
+//! Utilities for driving a [`Player`] without Spotify or Discord.
+//!
+//! A fake player does not connect to Spotify, instead the librespot and sink events it reacts to are sent through a
+//! [`FakeSpotify`], and everything it would have asked Spotify Connect to do is recorded by [`FakeControls`].
+
+use std::sync::{atomic::AtomicBool, Arc, Mutex};
+
+use librespot::{
+    core::{Session as SpotifySession, SessionConfig, SpotifyId},
+    playback::player::PlayerEvent as SpotifyPlayerEvent,
+};
+use spoticord_audio::{effects, loudness, recorder, sink::SinkEvent, stream::Stream};
+use tokio::sync::{mpsc, watch};
+
+use crate::{
+    controls::Controls, output::Output, AudioOptions, Player, PlayerEvent, PlayerHandle, Stall,
+};
+
+/// A request the player made to Spotify Connect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlCommand {
+    Play,
+    Pause,
+    Next,
+    Previous,
+    Load {
+        track_id: SpotifyId,
+        position_ms: u32,
+    },
+    Seek {
+        position_ms: u32,
+    },
+    Shutdown,
+}
+
+/// Records the requests the player makes, instead of sending them to Spotify
+#[derive(Clone, Default)]
+pub struct FakeControls {
+    commands: Arc<Mutex<Vec<ControlCommand>>>,
+}
+
+impl FakeControls {
+    /// All requests that have been made so far, in order
+    pub fn commands(&self) -> Vec<ControlCommand> {
+        self.commands.lock().expect("Mutex was poisoned").clone()
+    }
+
+    fn push(&self, command: ControlCommand) {
+        self.commands
+            .lock()
+            .expect("Mutex was poisoned")
+            .push(command);
+    }
+}
+
+impl Controls for FakeControls {
+    fn play(&self) {
+        self.push(ControlCommand::Play);
+    }
+
+    fn pause(&self) {
+        self.push(ControlCommand::Pause);
+    }
+
+    fn next(&self) {
+        self.push(ControlCommand::Next);
+    }
+
+    fn previous(&self) {
+        self.push(ControlCommand::Previous);
+    }
+
+    fn load(&self, track_id: SpotifyId, position_ms: u32) {
+        self.push(ControlCommand::Load {
+            track_id,
+            position_ms,
+        });
+    }
+
+    fn seek(&self, position_ms: u32) {
+        self.push(ControlCommand::Seek { position_ms });
+    }
+
+    fn shutdown(&self) {
+        self.push(ControlCommand::Shutdown);
+    }
+}
+
+/// Feeds events to a fake player, as if they were coming from librespot
+pub struct FakeSpotify {
+    spotify_events: mpsc::UnboundedSender<SpotifyPlayerEvent>,
+    sink_events: mpsc::UnboundedSender<SinkEvent>,
+    controls: FakeControls,
+}
+
+impl FakeSpotify {
+    /// Send an event as if it was emitted by the librespot player
+    pub fn send(&self, event: SpotifyPlayerEvent) {
+        _ = self.spotify_events.send(event);
+    }
+
+    /// Send an event as if it was emitted by the audio sink
+    pub fn sink(&self, event: SinkEvent) {
+        _ = self.sink_events.send(event);
+    }
+
+    /// The requests the player has made to Spotify Connect
+    pub fn controls(&self) -> &FakeControls {
+        &self.controls
+    }
+}
+
+impl Player {
+    /// Create a player that is driven by a [`FakeSpotify`] instead of librespot.
+    ///
+    /// The player only reads from `stream` to gather statistics, audio has to be written into it by the caller.
+    pub fn fake(
+        stream: Stream,
+        output: Arc<dyn Output>,
+        audio: AudioOptions,
+    ) -> (PlayerHandle, mpsc::Receiver<PlayerEvent>, FakeSpotify) {
+        let (event_tx, event_rx) = mpsc::channel(16);
+        let (spotify_tx, spotify_rx) = mpsc::unbounded_channel();
+        let (sink_tx, sink_rx) = mpsc::unbounded_channel();
+        let (tx, rx) = mpsc::channel(16);
+
+        // Nothing consumes the audio side of these, as there is no librespot sink
+        let (effects, _) = effects::channel();
+        let (crossfade, _) = watch::channel(audio.crossfade);
+        let (loudness, _) = loudness::meter();
+        let (recorder, _) = recorder::channel();
+        let (stretch, _) = watch::channel(1.0);
+
+        let controls = FakeControls::default();
+
+        let player = Self {
+            session: SpotifySession::new(SessionConfig::default(), None),
+            controls: Box::new(controls.clone()),
+            output,
+            stream,
+            effects,
+            crossfade,
+            loudness,
+            recorder,
+            ducking: None,
+            stall: Stall::default(),
+            speed: 1.0,
+            stretch,
+
+            playback_info: None,
+
+            events: event_tx,
+
+            commands: rx,
+            spotify_events: spotify_rx,
+            sink_events: sink_rx,
+
+            shutdown: Arc::new(AtomicBool::new(false)),
+        };
+
+        tokio::spawn(player.run());
+
+        (
+            PlayerHandle { commands: tx },
+            event_rx,
+            FakeSpotify {
+                spotify_events: spotify_tx,
+                sink_events: sink_tx,
+                controls,
+            },
+        )
+    }
+}
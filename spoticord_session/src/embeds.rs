@@ -0,0 +1,88 @@
+use serenity::all::ChannelId;
+use tokio::task::JoinHandle;
+
+use crate::playback_embed::PlaybackEmbedHandle;
+
+/// The maximum amount of embeds of a single kind that a session keeps up to date
+pub const MAX_EMBEDS: usize = 5;
+
+/// An embed that is kept up to date by a task of its own
+pub trait LiveEmbed {
+    /// Whether the embed is still being updated
+    fn is_live(&self) -> bool;
+
+    /// Stop updating the embed
+    fn close(self);
+}
+
+impl LiveEmbed for PlaybackEmbedHandle {
+    fn is_live(&self) -> bool {
+        self.is_valid()
+    }
+
+    fn close(self) {
+        // Dropping the handle closes the channel, which stops the embed
+    }
+}
+
+impl LiveEmbed for JoinHandle<()> {
+    fn is_live(&self) -> bool {
+        !self.is_finished()
+    }
+
+    fn close(self) {
+        self.abort();
+    }
+}
+
+/// The live embeds of a single kind within a session, with at most one per channel
+pub struct EmbedRegistry<T> {
+    embeds: Vec<(ChannelId, T)>,
+}
+
+impl<T: LiveEmbed> EmbedRegistry<T> {
+    pub fn new() -> Self {
+        Self { embeds: vec![] }
+    }
+
+    /// Register an embed, which replaces the embed that was previously created in the same channel.
+    ///
+    /// If there are already [`MAX_EMBEDS`] embeds, the oldest one is closed to make room.
+    pub fn insert(&mut self, channel: ChannelId, embed: T) {
+        self.prune();
+
+        if let Some(index) = self.embeds.iter().position(|(id, _)| *id == channel) {
+            let (_, previous) = self.embeds.remove(index);
+            previous.close();
+        }
+
+        if self.embeds.len() >= MAX_EMBEDS {
+            let (_, oldest) = self.embeds.remove(0);
+            oldest.close();
+        }
+
+        self.embeds.push((channel, embed));
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.embeds.iter().map(|(_, embed)| embed)
+    }
+
+    /// Forget about embeds that are no longer being updated
+    pub fn prune(&mut self) {
+        self.embeds.retain(|(_, embed)| embed.is_live());
+    }
+
+    /// Close all embeds
+    pub fn clear(&mut self) {
+        for (_, embed) in self.embeds.drain(..) {
+            embed.close();
+        }
+    }
+}
+
+impl<T: LiveEmbed> Default for EmbedRegistry<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,17 +1,23 @@
+pub mod embeds;
 pub mod error;
 pub mod lyrics_embed;
 pub mod manager;
+pub mod now_playing;
+pub mod play_context;
 pub mod playback_embed;
+#[cfg(feature = "testing")]
+pub mod testing;
 
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use embeds::EmbedRegistry;
 use error::Error;
 use error::Result;
 use librespot::{
-    core::connection,
+    core::{connection, SpotifyId},
     discovery::Credentials,
     protocol::{authentication::AuthenticationType, keyexchange::ErrorCode},
 };
-use log::{debug, error, trace};
+use log::{debug, error, trace, warn};
 use lyrics_embed::LyricsEmbed;
 use manager::{SessionManager, SessionQuery};
 use playback_embed::{PlaybackEmbed, PlaybackEmbedHandle};
@@ -21,15 +27,34 @@ use serenity::{
     },
     async_trait,
 };
-use songbird::{model::payload::ClientDisconnect, Call, CoreEvent, Event, EventContext};
-use spoticord_player::{Player, PlayerEvent, PlayerHandle};
+use songbird::{
+    input::File, model::payload::ClientDisconnect, tracks::TrackHandle, Call, CoreEvent, Event,
+    EventContext, TrackEvent,
+};
+use spoticord_database::{
+    error::DatabaseResultExt,
+    models::{Guild, User},
+};
+use spoticord_i18n::{t, Locale};
+use spoticord_player::{AudioOptions, NormalizationType, Player, PlayerEvent, PlayerHandle};
 use spoticord_utils::discord::Colors;
-use std::{ops::ControlFlow, sync::Arc, time::Duration};
+use std::{ops::ControlFlow, path::PathBuf, sync::Arc, time::Duration};
 use tokio::{
     sync::{mpsc, oneshot, Mutex},
     task::JoinHandle,
+    time::Instant,
 };
 
+/// Episodes that were left before this position (in milliseconds) start from the beginning next time
+const EPISODE_MIN_PROGRESS: u32 = 10_000;
+
+/// Episodes that were left this close (in milliseconds) to their end count as finished
+const EPISODE_FINISHED_MARGIN: u32 = 30_000;
+
+/// Episodes are only resumed when they start before this position (in milliseconds), so a position that was picked
+/// in the Spotify client is left alone
+const EPISODE_RESUME_THRESHOLD: u32 = 5_000;
+
 #[derive(Debug)]
 pub enum SessionCommand {
     GetOwner(oneshot::Sender<UserId>),
@@ -40,10 +65,13 @@ pub enum SessionCommand {
         SessionHandle,
         CommandInteraction,
         playback_embed::UpdateBehavior,
+        Locale,
     ),
-    CreateLyricsEmbed(SessionHandle, CommandInteraction),
+    CreateLyricsEmbed(SessionHandle, CommandInteraction, bool, Locale),
 
     Reactivate(UserId, oneshot::Sender<Result<()>>),
+    Announce(PathBuf),
+    AnnouncementEnded(u64),
     ShutdownPlayer,
     Disconnect,
     DisconnectTimedOut,
@@ -56,6 +84,9 @@ pub struct Session {
     guild_id: GuildId,
     text_channel: GuildChannel,
     call: Arc<Mutex<Call>>,
+
+    /// The language of messages that are sent to the text channel
+    locale: Locale,
     player: PlayerHandle,
 
     owner: UserId,
@@ -69,8 +100,12 @@ pub struct Session {
     commands_inner_tx: mpsc::Sender<SessionCommand>,
     commands_inner_rx: mpsc::Receiver<SessionCommand>,
 
-    playback_embed: Option<PlaybackEmbedHandle>,
-    lyrics_embed: Option<JoinHandle<()>>,
+    playback_embeds: EmbedRegistry<PlaybackEmbedHandle>,
+    lyrics_embeds: EmbedRegistry<JoinHandle<()>>,
+
+    /// The announcement that is currently being played over the music
+    announcement: Option<TrackHandle>,
+    announcements: u64,
 }
 
 impl Session {
@@ -82,6 +117,7 @@ impl Session {
         voice_channel_id: ChannelId,
         text_channel_id: ChannelId,
         owner: UserId,
+        locale: Locale,
     ) -> Result<SessionHandle> {
         use Error::*;
 
@@ -113,11 +149,17 @@ impl Session {
             .await?;
 
         // Get user preferences
-        let device_name = session_manager
+        let user = session_manager
             .database()
             .get_user(owner.to_string())
-            .await?
-            .device_name;
+            .await?;
+
+        // Get server settings
+        let guild = session_manager
+            .database()
+            .get_or_create_guild(guild_id.to_string())
+            .await?;
+        let audio = audio_options(&user, &guild);
 
         let credentials = match account
             .session_token
@@ -162,7 +204,7 @@ impl Session {
         }
 
         let (player, events, auth_data) =
-            match Player::create(credentials, call.clone(), device_name).await {
+            match Player::create(credentials, call.clone(), user.device_name, audio).await {
                 Ok(player) => player,
                 Err(why) => {
                     // Leave call on error, otherwise bot will be stuck in call forever until manually disconnected or taken over
@@ -212,6 +254,7 @@ impl Session {
             text_channel,
 
             call,
+            locale,
             player,
 
             guild_id,
@@ -226,8 +269,11 @@ impl Session {
             commands_inner_tx: inner_tx,
             commands_inner_rx: inner_rx,
 
-            playback_embed: None,
-            lyrics_embed: None,
+            playback_embeds: EmbedRegistry::new(),
+            lyrics_embeds: EmbedRegistry::new(),
+
+            announcement: None,
+            announcements: 0,
         };
         session.start_timeout();
 
@@ -286,24 +332,25 @@ impl Session {
             SessionCommand::GetPlayer(sender) => _ = sender.send(self.player.clone()),
             SessionCommand::GetActive(sender) => _ = sender.send(self.active),
 
-            SessionCommand::CreatePlaybackEmbed(handle, interaction, behavior) => {
-                match PlaybackEmbed::create(self, handle, interaction, behavior).await {
-                    Ok(opt_handle) => {
-                        self.playback_embed = opt_handle;
+            SessionCommand::CreatePlaybackEmbed(handle, interaction, behavior, locale) => {
+                let channel = interaction.channel_id;
+
+                match PlaybackEmbed::create(self, handle, interaction, behavior, locale).await {
+                    Ok(Some(playback_embed)) => {
+                        self.playback_embeds.insert(channel, playback_embed);
                     }
+                    Ok(None) => {}
                     Err(why) => {
                         error!("Failed to create playing embed: {why}");
                     }
                 };
             }
-            SessionCommand::CreateLyricsEmbed(handle, interaction) => {
-                match LyricsEmbed::create(self, handle, interaction).await {
-                    Ok(Some(lyrics_embed)) => {
-                        if let Some(current) = self.lyrics_embed.take() {
-                            current.abort();
-                        }
+            SessionCommand::CreateLyricsEmbed(handle, interaction, karaoke, locale) => {
+                let channel = interaction.channel_id;
 
-                        self.lyrics_embed = Some(lyrics_embed);
+                match LyricsEmbed::create(self, handle, interaction, karaoke, locale).await {
+                    Ok(Some(lyrics_embed)) => {
+                        self.lyrics_embeds.insert(channel, lyrics_embed);
                     }
                     Ok(None) => {}
                     Err(why) => {
@@ -315,6 +362,13 @@ impl Session {
             SessionCommand::Reactivate(new_owner, tx) => {
                 _ = tx.send(self.reactivate(new_owner).await)
             }
+            SessionCommand::Announce(path) => self.announce(path).await,
+            SessionCommand::AnnouncementEnded(id) => {
+                // Ignore announcements that were replaced by a newer one
+                if id == self.announcements && self.announcement.take().is_some() {
+                    self.player.set_ducked(false).await;
+                }
+            }
             SessionCommand::ShutdownPlayer => self.shutdown_player().await,
             SessionCommand::Disconnect => {
                 self.disconnect().await;
@@ -330,8 +384,8 @@ impl Session {
                         &self.context,
                         CreateMessage::new().embed(
                             CreateEmbed::new()
-                                .title("It's a little quiet in here")
-                                .description("The bot has been inactive for too long, and has been disconnected.")
+                                .title(t!(self.locale, "session-timeout-title"))
+                                .description(t!(self.locale, "session-timeout-description"))
                                 .color(Colors::Warning),
                         ),
                     )
@@ -345,11 +399,57 @@ impl Session {
     }
 
     async fn handle_event(&mut self, event: PlayerEvent) {
-        match event {
+        match &event {
             PlayerEvent::Play => self.stop_timeout(),
             PlayerEvent::Pause => self.start_timeout(),
             PlayerEvent::Stopped => self.shutdown_player().await,
-            PlayerEvent::TrackChanged(_) => {}
+            PlayerEvent::UpNextChanged => {}
+            PlayerEvent::TrackChanged(info) => {
+                if info.is_episode() && info.current_position() < EPISODE_RESUME_THRESHOLD {
+                    self.resume_episode(info.track_id()).await;
+                }
+            }
+            PlayerEvent::EpisodeProgress {
+                episode,
+                position,
+                duration,
+            } => {
+                self.save_episode_progress(*episode, *position, *duration)
+                    .await;
+            }
+            PlayerEvent::Stalled {
+                position,
+                attempt,
+                recovering: true,
+            } => {
+                warn!(
+                    "Playback in guild {} stalled at {position} ms, reloading track (attempt {attempt})",
+                    self.guild_id
+                );
+            }
+            PlayerEvent::Stalled {
+                position,
+                recovering: false,
+                ..
+            } => {
+                error!(
+                    "Playback in guild {} stalled at {position} ms, giving up on recovery",
+                    self.guild_id
+                );
+
+                _ = self
+                    .text_channel
+                    .send_message(
+                        &self.context,
+                        CreateMessage::new().embed(
+                            CreateEmbed::new()
+                                .title(t!(self.locale, "session-stalled-title"))
+                                .description(t!(self.locale, "session-stalled-description"))
+                                .color(Colors::Error),
+                        ),
+                    )
+                    .await;
+            }
             PlayerEvent::ConnectionReset => {
                 self.disconnect().await;
 
@@ -359,8 +459,8 @@ impl Session {
                         &self.context,
                         CreateMessage::new().embed(
                             CreateEmbed::new()
-                                .title("Spotify connection lost")
-                                .description("The bot has lost connection to the Spotify AP servers.\nThis is most likely caused by a connection reset on Spotify's end.\n\nUse `/join` to resummon the bot to your voice channel.")
+                                .title(t!(self.locale, "session-connection-lost-title"))
+                                .description(t!(self.locale, "session-connection-lost-description"))
                                 .color(Colors::Error),
                         ),
                     )
@@ -368,13 +468,22 @@ impl Session {
             }
         }
 
+        // Stalls and episode progress don't change anything that is shown in the embed
+        if matches!(
+            event,
+            PlayerEvent::Stalled { .. } | PlayerEvent::EpisodeProgress { .. }
+        ) {
+            return;
+        }
+
         let force_edit = !matches!(event, PlayerEvent::TrackChanged(_));
 
-        if let Some(playback_embed) = &self.playback_embed {
-            if playback_embed.invoke_update(force_edit).await.is_err() {
-                self.playback_embed = None;
-            }
+        // Every embed decides for itself whether it is edited or re-sent
+        for playback_embed in self.playback_embeds.iter() {
+            _ = playback_embed.invoke_update(force_edit).await;
         }
+
+        self.playback_embeds.prune();
     }
 
     fn start_timeout(&mut self) {
@@ -382,22 +491,26 @@ impl Session {
             _ = tx.send(());
         }
 
-        let (tx, rx) = oneshot::channel::<()>();
+        let (tx, mut rx) = oneshot::channel::<()>();
         self.timeout_tx = Some(tx);
 
         let inner_tx = self.commands_inner_tx.clone();
 
         tokio::spawn(async move {
-            let mut timer =
-                tokio::time::interval(Duration::from_secs(spoticord_config::DISCONNECT_TIME));
-
-            // Ignore immediate tick
-            timer.tick().await;
-
-            tokio::select! {
-                _ = rx => return,
-                _ = timer.tick() => {}
-            };
+            let started = Instant::now();
+            let mut config = spoticord_config::subscribe();
+
+            loop {
+                // Re-evaluated on configuration changes, so a new disconnect time also applies to running timeouts
+                let deadline =
+                    started + Duration::from_secs(config.borrow_and_update().bot.disconnect_time);
+
+                tokio::select! {
+                    _ = &mut rx => return,
+                    _ = tokio::time::sleep_until(deadline) => break,
+                    Ok(()) = config.changed() => continue,
+                };
+            }
 
             // Disconnect through inner communication
             _ = inner_tx.send(SessionCommand::DisconnectTimedOut).await;
@@ -423,12 +536,15 @@ impl Session {
         let account = self.session_manager.database().get_account(user_id).await?;
 
         // Get user preferences
-        let device_name = self
+        let user = self.session_manager.database().get_user(user_id).await?;
+
+        // Get server settings
+        let guild = self
             .session_manager
             .database()
-            .get_user(user_id)
-            .await?
-            .device_name;
+            .get_or_create_guild(self.guild_id.to_string())
+            .await?;
+        let audio = audio_options(&user, &guild);
 
         let credentials = match account
             .session_token
@@ -456,7 +572,7 @@ impl Session {
         );
 
         let (player, player_events, auth_data) =
-            match Player::create(credentials, self.call.clone(), device_name).await {
+            match Player::create(credentials, self.call.clone(), user.device_name, audio).await {
                 Ok(player) => player,
                 Err(why) => {
                     if let Some(connection::AuthenticationError::LoginFailed(
@@ -500,7 +616,97 @@ impl Session {
         Ok(())
     }
 
+    /// Play an audio file over the music, which is ducked until the announcement has finished
+    async fn announce(&mut self, path: PathBuf) {
+        if let Some(announcement) = self.announcement.take() {
+            _ = announcement.stop();
+        }
+
+        self.announcements += 1;
+
+        let track = self.call.lock().await.play_input(File::new(path).into());
+        let handler = AnnouncementEnd {
+            id: self.announcements,
+            commands: self.commands_inner_tx.clone(),
+        };
+
+        _ = track.add_event(Event::Track(TrackEvent::End), handler.clone());
+        _ = track.add_event(Event::Track(TrackEvent::Error), handler);
+
+        self.announcement = Some(track);
+        self.player.set_ducked(true).await;
+    }
+
+    /// Continue an episode where the owner left off last time
+    async fn resume_episode(&self, episode: SpotifyId) {
+        let Ok(episode_id) = episode.to_base62() else {
+            return;
+        };
+
+        match self
+            .session_manager
+            .database()
+            .get_episode_progress(self.owner.to_string(), &episode_id)
+            .await
+            .optional()
+        {
+            Ok(Some(progress)) => {
+                debug!(
+                    "Resuming episode {episode_id} at {} ms",
+                    progress.position_ms
+                );
+
+                self.player.seek(progress.position_ms as u32).await;
+            }
+            Ok(None) => {}
+            Err(why) => error!("Failed to retrieve episode progress: {why}"),
+        }
+    }
+
+    /// Remember where the owner left off in an episode, or forget it if the episode was barely started or finished
+    async fn save_episode_progress(&self, episode: SpotifyId, position: u32, duration: u32) {
+        let Ok(episode_id) = episode.to_base62() else {
+            return;
+        };
+
+        let database = self.session_manager.database();
+        let user_id = self.owner.to_string();
+
+        let result =
+            if position < EPISODE_MIN_PROGRESS || position + EPISODE_FINISHED_MARGIN >= duration {
+                database
+                    .delete_episode_progress(&user_id, &episode_id)
+                    .await
+                    .map(|_| ())
+            } else {
+                database
+                    .save_episode_progress(&user_id, &episode_id, position)
+                    .await
+            };
+
+        if let Err(why) = result {
+            error!("Failed to save episode progress: {why}");
+        }
+    }
+
+    /// Save the progress of the episode that is currently playing, as the player won't report it when it is shut down
+    async fn save_current_episode(&self) {
+        let Ok(Some(playback_info)) = self.player.playback_info().await else {
+            return;
+        };
+
+        if playback_info.is_episode() {
+            self.save_episode_progress(
+                playback_info.track_id(),
+                playback_info.current_position(),
+                playback_info.duration(),
+            )
+            .await;
+        }
+    }
+
     async fn shutdown_player(&mut self) {
+        self.save_current_episode().await;
         self.player.shutdown().await;
         self.start_timeout();
 
@@ -515,6 +721,18 @@ impl Session {
         // Kill timeout if one is running
         self.stop_timeout();
 
+        if self.active {
+            self.save_current_episode().await;
+        }
+
+        // Let the embeds know that nothing is being played anymore
+        for playback_embed in self.playback_embeds.iter() {
+            _ = playback_embed.close().await;
+        }
+
+        self.playback_embeds.clear();
+        self.lyrics_embeds.clear();
+
         // Force close channels, as handles may otherwise hold this struct hostage
         self.commands.close();
         self.events.close();
@@ -525,6 +743,25 @@ impl Session {
     }
 }
 
+/// Notifies the session that an announcement has stopped playing
+#[derive(Clone)]
+struct AnnouncementEnd {
+    id: u64,
+    commands: mpsc::Sender<SessionCommand>,
+}
+
+#[async_trait]
+impl songbird::EventHandler for AnnouncementEnd {
+    async fn act(&self, _: &EventContext<'_>) -> Option<Event> {
+        _ = self
+            .commands
+            .send(SessionCommand::AnnouncementEnded(self.id))
+            .await;
+
+        Some(Event::Cancel)
+    }
+}
+
 impl Drop for Session {
     fn drop(&mut self) {
         // Abort timeout task
@@ -532,10 +769,8 @@ impl Drop for Session {
             _ = tx.send(());
         }
 
-        // Abort lyrics task
-        if let Some(lyrics) = self.lyrics_embed.take() {
-            lyrics.abort();
-        }
+        // Abort lyrics tasks
+        self.lyrics_embeds.clear();
 
         // Clean up the session from the session manager
         // This is done in Drop::drop to ensure that the session always cleans up after itself
@@ -550,6 +785,23 @@ impl Drop for Session {
     }
 }
 
+/// Convert the stored audio preferences of a user and the settings of a server into player options
+fn audio_options(user: &User, guild: &Guild) -> AudioOptions {
+    let normalization = match (user.normalization, user.normalization_type.as_str()) {
+        (false, _) => None,
+        (true, "album") => Some(NormalizationType::Album),
+        (true, "track") => Some(NormalizationType::Track),
+        (true, _) => Some(NormalizationType::Auto),
+    };
+
+    AudioOptions {
+        bitrate: user.bitrate.map(|bitrate| bitrate as u16),
+        normalization,
+        pregain: user.normalization_pregain as f64,
+        crossfade: Duration::from_secs(guild.crossfade.max(0) as u64),
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct SessionHandle {
     guild: GuildId,
@@ -624,12 +876,14 @@ impl SessionHandle {
         &self,
         interaction: &CommandInteraction,
         behavior: playback_embed::UpdateBehavior,
+        locale: Locale,
     ) -> anyhow::Result<()> {
         self.commands
             .send(SessionCommand::CreatePlaybackEmbed(
                 self.clone(),
                 interaction.to_owned(),
                 behavior,
+                locale,
             ))
             .await?;
 
@@ -638,10 +892,30 @@ impl SessionHandle {
 
     /// Create a lyrics embed as a response to an interaction
     ///
-    /// This lyrics embed will automatically retrieve the lyrics and update the embed accordingly
-    pub async fn create_lyrics_embed(&self, interaction: CommandInteraction) -> anyhow::Result<()> {
+    /// This lyrics embed will automatically retrieve the lyrics and update the embed accordingly.
+    /// In karaoke mode, synced lyrics are shown a few lines at a time with the line that is being sung highlighted.
+    pub async fn create_lyrics_embed(
+        &self,
+        interaction: CommandInteraction,
+        karaoke: bool,
+        locale: Locale,
+    ) -> anyhow::Result<()> {
+        self.commands
+            .send(SessionCommand::CreateLyricsEmbed(
+                self.clone(),
+                interaction,
+                karaoke,
+                locale,
+            ))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Play an audio file over the music, replacing any announcement that is still playing
+    pub async fn announce(&self, path: impl Into<PathBuf>) -> anyhow::Result<()> {
         self.commands
-            .send(SessionCommand::CreateLyricsEmbed(self.clone(), interaction))
+            .send(SessionCommand::Announce(path.into()))
             .await?;
 
         Ok(())
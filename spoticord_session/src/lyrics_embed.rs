@@ -1,40 +1,74 @@
-use std::{ops::ControlFlow, time::Duration};
+use std::{borrow::Cow, ops::ControlFlow, time::Duration};
 
 use anyhow::Result;
-use librespot::{
-    core::SpotifyId,
-    metadata::{
-        lyrics::{Line, SyncType},
-        Lyrics,
-    },
-};
+use librespot::core::SpotifyId;
 use log::error;
 use serenity::{
     all::{
-        CommandInteraction, ComponentInteraction, ComponentInteractionCollector, Context,
-        CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse,
-        CreateInteractionResponseMessage, EditMessage, Message,
+        ButtonStyle, CommandInteraction, ComponentInteraction, ComponentInteractionCollector,
+        Context, CreateActionRow, CreateAttachment, CreateButton, CreateEmbed, CreateEmbedFooter,
+        CreateInteractionResponse, CreateInteractionResponseFollowup,
+        CreateInteractionResponseMessage, CreateMessage, EditInteractionResponse, EditMessage,
+        Message,
     },
     futures::StreamExt,
 };
-use spoticord_player::info::PlaybackInfo;
-use spoticord_utils::discord::Colors;
-use tokio::task::JoinHandle;
+use spoticord_i18n::{t, Locale};
+use spoticord_player::{
+    info::PlaybackInfo,
+    lyrics::{LyricsLine, TrackLyrics},
+};
+use spoticord_utils::{
+    discord::Colors,
+    romanize::{can_romanize, romanize, romanize_split},
+};
+use tokio::{task::JoinHandle, time::Instant};
 
 use crate::{Session, SessionHandle};
 
 const PAGE_LENGTH: usize = 3000;
 const TIME_OFFSET: u32 = 1000;
 
+/// The amount of lines that are shown before and after the active line in karaoke mode
+const KARAOKE_BEFORE: usize = 2;
+const KARAOKE_AFTER: usize = 3;
+
+/// How far (in ms) karaoke mode runs ahead of the song, to make up for the time it takes for an edit to show up
+const KARAOKE_LEAD: u32 = 300;
+
+/// The minimum time between two edits of the message, which keeps us well under Discord's rate limits
+const MIN_EDIT_INTERVAL: Duration = Duration::from_millis(1250);
+
+/// The maximum time between checks of the playback state, so track changes and seeking are picked up
+const MAX_TICK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The part of the lyrics that is being sung, which is highlighted in karaoke mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cursor {
+    /// The line that is being sung, or None if the first line hasn't started yet
+    line: Option<usize>,
+
+    /// The amount of characters of the line that have been sung, if the lyrics have syllable timing
+    chars: Option<usize>,
+}
+
 pub struct LyricsEmbed {
-    guild_id: String,
+    id: String,
     ctx: Context,
     session: SessionHandle,
     message: Message,
     track: SpotifyId,
+    locale: Locale,
 
-    lyrics: Option<Lyrics>,
+    lyrics: Option<TrackLyrics>,
     page: usize,
+
+    karaoke: bool,
+    cursor: Cursor,
+    last_edit: Instant,
+
+    /// Whether the lyrics are transliterated to the Latin alphabet
+    romanized: bool,
 }
 
 impl LyricsEmbed {
@@ -42,77 +76,126 @@ impl LyricsEmbed {
         session: &Session,
         handle: SessionHandle,
         interaction: CommandInteraction,
+        karaoke: bool,
+        locale: Locale,
     ) -> Result<Option<JoinHandle<()>>> {
         let ctx = session.context.clone();
 
         if !session.active {
-            respond_not_playing(&ctx, interaction).await?;
+            respond_not_playing(&ctx, interaction, locale).await?;
 
             return Ok(None);
         }
 
         let Some(playback_info) = session.player.playback_info().await? else {
-            respond_not_playing(&ctx, interaction).await?;
+            respond_not_playing(&ctx, interaction, locale).await?;
 
             return Ok(None);
         };
 
-        let guild_id = interaction
-            .guild_id
-            .expect("interaction was outside of a guild")
-            .to_string();
-        let lyrics = session.player.get_lyrics().await?;
-
-        // Send initial message
+        // Looking up the lyrics can take longer than Discord waits for a reply, so the reply is sent once they're found
         interaction
             .create_response(
                 &ctx,
-                CreateInteractionResponse::Message(
-                    CreateInteractionResponseMessage::new()
-                        .embed(lyrics_embed(&lyrics, &playback_info, 0))
-                        .components(vec![lyrics_buttons(&guild_id, &lyrics, 0)]),
-                ),
+                CreateInteractionResponse::Defer(CreateInteractionResponseMessage::new()),
             )
             .await?;
 
-        // Retrieve message instead of editing interaction response, as those tokens are only valid for 15 minutes
-        let message = interaction.get_response(&ctx).await?;
+        let player = session.player.clone();
+        let task = tokio::spawn(async move {
+            let Ok(lyrics) = player.get_lyrics().await else {
+                error!("Failed to retrieve lyrics for lyrics embed");
 
-        let this = Self {
-            guild_id: guild_id.clone(),
-            ctx: ctx.clone(),
-            session: handle,
-            message,
-            track: playback_info.track_id(),
+                _ = interaction
+                    .edit_response(
+                        &ctx,
+                        EditInteractionResponse::new().embed(not_playing_embed(locale)),
+                    )
+                    .await;
 
-            lyrics,
-            page: 0,
-        };
+                return;
+            };
 
-        let collector = ComponentInteractionCollector::new(&ctx)
-            .filter(move |press| {
-                let parts = press.data.custom_id.split(':').collect::<Vec<_>>();
+            // Every embed has its own buttons, as a session can have multiple lyrics embeds
+            let id = interaction.id.to_string();
+            let cursor = karaoke_cursor(&lyrics, playback_info.current_position());
+            let karaoke_view = karaoke.then_some(cursor);
+
+            // Complete the deferred reply, which gives us the message to keep editing afterwards
+            let message = match interaction
+                .edit_response(
+                    &ctx,
+                    EditInteractionResponse::new()
+                        .embed(lyrics_embed(
+                            &lyrics,
+                            &playback_info,
+                            0,
+                            karaoke_view,
+                            false,
+                            locale,
+                        ))
+                        .components(vec![lyrics_buttons(
+                            &id, &lyrics, 0, karaoke, false, locale,
+                        )]),
+                )
+                .await
+            {
+                Ok(message) => message,
+                Err(why) => {
+                    error!("Failed to send lyrics embed: {why}");
 
-                matches!(parts.first(), Some(&"lyrics"))
-                    && matches!(parts.last(), Some(id) if id == &guild_id)
-            })
-            .timeout(Duration::from_secs(3600 * 24));
+                    return;
+                }
+            };
+
+            let this = Self {
+                id: id.clone(),
+                ctx: ctx.clone(),
+                session: handle,
+                message,
+                track: playback_info.track_id(),
+                locale,
+
+                lyrics,
+                page: 0,
+
+                karaoke,
+                cursor,
+                last_edit: Instant::now(),
+
+                romanized: false,
+            };
 
-        let handle = tokio::spawn(this.run(collector));
+            let collector = ComponentInteractionCollector::new(&ctx)
+                .filter(move |press| {
+                    let parts = press.data.custom_id.split(':').collect::<Vec<_>>();
 
-        Ok(Some(handle))
+                    matches!(parts.first(), Some(&"lyrics"))
+                        && matches!(parts.last(), Some(press_id) if press_id == &id)
+                })
+                .timeout(Duration::from_secs(3600 * 24));
+
+            this.run(collector).await;
+        });
+
+        Ok(Some(task))
     }
 
     async fn run(mut self, collector: ComponentInteractionCollector) {
         let mut stream = collector.stream();
-        let mut interval = tokio::time::interval(Duration::from_secs(2));
+
+        // The next tick is scheduled for whenever the lyrics are expected to change
+        let sleep = tokio::time::sleep(MIN_EDIT_INTERVAL);
+        tokio::pin!(sleep);
 
         loop {
             tokio::select! {
-                _ = interval.tick() => {
-                    if self.handle_tick().await.is_break() {
+                _ = &mut sleep => {
+                    let ControlFlow::Continue(delay) = self.handle_tick().await else {
                         break;
-                    }
+                    };
+
+                    sleep.as_mut().reset(Instant::now() + delay);
                 }
 
                 opt_press = stream.next() => {
@@ -133,7 +216,8 @@ impl LyricsEmbed {
         }
     }
 
-    async fn handle_tick(&mut self) -> ControlFlow<(), ()> {
+    /// Check whether the lyrics need to be updated, returning how long to wait until the next check
+    async fn handle_tick(&mut self) -> ControlFlow<(), Duration> {
         let Ok(player) = self.session.player().await else {
             // Failure means that the session is gone, so we quit
             return ControlFlow::Break(());
@@ -141,12 +225,12 @@ impl LyricsEmbed {
 
         if !matches!(self.session.active().await, Ok(true)) {
             // If the session is currently not active, just wait until it becomes active again
-            return ControlFlow::Continue(());
+            return ControlFlow::Continue(MAX_TICK_INTERVAL);
         }
 
         let Ok(Some(playback_info)) = player.playback_info().await else {
             // If we're not playing anything, just wait until we are
-            return ControlFlow::Continue(());
+            return ControlFlow::Continue(MAX_TICK_INTERVAL);
         };
 
         if playback_info.track_id() != self.track {
@@ -164,89 +248,61 @@ impl LyricsEmbed {
             self.lyrics = lyrics;
             self.page = 0;
             self.track = playback_info.track_id();
+            self.cursor = karaoke_cursor(&self.lyrics, playback_info.current_position());
 
-            if let Err(why) = self
-                .message
-                .edit(
-                    &self.ctx,
-                    EditMessage::new()
-                        .embed(lyrics_embed(&self.lyrics, &playback_info, self.page))
-                        .components(vec![lyrics_buttons(
-                            &self.guild_id,
-                            &self.lyrics,
-                            self.page,
-                        )]),
-                )
-                .await
-            {
-                error!("Failed to update lyrics: {why}");
-
-                return ControlFlow::Break(());
-            }
+            self.update(&playback_info).await?;
 
-            return ControlFlow::Continue(());
+            return ControlFlow::Continue(self.next_tick(&playback_info));
         }
 
-        // We're still playing the same song, check if we need to update the page
+        // We're still playing the same song, check if we need to update the page or highlighted line
         let Some(lyrics) = &self.lyrics else {
             // No lyrics in current song, just continue until we have one with
-            return ControlFlow::Continue(());
+            return ControlFlow::Continue(MAX_TICK_INTERVAL);
         };
 
-        if !matches!(lyrics.lyrics.sync_type, SyncType::LineSynced) {
-            // Only synced lyrics should auto-swap to new pages
-            return ControlFlow::Continue(());
+        if !lyrics.synced {
+            // Only synced lyrics should follow the song
+            return ControlFlow::Continue(MAX_TICK_INTERVAL);
         }
 
-        let new_page = page_at_position(lyrics, playback_info.current_position()).unwrap_or(0);
+        let position = playback_info.current_position();
 
-        if new_page != self.page {
-            // We've arrived on a new page: swap em up!
+        if self.karaoke {
+            let cursor = karaoke_cursor(&self.lyrics, position);
 
-            self.page = new_page;
+            if cursor != self.cursor {
+                self.cursor = cursor;
+                self.update(&playback_info).await?;
+            }
+        } else {
+            let new_page =
+                page_at_position(&display_lines(lyrics, self.romanized), position).unwrap_or(0);
 
-            if let Err(why) = self
-                .message
-                .edit(
-                    &self.ctx,
-                    EditMessage::new()
-                        .embed(lyrics_embed(&self.lyrics, &playback_info, new_page))
-                        .components(vec![lyrics_buttons(&self.guild_id, &self.lyrics, new_page)]),
-                )
-                .await
-            {
-                error!("Failed to update lyrics: {why}");
+            if new_page != self.page {
+                // We've arrived on a new page: swap em up!
 
-                return ControlFlow::Break(());
+                self.page = new_page;
+                self.update(&playback_info).await?;
             }
         }
 
-        ControlFlow::Continue(())
+        ControlFlow::Continue(self.next_tick(&playback_info))
     }
 
     async fn handle_press(&mut self, press: ComponentInteraction) -> ControlFlow<(), ()> {
-        let next = match press.data.custom_id.split(':').nth(1) {
-            Some("next") => true,
-            Some("prev") => false,
-            _ => return ControlFlow::Continue(()),
-        };
-
-        let Some(lyrics) = &self.lyrics else {
-            return ControlFlow::Continue(());
-        };
-
-        if !matches!(lyrics.lyrics.sync_type, SyncType::Unsynced) {
-            // Only allow manual swapping if lyrics are unsynced
+        let action = press.data.custom_id.split(':').nth(1);
 
+        if !matches!(
+            action,
+            Some("next" | "prev" | "karaoke" | "romanize" | "dm")
+        ) {
             return ControlFlow::Continue(());
         }
 
-        let length = lyrics
-            .lyrics
-            .lines
-            .iter()
-            .fold(0, |acc, line| acc + line.words.len());
-        let pages = length / PAGE_LENGTH + if length % PAGE_LENGTH > 0 { 1 } else { 0 };
+        let Some(lyrics) = &self.lyrics else {
+            return ControlFlow::Continue(());
+        };
 
         let Ok(player) = self.session.player().await else {
             return ControlFlow::Continue(());
@@ -256,22 +312,112 @@ impl LyricsEmbed {
             return ControlFlow::Continue(());
         };
 
-        match next {
-            true if self.page < pages - 1 => self.page += 1,
-            false if self.page > 0 => self.page -= 1,
+        if action == Some("dm") {
+            let file = lyrics_file(lyrics, &playback_info);
+
+            let result = press
+                .user
+                .direct_message(
+                    &self.ctx,
+                    CreateMessage::new()
+                        .content(t!(self.locale, "lyrics-file", track = playback_info.name()))
+                        .add_file(file),
+                )
+                .await;
+
+            let embed = match result {
+                Ok(_) => CreateEmbed::new()
+                    .description(t!(self.locale, "lyrics-dm-sent"))
+                    .color(Colors::Success),
+                Err(why) => {
+                    error!("Failed to send lyrics to DMs: {why}");
+
+                    CreateEmbed::new()
+                        .title(t!(self.locale, "lyrics-dm-failed-title"))
+                        .description(t!(self.locale, "lyrics-dm-failed-description"))
+                        .color(Colors::Error)
+                }
+            };
+
+            _ = press
+                .create_followup(
+                    &self.ctx,
+                    CreateInteractionResponseFollowup::new()
+                        .embed(embed)
+                        .ephemeral(true),
+                )
+                .await;
+
+            return ControlFlow::Continue(());
+        }
+
+        if action == Some("karaoke") {
+            if !lyrics.synced {
+                // Karaoke mode needs to know when lines are being sung
+                return ControlFlow::Continue(());
+            }
+
+            let position = playback_info.current_position();
+
+            self.karaoke = !self.karaoke;
+            self.cursor = karaoke_cursor(&self.lyrics, position);
+            self.page =
+                page_at_position(&display_lines(lyrics, self.romanized), position).unwrap_or(0);
+
+            return self.update(&playback_info).await;
+        }
+
+        if action == Some("romanize") {
+            // Transliterated lines have different lengths, so stay on the page that shows the same lines
+            let line = first_line_of_page(&display_lines(lyrics, self.romanized), self.page);
+
+            self.romanized = !self.romanized;
+            self.page = page_of_line(&display_lines(lyrics, self.romanized), line);
+
+            return self.update(&playback_info).await;
+        }
+
+        if lyrics.synced {
+            // Only allow manual swapping if lyrics are unsynced
+
+            return ControlFlow::Continue(());
+        }
+
+        let pages = page_count(&display_lines(lyrics, self.romanized));
+
+        match action {
+            Some("next") if self.page < pages - 1 => self.page += 1,
+            Some("prev") if self.page > 0 => self.page -= 1,
             _ => return ControlFlow::Continue(()),
         }
 
+        self.update(&playback_info).await
+    }
+
+    /// Edit the message to show the current state of the lyrics
+    async fn update(&mut self, playback_info: &PlaybackInfo) -> ControlFlow<(), ()> {
+        self.last_edit = Instant::now();
+
         if let Err(why) = self
             .message
             .edit(
                 &self.ctx,
                 EditMessage::new()
-                    .embed(lyrics_embed(&self.lyrics, &playback_info, self.page))
+                    .embed(lyrics_embed(
+                        &self.lyrics,
+                        playback_info,
+                        self.page,
+                        self.karaoke.then_some(self.cursor),
+                        self.romanized,
+                        self.locale,
+                    ))
                     .components(vec![lyrics_buttons(
-                        &self.guild_id,
+                        &self.id,
                         &self.lyrics,
                         self.page,
+                        self.karaoke,
+                        self.romanized,
+                        self.locale,
                     )]),
             )
             .await
@@ -283,15 +429,36 @@ impl LyricsEmbed {
 
         ControlFlow::Continue(())
     }
+
+    /// Determine how long to wait until the lyrics are expected to change, without editing the message too often
+    fn next_tick(&self, playback_info: &PlaybackInfo) -> Duration {
+        let change = match &self.lyrics {
+            Some(lyrics) if lyrics.synced && playback_info.playing() => {
+                next_change(lyrics, playback_info.current_position(), self.karaoke)
+            }
+            _ => None,
+        };
+
+        let earliest = MIN_EDIT_INTERVAL.saturating_sub(self.last_edit.elapsed());
+
+        change
+            .map(|ms| Duration::from_millis(ms as u64))
+            .unwrap_or(MAX_TICK_INTERVAL)
+            .clamp(earliest.max(Duration::from_millis(100)), MAX_TICK_INTERVAL)
+    }
 }
 
-async fn respond_not_playing(context: &Context, interaction: CommandInteraction) -> Result<()> {
+async fn respond_not_playing(
+    context: &Context,
+    interaction: CommandInteraction,
+    locale: Locale,
+) -> Result<()> {
     interaction
         .create_response(
             context,
             CreateInteractionResponse::Message(
                 CreateInteractionResponseMessage::new()
-                    .embed(not_playing_embed())
+                    .embed(not_playing_embed(locale))
                     .ephemeral(true),
             ),
         )
@@ -300,25 +467,23 @@ async fn respond_not_playing(context: &Context, interaction: CommandInteraction)
     Ok(())
 }
 
-fn not_playing_embed() -> CreateEmbed {
+fn not_playing_embed(locale: Locale) -> CreateEmbed {
     CreateEmbed::new()
-        .title("Cannot get lyrics")
-        .description("I'm currently not playing any music in this server.")
+        .title(t!(locale, "lyrics-not-playing-title"))
+        .description(t!(locale, "not-playing"))
         .color(Colors::Error)
 }
 
-fn lyrics_embed(lyrics: &Option<Lyrics>, playback_info: &PlaybackInfo, page: usize) -> CreateEmbed {
+fn lyrics_embed(
+    lyrics: &Option<TrackLyrics>,
+    playback_info: &PlaybackInfo,
+    page: usize,
+    karaoke: Option<Cursor>,
+    romanized: bool,
+    locale: Locale,
+) -> CreateEmbed {
     match (lyrics, playback_info.artists()) {
         (Some(lyrics), Some(artists)) => {
-            let length = lyrics
-                .lyrics
-                .lines
-                .iter()
-                .fold(0, |acc, line| acc + line.words.len());
-
-            let page = &into_pages(&lyrics.lyrics.lines)
-                [if page * PAGE_LENGTH > length { 0 } else { page }];
-
             let title = format!(
                 "{} - {}",
                 playback_info.name(),
@@ -330,16 +495,27 @@ fn lyrics_embed(lyrics: &Option<Lyrics>, playback_info: &PlaybackInfo, page: usi
                     .join(", "),
             );
 
-            let description = page
-                .iter()
-                .map(|page| page.words.replace('♪', "\n♪\n"))
-                .collect::<Vec<_>>()
-                .join("\n");
+            let description = match karaoke {
+                Some(cursor) if lyrics.synced => karaoke_description(lyrics, cursor, romanized),
+                _ => {
+                    let pages = into_pages(&display_lines(lyrics, romanized));
+                    let page = &pages[if page < pages.len() { page } else { 0 }];
+
+                    page.iter()
+                        .map(|page| page.words.replace('♪', "\n♪\n"))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                }
+            };
 
-            let mut footer = format!("Lyrics provided by {}", lyrics.lyrics.provider_display_name);
+            let mut footer = t!(
+                locale,
+                "lyrics-attribution",
+                provider = lyrics.attribution()
+            );
 
-            if matches!(lyrics.lyrics.sync_type, SyncType::LineSynced) {
-                footer.push_str(" | Synced to song");
+            if lyrics.synced {
+                footer.push_str(&format!(" | {}", t!(locale, "lyrics-synced")));
             }
 
             CreateEmbed::new()
@@ -349,30 +525,29 @@ fn lyrics_embed(lyrics: &Option<Lyrics>, playback_info: &PlaybackInfo, page: usi
                 .color(Colors::Info)
         }
         _ => CreateEmbed::new()
-            .title("No lyrics available")
-            .description("This current track has no lyrics available. Just enjoy the tunes!")
+            .title(t!(locale, "lyrics-unavailable-title"))
+            .description(t!(locale, "lyrics-unavailable-description"))
             .color(Colors::Info),
     }
 }
 
-fn lyrics_buttons(id: &str, lyrics: &Option<Lyrics>, page: usize) -> CreateActionRow {
-    let (can_prev, can_next) = match lyrics {
-        Some(lyrics) => match lyrics.lyrics.sync_type {
-            SyncType::Unsynced => {
-                // Only unsynced lyrics can have its pages flipped through by the user
-
-                let length = lyrics
-                    .lyrics
-                    .lines
-                    .iter()
-                    .fold(0, |acc, line| acc + line.words.len());
-                let pages = length / PAGE_LENGTH + if length % PAGE_LENGTH > 0 { 1 } else { 0 };
-
-                (page > 0, page < pages - 1)
-            }
-            SyncType::LineSynced => (false, false),
-        },
-        None => (false, false),
+fn lyrics_buttons(
+    id: &str,
+    lyrics: &Option<TrackLyrics>,
+    page: usize,
+    karaoke: bool,
+    romanized: bool,
+    locale: Locale,
+) -> CreateActionRow {
+    let (can_prev, can_next, can_karaoke) = match lyrics {
+        // Only unsynced lyrics can have its pages flipped through by the user
+        Some(lyrics) if !lyrics.synced => {
+            let pages = page_count(&display_lines(lyrics, romanized));
+
+            (page > 0, page < pages - 1, false)
+        }
+        Some(_) => (false, false, true),
+        None => (false, false, false),
     };
 
     CreateActionRow::Buttons(vec![
@@ -382,10 +557,150 @@ fn lyrics_buttons(id: &str, lyrics: &Option<Lyrics>, page: usize) -> CreateActio
         CreateButton::new(format!("lyrics:next:{id}"))
             .disabled(!can_next)
             .label(">"),
+        CreateButton::new(format!("lyrics:karaoke:{id}"))
+            .disabled(!can_karaoke)
+            .style(if karaoke {
+                ButtonStyle::Success
+            } else {
+                ButtonStyle::Secondary
+            })
+            .label(t!(locale, "lyrics-karaoke")),
+        CreateButton::new(format!("lyrics:romanize:{id}"))
+            .disabled(
+                !lyrics.as_ref().is_some_and(|lyrics| {
+                    lyrics.lines.iter().any(|line| can_romanize(&line.words))
+                }),
+            )
+            .style(if romanized {
+                ButtonStyle::Success
+            } else {
+                ButtonStyle::Secondary
+            })
+            .label(t!(locale, "lyrics-romanize")),
+        CreateButton::new(format!("lyrics:dm:{id}"))
+            .disabled(lyrics.is_none())
+            .style(ButtonStyle::Secondary)
+            .label(t!(locale, "lyrics-send-to-dms")),
     ])
 }
 
-fn into_pages(lines: &[Line]) -> Vec<Vec<Line>> {
+/// Create a file with the complete lyrics, which is an `.lrc` file with timestamps if the lyrics are synced
+pub fn lyrics_file(lyrics: &TrackLyrics, playback_info: &PlaybackInfo) -> CreateAttachment {
+    let name = playback_info.name();
+    let artists = playback_info
+        .artists()
+        .map(|artists| {
+            artists
+                .0
+                .into_iter()
+                .map(|artist| artist.name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .unwrap_or_default();
+
+    let title = if artists.is_empty() {
+        name.clone()
+    } else {
+        format!("{artists} - {name}")
+    };
+
+    let mut content = String::new();
+
+    let extension = if lyrics.synced {
+        content.push_str(&format!("[ti:{name}]\n"));
+        content.push_str(&format!("[ar:{artists}]\n"));
+
+        if let Some(album) = playback_info.album_name() {
+            content.push_str(&format!("[al:{album}]\n"));
+        }
+
+        let duration = playback_info.duration() / 1000;
+        content.push_str(&format!(
+            "[length:{:02}:{:02}]\n",
+            duration / 60,
+            duration % 60
+        ));
+
+        for line in &lyrics.lines {
+            let time = line.start_time_ms;
+
+            content.push_str(&format!(
+                "[{:02}:{:02}.{:02}]{}\n",
+                time / 60_000,
+                time / 1000 % 60,
+                time % 1000 / 10,
+                line.words
+            ));
+        }
+
+        "lrc"
+    } else {
+        content.push_str(&format!("{title}\n\n"));
+
+        for line in &lyrics.lines {
+            content.push_str(&line.words);
+            content.push('\n');
+        }
+
+        "txt"
+    };
+
+    // Keep the file name free of characters that aren't allowed on every platform
+    let filename = title
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c => c,
+        })
+        .collect::<String>();
+
+    CreateAttachment::bytes(content, format!("{filename}.{extension}"))
+}
+
+/// The lines as they are shown, which are transliterated to the Latin alphabet if `romanized` is set
+fn display_lines(lyrics: &TrackLyrics, romanized: bool) -> Cow<'_, [LyricsLine]> {
+    if !romanized {
+        return Cow::Borrowed(&lyrics.lines);
+    }
+
+    Cow::Owned(
+        lyrics
+            .lines
+            .iter()
+            .map(|line| LyricsLine {
+                words: romanize(&line.words),
+                ..line.clone()
+            })
+            .collect(),
+    )
+}
+
+fn page_count(lines: &[LyricsLine]) -> usize {
+    into_pages(lines).len()
+}
+
+/// The index of the first line that is shown on `page`
+fn first_line_of_page(lines: &[LyricsLine], page: usize) -> usize {
+    into_pages(lines).iter().take(page).map(Vec::len).sum()
+}
+
+/// The page that shows the line at `index`
+fn page_of_line(lines: &[LyricsLine], index: usize) -> usize {
+    let mut seen = 0;
+
+    for (page, current) in into_pages(lines).iter().enumerate() {
+        seen += current.len();
+
+        if index < seen {
+            return page;
+        }
+    }
+
+    0
+}
+
+fn into_pages(lines: &[LyricsLine]) -> Vec<Vec<LyricsLine>> {
     let mut result = vec![];
     let mut current = vec![];
     let mut current_position = 0;
@@ -406,18 +721,12 @@ fn into_pages(lines: &[Line]) -> Vec<Vec<Line>> {
     result
 }
 
-fn page_at_position(lyrics: &Lyrics, position: u32) -> Option<usize> {
-    let pages = into_pages(&lyrics.lyrics.lines);
+fn page_at_position(lines: &[LyricsLine], position: u32) -> Option<usize> {
+    let pages = into_pages(lines);
 
     for (i, line) in pages.iter().enumerate() {
         if let Some(first) = line.first() {
-            let Ok(time) = first
-                .start_time_ms
-                .parse::<u32>()
-                .map(|v| v.saturating_sub(TIME_OFFSET))
-            else {
-                return None;
-            };
+            let time = first.start_time_ms.saturating_sub(TIME_OFFSET);
 
             if position < time {
                 return Some(if i == 0 { 0 } else { i - 1 });
@@ -425,17 +734,8 @@ fn page_at_position(lyrics: &Lyrics, position: u32) -> Option<usize> {
         }
 
         if let (Some(first), Some(last)) = (line.first(), line.last()) {
-            let (Ok(first), Ok(last)) = (
-                first
-                    .start_time_ms
-                    .parse::<u32>()
-                    .map(|v| v.saturating_sub(TIME_OFFSET)),
-                last.start_time_ms
-                    .parse::<u32>()
-                    .map(|v| v.saturating_sub(TIME_OFFSET)),
-            ) else {
-                return None;
-            };
+            let first = first.start_time_ms.saturating_sub(TIME_OFFSET);
+            let last = last.start_time_ms.saturating_sub(TIME_OFFSET);
 
             if position >= first && position <= last {
                 return Some(i);
@@ -445,3 +745,119 @@ fn page_at_position(lyrics: &Lyrics, position: u32) -> Option<usize> {
 
     Some(pages.len() - 1)
 }
+
+/// Find the line, and the part of that line, that is being sung at `position`
+fn karaoke_cursor(lyrics: &Option<TrackLyrics>, position: u32) -> Cursor {
+    let Some(lyrics) = lyrics else {
+        return Cursor {
+            line: None,
+            chars: None,
+        };
+    };
+
+    let position = position + KARAOKE_LEAD;
+
+    let line = lyrics
+        .lines
+        .iter()
+        .rposition(|line| line.start_time_ms <= position);
+
+    let chars = line
+        .map(|line| &lyrics.lines[line].syllables)
+        .filter(|syllables| !syllables.is_empty())
+        .map(|syllables| {
+            syllables
+                .iter()
+                .take_while(|syllable| syllable.start_time_ms <= position)
+                .map(|syllable| syllable.num_chars)
+                .sum()
+        });
+
+    Cursor { line, chars }
+}
+
+/// The amount of ms until the next line (or syllable, in karaoke mode) starts
+fn next_change(lyrics: &TrackLyrics, position: u32, karaoke: bool) -> Option<u32> {
+    let (position, offset) = if karaoke {
+        (position + KARAOKE_LEAD, 0)
+    } else {
+        (position, TIME_OFFSET)
+    };
+
+    let lines = lyrics
+        .lines
+        .iter()
+        .map(|line| line.start_time_ms.saturating_sub(offset));
+
+    let syllables = lyrics
+        .lines
+        .iter()
+        .flat_map(|line| &line.syllables)
+        .filter(|_| karaoke)
+        .map(|syllable| syllable.start_time_ms);
+
+    lines
+        .chain(syllables)
+        .filter(|time| *time > position)
+        .min()
+        .map(|time| time - position)
+}
+
+/// Show the lines around the line that is being sung, with the part that has been sung in bold
+fn karaoke_description(lyrics: &TrackLyrics, cursor: Cursor, romanized: bool) -> String {
+    // Syllable timing refers to the original text, so lines are only transliterated right before they are shown
+    let display = |text: &str| {
+        if romanized {
+            romanize(text)
+        } else {
+            text.to_string()
+        }
+    };
+
+    let lines = &lyrics.lines;
+    let current = cursor.line.unwrap_or(0);
+    let start = current.saturating_sub(KARAOKE_BEFORE);
+    let end = (current + KARAOKE_AFTER + 1).min(lines.len());
+
+    lines[start..end]
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            if line.words.trim().is_empty() {
+                return "♪".to_string();
+            }
+
+            if cursor.line != Some(start + i) {
+                return display(&line.words);
+            }
+
+            let Some(chars) = cursor.chars else {
+                return format!("**{}**", display(line.words.trim()));
+            };
+
+            // Split on characters, as the syllable lengths are in characters instead of bytes
+            let split = line
+                .words
+                .char_indices()
+                .nth(chars)
+                .map(|(index, _)| index)
+                .unwrap_or(line.words.len());
+
+            // Romanize the line as a whole, as a syllable can span the split, like っち or きゃ
+            let (sung, rest) = if romanized {
+                romanize_split(&line.words, split)
+            } else {
+                let (sung, rest) = line.words.split_at(split);
+                (sung.to_string(), rest.to_string())
+            };
+            let bold = sung.trim_end();
+
+            if bold.is_empty() {
+                format!("{sung}{rest}")
+            } else {
+                format!("**{bold}**{}{rest}", &sung[bold.len()..])
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
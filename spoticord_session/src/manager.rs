@@ -2,9 +2,11 @@ use super::{Session, SessionHandle};
 use crate::error::Result;
 use serenity::all::{ChannelId, GuildId, UserId};
 use songbird::Songbird;
-use spoticord_database::Database;
+use spoticord_database::{store::Store, Database};
+use spoticord_i18n::Locale;
 use std::{
     collections::HashMap,
+    future::Future,
     sync::{Arc, Mutex},
 };
 
@@ -22,6 +24,31 @@ pub enum SessionQuery {
     Owner(UserId),
 }
 
+/// The parts of a session manager that commands base their decisions on.
+///
+/// This allows command logic to be used with the in-memory sessions that are available with the `testing` feature.
+pub trait Sessions: Send + Sync {
+    type Session: SessionState;
+    type Database: Store;
+
+    fn get_session(&self, query: SessionQuery) -> Option<Self::Session>;
+
+    fn database(&self) -> Self::Database;
+}
+
+/// The state of a session that commands base their decisions on
+pub trait SessionState: Clone + Send + Sync {
+    fn guild(&self) -> GuildId;
+
+    fn voice_channel(&self) -> ChannelId;
+
+    /// Retrieve the current owner of the session
+    fn owner(&self) -> impl Future<Output = anyhow::Result<UserId>> + Send;
+
+    /// Whether the session has an active player
+    fn active(&self) -> impl Future<Output = anyhow::Result<bool>> + Send;
+}
+
 impl SessionManager {
     pub fn new(songbird: Arc<Songbird>, database: Database) -> Self {
         Self {
@@ -40,6 +67,7 @@ impl SessionManager {
         voice_channel_id: ChannelId,
         text_channel_id: ChannelId,
         owner: UserId,
+        locale: Locale,
     ) -> Result<SessionHandle> {
         let handle = Session::create(
             self.clone(),
@@ -48,6 +76,7 @@ impl SessionManager {
             voice_channel_id,
             text_channel_id,
             owner,
+            locale,
         )
         .await?;
 
@@ -122,4 +151,49 @@ impl SessionManager {
     pub fn database(&self) -> Database {
         self.database.clone()
     }
+
+    /// Determine the language to respond in, which is the language of the server if it has been set, or otherwise the
+    /// language of the user.
+    pub async fn locale(&self, guild: Option<GuildId>, user_locale: Option<&str>) -> Locale {
+        if let Some(guild) = guild {
+            if let Ok(guild) = self.database.get_guild(guild.to_string()).await {
+                if let Some(locale) = guild.locale.as_deref().and_then(Locale::exact) {
+                    return locale;
+                }
+            }
+        }
+
+        user_locale.map(Locale::new).unwrap_or_default()
+    }
+}
+
+impl Sessions for SessionManager {
+    type Session = SessionHandle;
+    type Database = Database;
+
+    fn get_session(&self, query: SessionQuery) -> Option<SessionHandle> {
+        SessionManager::get_session(self, query)
+    }
+
+    fn database(&self) -> Database {
+        SessionManager::database(self)
+    }
+}
+
+impl SessionState for SessionHandle {
+    fn guild(&self) -> GuildId {
+        SessionHandle::guild(self)
+    }
+
+    fn voice_channel(&self) -> ChannelId {
+        SessionHandle::voice_channel(self)
+    }
+
+    async fn owner(&self) -> anyhow::Result<UserId> {
+        SessionHandle::owner(self).await
+    }
+
+    async fn active(&self) -> anyhow::Result<bool> {
+        SessionHandle::active(self).await
+    }
 }
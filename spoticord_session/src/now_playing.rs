@@ -0,0 +1,325 @@
+use std::{collections::HashMap, io::Cursor, sync::LazyLock, time::Duration};
+
+use anyhow::Result;
+use image::{
+    imageops::{self, FilterType},
+    DynamicImage, ImageFormat, Rgba, RgbaImage,
+};
+use reqwest::Client;
+use serenity::all::Colour;
+use spoticord_player::info::PlaybackInfo;
+use spoticord_utils::discord::Colors;
+
+/// The name of the attachment, which embeds refer to using `attachment://now-playing.png`
+pub const FILE_NAME: &str = "now-playing.png";
+
+const WIDTH: u32 = 640;
+const HEIGHT: u32 = 160;
+const PADDING: u32 = 16;
+const COVER_SIZE: u32 = HEIGHT - PADDING * 2;
+
+const BAR_HEIGHT: u32 = 8;
+const KNOB_RADIUS: u32 = 9;
+const ICON_SIZE: u32 = 36;
+
+static CLIENT: LazyLock<Client> = LazyLock::new(|| {
+    Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .unwrap_or_default()
+});
+
+/// The cover art of a track, which is downloaded once and reused for every update of an embed
+pub struct Artwork {
+    url: String,
+    cover: RgbaImage,
+    accent: [u8; 3],
+}
+
+impl Artwork {
+    pub async fn fetch(url: &str) -> Result<Self> {
+        let bytes = CLIENT
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        Ok(Self::new(url, &image::load_from_memory(&bytes)?))
+    }
+
+    fn new(url: &str, image: &DynamicImage) -> Self {
+        let accent = dominant_colour(image).unwrap_or_else(|| {
+            let [_, r, g, b] = (Colors::Info as u32).to_be_bytes();
+
+            [r, g, b]
+        });
+
+        Self {
+            url: url.to_string(),
+            cover: image
+                .resize_to_fill(COVER_SIZE, COVER_SIZE, FilterType::Triangle)
+                .to_rgba8(),
+            accent,
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The most prominent colour of the artwork, used as the accent of the card and the embed
+    pub fn colour(&self) -> Colour {
+        let [r, g, b] = self.accent;
+
+        Colour::from_rgb(r, g, b)
+    }
+}
+
+/// Render the card that shows the artwork and playback progress of the current track, encoded as PNG
+pub fn render(artwork: &Artwork, playback_info: &PlaybackInfo) -> Result<Vec<u8>> {
+    let background = mix(artwork.accent, [0, 0, 0], 0.75);
+    let track = mix(background, [255, 255, 255], 0.2);
+    let elapsed = mix(artwork.accent, [255, 255, 255], 0.3);
+
+    let mut card = RgbaImage::from_pixel(WIDTH, HEIGHT, rgba(background));
+    imageops::overlay(&mut card, &artwork.cover, PADDING as i64, PADDING as i64);
+
+    let left = PADDING * 2 + COVER_SIZE;
+    let right = WIDTH - PADDING * 2;
+
+    if playback_info.playing() {
+        draw_play_icon(&mut card, left, PADDING * 2, elapsed);
+    } else {
+        draw_pause_icon(&mut card, left, PADDING * 2, elapsed);
+    }
+
+    let duration = playback_info.duration().max(1) as u64;
+    let position = (playback_info.current_position() as u64).min(duration);
+    let filled = left + ((right - left) as u64 * position / duration) as u32;
+
+    let bar_top = HEIGHT - PADDING * 2 - BAR_HEIGHT / 2;
+    fill_rect(&mut card, left, bar_top, right - left, BAR_HEIGHT, track);
+    fill_rect(&mut card, left, bar_top, filled - left, BAR_HEIGHT, elapsed);
+    fill_circle(
+        &mut card,
+        filled,
+        bar_top + BAR_HEIGHT / 2,
+        KNOB_RADIUS,
+        [255, 255, 255],
+    );
+
+    let mut png = vec![];
+    DynamicImage::ImageRgba8(card).write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+
+    Ok(png)
+}
+
+/// Find the most common colourful shade in an image, ignoring greys and near-black or near-white pixels
+fn dominant_colour(image: &DynamicImage) -> Option<[u8; 3]> {
+    let small = image.resize_exact(32, 32, FilterType::Triangle).to_rgb8();
+
+    // Shades are grouped by their 3 most significant bits per channel, and averaged within that group
+    let mut shades: HashMap<[u8; 3], (u32, [u32; 3])> = HashMap::new();
+
+    for pixel in small.pixels() {
+        let [r, g, b] = pixel.0;
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+
+        if max < 40 || min > 215 || max - min < 30 {
+            continue;
+        }
+
+        let (count, sum) = shades.entry([r >> 5, g >> 5, b >> 5]).or_default();
+        *count += 1;
+        sum[0] += r as u32;
+        sum[1] += g as u32;
+        sum[2] += b as u32;
+    }
+
+    let (count, sum) = shades.into_values().max_by_key(|(count, _)| *count)?;
+
+    Some(sum.map(|channel| (channel / count) as u8))
+}
+
+fn draw_play_icon(card: &mut RgbaImage, x: u32, y: u32, colour: [u8; 3]) {
+    let width = ICON_SIZE * 7 / 8;
+
+    // A triangle pointing right, drawn as columns that get shorter towards the tip
+    for dx in 0..width {
+        let height = ICON_SIZE * (width - dx) / width;
+
+        fill_rect(
+            card,
+            x + dx,
+            y + (ICON_SIZE - height) / 2,
+            1,
+            height,
+            colour,
+        );
+    }
+}
+
+fn draw_pause_icon(card: &mut RgbaImage, x: u32, y: u32, colour: [u8; 3]) {
+    let bar_width = ICON_SIZE / 3;
+
+    fill_rect(card, x, y, bar_width, ICON_SIZE, colour);
+    fill_rect(card, x + bar_width * 2, y, bar_width, ICON_SIZE, colour);
+}
+
+fn fill_rect(card: &mut RgbaImage, x: u32, y: u32, width: u32, height: u32, colour: [u8; 3]) {
+    for py in y..(y + height).min(card.height()) {
+        for px in x..(x + width).min(card.width()) {
+            card.put_pixel(px, py, rgba(colour));
+        }
+    }
+}
+
+fn fill_circle(card: &mut RgbaImage, cx: u32, cy: u32, radius: u32, colour: [u8; 3]) {
+    let (cx, cy, radius) = (cx as i64, cy as i64, radius as i64);
+
+    for py in (cy - radius).max(0)..(cy + radius + 1).min(card.height() as i64) {
+        for px in (cx - radius).max(0)..(cx + radius + 1).min(card.width() as i64) {
+            if (px - cx).pow(2) + (py - cy).pow(2) <= radius.pow(2) {
+                card.put_pixel(px as u32, py as u32, rgba(colour));
+            }
+        }
+    }
+}
+
+/// Blend `from` towards `to`, where an `amount` of 1.0 results in `to`
+fn mix(from: [u8; 3], to: [u8; 3], amount: f32) -> [u8; 3] {
+    std::array::from_fn(|i| (from[i] as f32 + (to[i] as f32 - from[i] as f32) * amount) as u8)
+}
+
+fn rgba([r, g, b]: [u8; 3]) -> Rgba<u8> {
+    Rgba([r, g, b, 255])
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{ImageReader, RgbImage};
+    use librespot::{
+        core::SpotifyId,
+        metadata::{
+            artist::ArtistsWithRole,
+            audio::{AudioFiles, AudioItem, UniqueFields},
+        },
+    };
+
+    use super::*;
+
+    const RED: [u8; 3] = [200, 40, 40];
+
+    fn solid(colour: [u8; 3]) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_pixel(300, 300, image::Rgb(colour)))
+    }
+
+    fn playback_info(position: u32, playing: bool) -> PlaybackInfo {
+        let uri = "spotify:track:4uLU6hMCjMI75M1A2tKUQC";
+
+        PlaybackInfo::new(
+            AudioItem {
+                track_id: SpotifyId::from_uri(uri).expect("invalid uri"),
+                uri: uri.to_string(),
+                files: AudioFiles::default(),
+                name: "Name".to_string(),
+                covers: vec![],
+                language: vec![],
+                duration_ms: 180_000,
+                is_explicit: false,
+                availability: Ok(()),
+                alternatives: None,
+                unique_fields: UniqueFields::Track {
+                    artists: ArtistsWithRole::default(),
+                    album: "Album".to_string(),
+                    album_artists: vec![],
+                    popularity: 50,
+                    number: 3,
+                    disc_number: 1,
+                },
+            },
+            position,
+            playing,
+        )
+    }
+
+    #[test]
+    fn dominant_colour_of_solid_image() {
+        assert_eq!(dominant_colour(&solid(RED)), Some(RED));
+    }
+
+    #[test]
+    fn dominant_colour_ignores_greys() {
+        assert_eq!(dominant_colour(&solid([128, 128, 128])), None);
+        assert_eq!(dominant_colour(&solid([250, 250, 250])), None);
+        assert_eq!(dominant_colour(&solid([10, 0, 20])), None);
+
+        // A grey border around a coloured centre, where most of the pixels are grey
+        let mut image = RgbImage::from_pixel(300, 300, image::Rgb([128, 128, 128]));
+        for (x, y, pixel) in image.enumerate_pixels_mut() {
+            if (100..200).contains(&x) && (100..200).contains(&y) {
+                *pixel = image::Rgb(RED);
+            }
+        }
+
+        // Pixels at the edge of the centre are blended with the border when the image is scaled down
+        let colour = dominant_colour(&DynamicImage::ImageRgb8(image)).expect("expected a colour");
+        assert!(
+            (0..3).all(|i| colour[i].abs_diff(RED[i]) <= 4),
+            "{colour:?}"
+        );
+    }
+
+    #[test]
+    fn artwork_without_colour_uses_default() {
+        let artwork = Artwork::new("https://i.scdn.co/image/cover", &solid([0, 0, 0]));
+
+        assert_eq!(artwork.colour(), Colour::from(Colors::Info as u32));
+    }
+
+    #[test]
+    fn renders_png_card() {
+        let artwork = Artwork::new("https://i.scdn.co/image/cover", &solid(RED));
+        assert_eq!(artwork.colour(), Colour::from_rgb(RED[0], RED[1], RED[2]));
+
+        for playing in [true, false] {
+            let png = render(&artwork, &playback_info(90_000, playing)).unwrap();
+
+            let card = ImageReader::new(Cursor::new(&png))
+                .with_guessed_format()
+                .unwrap();
+            assert_eq!(card.format(), Some(ImageFormat::Png));
+
+            let card = card.decode().unwrap().to_rgba8();
+            assert_eq!(card.dimensions(), (WIDTH, HEIGHT));
+
+            // The cover is drawn on a darkened background of the accent colour
+            assert_eq!(card.get_pixel(PADDING + 1, PADDING + 1), &rgba(RED));
+            assert_eq!(
+                card.get_pixel(WIDTH - 1, 0),
+                &rgba(mix(RED, [0, 0, 0], 0.75))
+            );
+        }
+    }
+
+    #[test]
+    fn progress_bar_follows_position() {
+        let artwork = Artwork::new("https://i.scdn.co/image/cover", &solid(RED));
+        let elapsed = rgba(mix(RED, [255, 255, 255], 0.3));
+
+        let left = PADDING * 2 + COVER_SIZE;
+        let right = WIDTH - PADDING * 2;
+        let y = HEIGHT - PADDING * 2;
+
+        // Halfway through the track, away from the knob
+        let png = render(&artwork, &playback_info(90_000, false)).unwrap();
+        let card = image::load_from_memory(&png).unwrap().to_rgba8();
+        let middle = (left + right) / 2;
+
+        assert_eq!(card.get_pixel(middle - KNOB_RADIUS * 2, y), &elapsed);
+        assert_ne!(card.get_pixel(middle + KNOB_RADIUS * 2, y), &elapsed);
+    }
+}
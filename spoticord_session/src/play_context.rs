@@ -0,0 +1,210 @@
+//! The playlist, album, artist or show that tracks are being played from.
+//!
+//! librespot does not tell the player where the tracks it plays come from, so this is looked up in the Spotify Web API
+//! using the account of the owner of a session.
+
+use std::{sync::LazyLock, time::Duration};
+
+use anyhow::Result;
+use reqwest::{Client, StatusCode};
+use serde_json::Value;
+
+const API_URL: &str = "https://api.spotify.com/v1";
+
+static CLIENT: LazyLock<Client> = LazyLock::new(|| {
+    Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .unwrap_or_default()
+});
+
+/// Where the current track is being played from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayContext {
+    /// The type of the context, which is one of `playlist`, `album`, `artist` or `show`
+    pub kind: String,
+    pub name: String,
+
+    /// The link to the context on Spotify
+    pub url: String,
+}
+
+impl PlayContext {
+    /// Look up what the user with the given access token is playing from.
+    ///
+    /// This returns None if the user is not playing from a context, like when playing from their liked songs.
+    pub async fn fetch(access_token: &str) -> Result<Option<Self>> {
+        Self::fetch_from(API_URL, access_token).await
+    }
+
+    async fn fetch_from(api_url: &str, access_token: &str) -> Result<Option<Self>> {
+        let response = CLIENT
+            .get(format!("{api_url}/me/player"))
+            .bearer_auth(access_token)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        // Spotify responds without content when nothing is being played
+        if response.status() == StatusCode::NO_CONTENT {
+            return Ok(None);
+        }
+
+        let player: Value = serde_json::from_slice(&response.bytes().await?)?;
+        let context = &player["context"];
+
+        let (Some(kind), Some(href), Some(url)) = (
+            context["type"].as_str(),
+            context["href"].as_str(),
+            context["external_urls"]["spotify"].as_str(),
+        ) else {
+            return Ok(None);
+        };
+
+        if !matches!(kind, "playlist" | "album" | "artist" | "show") {
+            return Ok(None);
+        }
+
+        // The context only refers to the object it was played from, which has to be retrieved for its name
+        let mut request = CLIENT.get(href).bearer_auth(access_token);
+
+        if kind == "playlist" {
+            // Playlists include all of their tracks, unless only specific fields are asked for
+            request = request.query(&[("fields", "name")]);
+        }
+
+        let object: Value =
+            serde_json::from_slice(&request.send().await?.error_for_status()?.bytes().await?)?;
+
+        let Some(name) = object["name"]
+            .as_str()
+            .filter(|name| !name.trim().is_empty())
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(Self {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            url: url.to_string(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        task::JoinHandle,
+    };
+
+    use super::*;
+
+    /// Serve the given responses one request at a time, returning the url of the server and the requests it received
+    async fn serve(
+        responses: impl FnOnce(&str) -> Vec<(u16, String)>,
+    ) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let responses = responses(&url);
+
+        let requests = tokio::spawn(async move {
+            let mut requests = vec![];
+
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = vec![0; 4096];
+                let len = stream.read(&mut request).await.unwrap();
+                requests.push(String::from_utf8_lossy(&request[..len]).to_lowercase());
+
+                let response = format!(
+                    "HTTP/1.1 {status} OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+
+            requests
+        });
+
+        (url, requests)
+    }
+
+    #[tokio::test]
+    async fn playlist() {
+        let (url, requests) = serve(|url| {
+            let player = json!({
+                "context": {
+                    "type": "playlist",
+                    "href": format!("{url}/playlists/abc"),
+                    "external_urls": { "spotify": "https://open.spotify.com/playlist/abc" },
+                },
+            });
+
+            vec![
+                (200, player.to_string()),
+                (200, json!({ "name": "Road trip" }).to_string()),
+            ]
+        })
+        .await;
+
+        let context = PlayContext::fetch_from(&url, "token").await.unwrap();
+
+        assert_eq!(
+            context,
+            Some(PlayContext {
+                kind: "playlist".into(),
+                name: "Road trip".into(),
+                url: "https://open.spotify.com/playlist/abc".into(),
+            })
+        );
+
+        let requests = requests.await.unwrap();
+        assert!(requests[0].starts_with("get /me/player "));
+        assert!(requests[1].starts_with("get /playlists/abc?fields=name "));
+        assert!(requests
+            .iter()
+            .all(|request| request.contains("authorization: bearer token")));
+    }
+
+    #[tokio::test]
+    async fn nothing_playing() {
+        let (url, _) = serve(|_| vec![(204, String::new())]).await;
+
+        assert_eq!(PlayContext::fetch_from(&url, "token").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn without_context() {
+        // Liked songs are played without a context, and collections are not a kind that is shown
+        let (url, _) = serve(|_| {
+            vec![
+                (200, json!({ "context": null }).to_string()),
+                (
+                    200,
+                    json!({
+                        "context": {
+                            "type": "collection",
+                            "href": "https://api.spotify.com/v1/me/tracks",
+                            "external_urls": { "spotify": "https://open.spotify.com/collection/tracks" },
+                        },
+                    })
+                    .to_string(),
+                ),
+            ]
+        })
+        .await;
+
+        assert_eq!(PlayContext::fetch_from(&url, "token").await.unwrap(), None);
+        assert_eq!(PlayContext::fetch_from(&url, "token").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn unauthorized() {
+        let (url, _) = serve(|_| vec![(401, String::new())]).await;
+
+        assert!(PlayContext::fetch_from(&url, "token").await.is_err());
+    }
+}
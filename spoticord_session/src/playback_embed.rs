@@ -1,25 +1,32 @@
 use anyhow::{anyhow, Result};
-use log::{error, trace};
+use librespot::core::SpotifyId;
+use log::{error, trace, warn};
 use poise::ChoiceParameter;
-use serenity::{
-    all::{
-        ButtonStyle, CommandInteraction, ComponentInteraction, ComponentInteractionCollector,
-        Context, CreateActionRow, CreateButton, CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter,
-        CreateInteractionResponse, CreateInteractionResponseFollowup,
-        CreateInteractionResponseMessage, CreateMessage, EditMessage, Message, User,
-    },
-    futures::StreamExt,
+use serenity::all::{
+    ButtonStyle, Colour, CommandInteraction, ComponentInteraction, Context, CreateActionRow,
+    CreateAttachment, CreateButton, CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter,
+    CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, EditAttachments,
+    EditMessage, GuildId, Message, User, UserId,
 };
+use spoticord_i18n::{t, Locale};
 use spoticord_player::{info::PlaybackInfo, PlayerHandle};
-use spoticord_utils::discord::Colors;
+use spoticord_utils::discord::{escape, Colors};
 use std::{ops::ControlFlow, time::Duration};
 use tokio::{sync::mpsc, time::Instant};
 
-use crate::{Session, SessionHandle};
+use crate::{
+    manager::{SessionManager, SessionQuery},
+    now_playing::{self, Artwork},
+    play_context::PlayContext,
+    Session, SessionHandle,
+};
 
 #[derive(Debug)]
 pub enum Command {
     InvokeUpdate(bool),
+
+    /// Show that nothing is being played anymore, and stop updating the embed
+    Close,
 }
 
 #[derive(Debug, Default, ChoiceParameter)]
@@ -46,10 +53,18 @@ impl UpdateBehavior {
 }
 
 pub struct PlaybackEmbed {
-    id: u64,
+    guild_id: GuildId,
     ctx: Context,
+    session_manager: SessionManager,
     session: SessionHandle,
     message: Message,
+    locale: Locale,
+
+    /// The artwork of the current track, kept around so that it is only downloaded once per track
+    artwork: Option<Artwork>,
+
+    /// What the current track is played from, along with the track it was looked up for
+    context: Option<(SpotifyId, Option<PlayContext>)>,
 
     last_update: Instant,
     update_in: Option<Duration>,
@@ -65,11 +80,12 @@ impl PlaybackEmbed {
         handle: SessionHandle,
         interaction: CommandInteraction,
         update_behavior: UpdateBehavior,
+        locale: Locale,
     ) -> Result<Option<PlaybackEmbedHandle>> {
         let ctx = session.context.clone();
 
         if !session.active {
-            respond_not_playing(&ctx, interaction).await?;
+            respond_not_playing(&ctx, interaction, locale).await?;
 
             return Ok(None);
         }
@@ -77,43 +93,44 @@ impl PlaybackEmbed {
         let owner = session.owner.to_user(&ctx).await?;
 
         let Some(playback_info) = session.player.playback_info().await? else {
-            respond_not_playing(&ctx, interaction).await?;
+            respond_not_playing(&ctx, interaction, locale).await?;
 
             return Ok(None);
         };
 
-        let ctx_id = interaction.id.get();
+        let guild_id = interaction
+            .guild_id
+            .expect("interaction was outside of a guild");
 
-        // Send initial reply
+        // Send initial reply, the card and context are looked up afterwards as Discord only waits 3 seconds for a reply
         interaction
             .create_response(
                 &ctx,
                 CreateInteractionResponse::Message(
                     CreateInteractionResponseMessage::new()
-                        .embed(build_embed(&playback_info, &owner))
-                        .components(vec![build_buttons(ctx_id, playback_info.playing())]),
+                        .embed(build_embed(&playback_info, &owner, None, None, locale))
+                        .components(vec![build_buttons(
+                            guild_id,
+                            playback_info.playing(),
+                            locale,
+                        )]),
                 ),
             )
             .await?;
 
-        // If this is a static embed, we don't need to return any handles
-        if update_behavior.is_static() {
-            return Ok(None);
-        }
-
         // Retrieve message instead of editing interaction response, as those tokens are only valid for 15 minutes
         let message = interaction.get_response(&ctx).await?;
 
-        let collector = ComponentInteractionCollector::new(&ctx)
-            .filter(move |press| press.data.custom_id.starts_with(&ctx_id.to_string()))
-            .timeout(Duration::from_secs(3600 * 24));
-
         let (tx, rx) = mpsc::channel(16);
-        let this = Self {
-            id: ctx_id,
+        let mut this = Self {
+            guild_id,
             ctx,
+            session_manager: session.session_manager.clone(),
             session: handle,
             message,
+            locale,
+            artwork: None,
+            context: None,
             last_update: Instant::now(),
             update_in: None,
             force_edit: false,
@@ -121,13 +138,24 @@ impl PlaybackEmbed {
             rx,
         };
 
-        tokio::spawn(this.run(collector));
+        // A static embed is only completed once, so we don't need to return any handles
+        if this.update_behavior.is_static() {
+            tokio::spawn(async move { this.update_embed(true).await });
+
+            return Ok(None);
+        }
+
+        // Button presses are handled by `handle_button`, so they keep working after this embed stops updating
+        tokio::spawn(this.run());
 
         Ok(Some(PlaybackEmbedHandle { tx }))
     }
 
-    async fn run(mut self, collector: ComponentInteractionCollector) {
-        let mut stream = collector.stream();
+    async fn run(mut self) {
+        // Complete the initial reply with the card and context
+        if self.update_embed(true).await.is_break() {
+            return;
+        }
 
         loop {
             tokio::select! {
@@ -141,14 +169,6 @@ impl PlaybackEmbed {
                     }
                 },
 
-                opt_press = stream.next() => {
-                    let Some(press) = opt_press else {
-                        break;
-                    };
-
-                    self.handle_press(press).await;
-                }
-
                 _ = async {
                     if let Some(update_in) = self.update_in.take()
                     {
@@ -179,76 +199,14 @@ impl PlaybackEmbed {
                     self.update_embed(force_edit).await?;
                 }
             }
-        }
-
-        ControlFlow::Continue(())
-    }
+            Command::Close => {
+                _ = self.update_not_playing().await;
 
-    async fn handle_press(&self, press: ComponentInteraction) {
-        trace!("Received button press: {press:?}");
-
-        let Ok((player, playback_info, owner)) = self.get_info().await else {
-            _ = press
-                .create_followup(
-                    &self.ctx,
-                    CreateInteractionResponseFollowup::new()
-                        .embed(
-                            CreateEmbed::new()
-                                .title("Cannot perform action")
-                                .description("I'm currently not playing any music in this server"),
-                        )
-                        .ephemeral(true),
-                )
-                .await;
-
-            return;
-        };
-
-        if press.user.id != owner.id {
-            _ = press
-                .create_followup(
-                    &self.ctx,
-                    CreateInteractionResponseFollowup::new()
-                        .embed(
-                            CreateEmbed::new()
-                                .title("Cannot perform action")
-                                .description("Only the host may use the media buttons"),
-                        )
-                        .ephemeral(true),
-                )
-                .await;
-
-            return;
-        }
-
-        match press.data.custom_id.split('-').last() {
-            Some("next") => player.next_track().await,
-            Some("prev") => player.previous_track().await,
-            Some("pause") => {
-                if playback_info.playing() {
-                    player.pause().await
-                } else {
-                    player.play().await
-                }
+                return ControlFlow::Break(());
             }
-
-            _ => {}
         }
 
-        _ = press
-            .create_response(&self.ctx, CreateInteractionResponse::Acknowledge)
-            .await;
-    }
-
-    async fn get_info(&self) -> Result<(PlayerHandle, PlaybackInfo, User)> {
-        let player = self.session.player().await?;
-        let owner = self.session.owner().await?.to_user(&self.ctx).await?;
-        let playback_info = player
-            .playback_info()
-            .await?
-            .ok_or_else(|| anyhow!("No playback info present"))?;
-
-        Ok((player, playback_info, owner))
+        ControlFlow::Continue(())
     }
 
     async fn update_embed(&mut self, force_edit: bool) -> ControlFlow<(), ()> {
@@ -282,19 +240,30 @@ impl PlaybackEmbed {
         };
 
         let should_pin = !force_edit && self.update_behavior.is_pinned();
+        let card = render_card(&mut self.artwork, &playback_info).await;
+        let context = self.play_context(&playback_info, owner.id).await;
+        let embed = build_embed(
+            &playback_info,
+            &owner,
+            card.as_ref(),
+            context.as_ref(),
+            self.locale,
+        );
+        let buttons = build_buttons(self.guild_id, playback_info.playing(), self.locale);
 
         if should_pin {
             self.message.delete(&self.ctx).await.ok();
 
+            let mut message = CreateMessage::new().embed(embed).components(vec![buttons]);
+
+            if let Some(card) = card {
+                message = message.add_file(card.attachment);
+            }
+
             match self
                 .message
                 .channel_id
-                .send_message(
-                    &self.ctx,
-                    CreateMessage::new()
-                        .embed(build_embed(&playback_info, &owner))
-                        .components(vec![build_buttons(self.id, playback_info.playing())]),
-                )
+                .send_message(&self.ctx, message)
                 .await
             {
                 Ok(message) => self.message = message,
@@ -309,8 +278,13 @@ impl PlaybackEmbed {
             .edit(
                 &self.ctx,
                 EditMessage::new()
-                    .embed(build_embed(&playback_info, &owner))
-                    .components(vec![build_buttons(self.id, playback_info.playing())]),
+                    .embed(embed)
+                    .components(vec![buttons])
+                    // Replaces the previous card, or removes it if the card could not be rendered
+                    .attachments(match card {
+                        Some(card) => EditAttachments::new().add(card.attachment),
+                        None => EditAttachments::new(),
+                    }),
             )
             .await
         {
@@ -324,6 +298,43 @@ impl PlaybackEmbed {
         ControlFlow::Continue(())
     }
 
+    /// Look up what the current track is played from, which is only done once per track
+    async fn play_context(
+        &mut self,
+        playback_info: &PlaybackInfo,
+        owner: UserId,
+    ) -> Option<PlayContext> {
+        let track_id = playback_info.track_id();
+
+        if let Some((context_track_id, context)) = &self.context {
+            if *context_track_id == track_id {
+                return context.clone();
+            }
+        }
+
+        let context = match self
+            .session_manager
+            .database()
+            .get_access_token(owner.to_string())
+            .await
+        {
+            Ok(access_token) => PlayContext::fetch(&access_token)
+                .await
+                .inspect_err(|why| warn!("Failed to retrieve play context: {why}"))
+                .ok()
+                .flatten(),
+            Err(why) => {
+                warn!("Failed to retrieve access token for play context: {why}");
+
+                None
+            }
+        };
+
+        self.context = Some((track_id, context.clone()));
+
+        context
+    }
+
     async fn update_not_playing(&mut self) -> Result<()> {
         // If pinned, try to delete old message and send new one
         if self.update_behavior.is_pinned() {
@@ -331,14 +342,22 @@ impl PlaybackEmbed {
             self.message = self
                 .message
                 .channel_id
-                .send_message(&self.ctx, CreateMessage::new().embed(not_playing_embed()))
+                .send_message(
+                    &self.ctx,
+                    CreateMessage::new().embed(not_playing_embed(self.locale)),
+                )
                 .await?;
 
             return Ok(());
         }
 
         self.message
-            .edit(&self.ctx, EditMessage::new().embed(not_playing_embed()))
+            .edit(
+                &self.ctx,
+                EditMessage::new()
+                    .embed(not_playing_embed(self.locale))
+                    .attachments(EditAttachments::new()),
+            )
             .await?;
 
         Ok(())
@@ -359,15 +378,136 @@ impl PlaybackEmbedHandle {
 
         Ok(())
     }
+
+    /// Let the embed show that nothing is being played anymore, after which it stops updating
+    pub async fn close(&self) -> Result<()> {
+        self.tx.send(Command::Close).await?;
+
+        Ok(())
+    }
+}
+
+/// Handle a press of a button of any playback embed that was ever sent, including those that are no longer updated.
+///
+/// The buttons act on whatever session currently exists in the server of the embed.
+pub async fn handle_button(
+    ctx: &Context,
+    session_manager: &SessionManager,
+    press: &ComponentInteraction,
+) -> Result<()> {
+    let Some((guild_id, action)) = parse_button(press) else {
+        // Not a playback button, other embeds handle their own buttons
+        return Ok(());
+    };
+
+    trace!("Received button press: {press:?}");
+
+    let locale = session_manager
+        .locale(press.guild_id, Some(press.locale.as_str()))
+        .await;
+
+    let info = match session_manager.get_session(SessionQuery::Guild(guild_id)) {
+        Some(session) => get_info(ctx, &session).await.ok(),
+        None => None,
+    };
+
+    let Some((player, playback_info, owner)) = info else {
+        return respond_error(ctx, press, locale, t!(locale, "not-playing")).await;
+    };
+
+    if press.user.id != owner.id {
+        return respond_error(ctx, press, locale, t!(locale, "playback-button-not-host")).await;
+    }
+
+    match action {
+        "next" => player.next_track().await,
+        "prev" => player.previous_track().await,
+        "pause" => {
+            if playback_info.playing() {
+                player.pause().await
+            } else {
+                player.play().await
+            }
+        }
+
+        _ => {}
+    }
+
+    press
+        .create_response(ctx, CreateInteractionResponse::Acknowledge)
+        .await?;
+
+    Ok(())
+}
+
+/// Find the server and action of a playback button, with ids that look like `playback:<action>:<guild id>`
+fn parse_button(press: &ComponentInteraction) -> Option<(GuildId, &str)> {
+    let custom_id = press.data.custom_id.as_str();
+
+    if let Some(rest) = custom_id.strip_prefix("playback:") {
+        let (action, guild_id) = rest.split_once(':')?;
+        let guild_id = guild_id.parse::<u64>().ok().filter(|id| *id != 0)?;
+
+        return Some((GuildId::new(guild_id), action));
+    }
+
+    // Embeds from older versions use `<interaction id>-<action>`, which only works within the same server
+    let (interaction_id, action) = custom_id.split_once('-')?;
+    interaction_id.parse::<u64>().ok()?;
+
+    Some((press.guild_id?, action))
+}
+
+async fn get_info(
+    ctx: &Context,
+    session: &SessionHandle,
+) -> Result<(PlayerHandle, PlaybackInfo, User)> {
+    let player = session.player().await?;
+    let owner = session.owner().await?.to_user(ctx).await?;
+    let playback_info = player
+        .playback_info()
+        .await?
+        .ok_or_else(|| anyhow!("No playback info present"))?;
+
+    Ok((player, playback_info, owner))
+}
+
+async fn respond_error(
+    ctx: &Context,
+    press: &ComponentInteraction,
+    locale: Locale,
+    description: String,
+) -> Result<()> {
+    press
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .embed(
+                        CreateEmbed::new()
+                            .title(t!(locale, "playback-button-error-title"))
+                            .description(description)
+                            .color(Colors::Error),
+                    )
+                    .ephemeral(true),
+            ),
+        )
+        .await?;
+
+    Ok(())
 }
 
-async fn respond_not_playing(context: &Context, interaction: CommandInteraction) -> Result<()> {
+async fn respond_not_playing(
+    context: &Context,
+    interaction: CommandInteraction,
+    locale: Locale,
+) -> Result<()> {
     interaction
         .create_response(
             context,
             CreateInteractionResponse::Message(
                 CreateInteractionResponseMessage::new()
-                    .embed(not_playing_embed())
+                    .embed(not_playing_embed(locale))
                     .ephemeral(true),
             ),
         )
@@ -376,86 +516,263 @@ async fn respond_not_playing(context: &Context, interaction: CommandInteraction)
     Ok(())
 }
 
-fn not_playing_embed() -> CreateEmbed {
+fn not_playing_embed(locale: Locale) -> CreateEmbed {
     CreateEmbed::new()
-        .title("Cannot display song details")
-        .description("I'm currently not playing any music in this server.")
+        .title(t!(locale, "playback-not-playing-title"))
+        .description(t!(locale, "not-playing"))
         .color(Colors::Error)
 }
 
-fn build_embed(playback_info: &PlaybackInfo, owner: &User) -> CreateEmbed {
+/// The rendered now playing card, which replaces the text progress bar of the embed
+struct Card {
+    attachment: CreateAttachment,
+    colour: Colour,
+}
+
+/// Render the card of the current track, downloading its artwork if it isn't the one that was downloaded before
+async fn render_card(artwork: &mut Option<Artwork>, playback_info: &PlaybackInfo) -> Option<Card> {
+    // Without artwork there is nothing to base the card on, so the text embed is used instead
+    let Some(url) = playback_info.thumbnail() else {
+        *artwork = None;
+
+        return None;
+    };
+
+    if artwork.as_ref().map(Artwork::url) != Some(url.as_str()) {
+        *artwork = match Artwork::fetch(&url).await {
+            Ok(artwork) => Some(artwork),
+            Err(why) => {
+                warn!("Failed to download artwork, falling back to text embed: {why}");
+
+                None
+            }
+        };
+    }
+
+    let artwork = artwork.as_ref()?;
+
+    match now_playing::render(artwork, playback_info) {
+        Ok(png) => Some(Card {
+            attachment: CreateAttachment::bytes(png, now_playing::FILE_NAME),
+            colour: artwork.colour(),
+        }),
+        Err(why) => {
+            warn!("Failed to render now playing card, falling back to text embed: {why}");
+
+            None
+        }
+    }
+}
+
+/// The amount of characters of an episode description that are shown in the embed
+const DESCRIPTION_LENGTH: usize = 300;
+
+fn build_embed(
+    playback_info: &PlaybackInfo,
+    owner: &User,
+    card: Option<&Card>,
+    context: Option<&PlayContext>,
+    locale: Locale,
+) -> CreateEmbed {
     let mut description = String::new();
 
-    description += &format!("## [{}]({})\n", playback_info.name(), playback_info.url());
+    let name = match playback_info.name() {
+        name if name.trim().is_empty() => t!(locale, "playback-unknown-track"),
+        name => name,
+    };
+
+    match playback_info.url() {
+        Some(url) => description += &format!("## [{name}]({url})\n"),
+        None => description += &format!("## {name}\n"),
+    }
 
-    if let Some(artists) = playback_info.artists() {
+    if let Some(artists) = playback_info
+        .artists()
+        .filter(|artists| !artists.is_empty())
+    {
         let artists = artists
             .iter()
-            .map(|artist| {
-                format!(
-                    "[{}](https://open.spotify.com/artist/{})",
-                    artist.name,
-                    artist.id.to_base62().expect("invalid artist")
-                )
+            .map(|artist| match artist.id.to_base62() {
+                Ok(id) if !playback_info.is_local() => {
+                    format!("[{}](https://open.spotify.com/artist/{id})", artist.name)
+                }
+                _ => artist.name.clone(),
             })
             .collect::<Vec<_>>()
             .join(", ");
 
-        description += &format!("By {artists}\n");
+        description += &format!("{}\n", t!(locale, "playback-artists", artists = artists));
+    }
+
+    if playback_info.is_local() {
+        description += &format!("{}\n", t!(locale, "playback-local-file"));
     }
 
-    if let Some(album_name) = playback_info.album_name() {
-        description += &format!("Album: **{album_name}**\n");
+    if let Some(album_name) = playback_info
+        .album_name()
+        .filter(|album_name| !album_name.is_empty())
+    {
+        description += &t!(locale, "playback-album", album = album_name);
+
+        match playback_info.album_position() {
+            Some((disc, track)) if disc > 1 => {
+                description += &format!(
+                    " {}",
+                    t!(
+                        locale,
+                        "playback-album-disc-track",
+                        disc = disc,
+                        track = track
+                    )
+                )
+            }
+            Some((_, track)) if track > 0 => {
+                description += &format!(" {}", t!(locale, "playback-album-track", track = track))
+            }
+            _ => {}
+        }
+
+        description += "\n";
+    }
+
+    if let Some(context) = context {
+        description += &format!(
+            "{}\n",
+            t!(
+                locale,
+                "playback-context",
+                kind = context.kind.as_str(),
+                name = format!("[{}]({})", escape(&context.name), context.url)
+            )
+        );
+    }
+
+    let mut flags = vec![];
+
+    if playback_info.is_explicit() {
+        flags.push(t!(locale, "playback-explicit"));
+    }
+
+    if let Some(popularity) = playback_info
+        .popularity()
+        .filter(|popularity| *popularity > 0)
+    {
+        flags.push(t!(locale, "playback-popularity", popularity = popularity));
+    }
+
+    if !flags.is_empty() {
+        description += &format!("{}\n", flags.join(" · "));
     }
 
     if let Some(show_name) = playback_info.show_name() {
-        description += &format!("On {show_name}\n");
+        description += &format!("{}\n", t!(locale, "playback-show", show = show_name));
+    }
+
+    if let Some(published_at) = playback_info.published_at() {
+        description += &format!(
+            "{}\n",
+            t!(
+                locale,
+                "playback-published",
+                timestamp = published_at.to_string()
+            )
+        );
+    }
+
+    if let Some(summary) = playback_info
+        .description()
+        .filter(|summary| !summary.trim().is_empty())
+    {
+        let mut summary = summary.trim().to_string();
+
+        if let Some((index, _)) = summary.char_indices().nth(DESCRIPTION_LENGTH) {
+            summary.truncate(index);
+            summary = format!("{}…", summary.trim_end());
+        }
+
+        description += "\n";
+
+        for line in escape(summary).lines() {
+            description += &format!("> {line}\n");
+        }
     }
 
     description += "\n";
 
     let position = playback_info.current_position();
-    let index = position * 20 / playback_info.duration();
 
-    description += if playback_info.playing() {
-        "▶️ "
-    } else {
-        "⏸️ "
-    };
+    // The card already shows whether the track is playing, and how far along it is
+    if card.is_none() {
+        let index = position * 20 / playback_info.duration().max(1);
 
-    for i in 0..20 {
-        if i == index {
-            description.push('🔵');
+        description += if playback_info.playing() {
+            "▶️ "
         } else {
-            description.push('▬');
+            "⏸️ "
+        };
+
+        for i in 0..20 {
+            if i == index {
+                description.push('🔵');
+            } else {
+                description.push('▬');
+            }
         }
+
+        description += "\n";
     }
 
-    description += "\n:alarm_clock: ";
+    description += ":alarm_clock: ";
     description += &format!(
         "{} / {}",
         spoticord_utils::time_to_string(position / 1000),
         spoticord_utils::time_to_string(playback_info.duration() / 1000)
     );
 
-    CreateEmbed::new()
+    if playback_info.speed() != 1.0 {
+        description += &format!(" ({}x)", playback_info.speed());
+    }
+
+    if let Some(up_next) = playback_info.up_next() {
+        description += "\n";
+        description += &if up_next.artists.is_empty() {
+            t!(locale, "playback-up-next", track = up_next.name.as_str())
+        } else {
+            t!(
+                locale,
+                "playback-up-next-by",
+                track = up_next.name.as_str(),
+                artists = up_next.artists.join(", ")
+            )
+        };
+    }
+
+    let embed = CreateEmbed::new()
         .author(
-            CreateEmbedAuthor::new("Currently Playing")
+            CreateEmbedAuthor::new(t!(locale, "playback-author"))
                 .icon_url("https://spoticord.com/spotify-logo.png"),
         )
         .description(description)
-        .thumbnail(playback_info.thumbnail())
         .footer(
             CreateEmbedFooter::new(owner.global_name.as_ref().unwrap_or(&owner.name))
                 .icon_url(owner.face()),
-        )
-        .color(Colors::Info)
+        );
+
+    match card {
+        Some(card) => embed
+            .image(format!("attachment://{}", now_playing::FILE_NAME))
+            .color(card.colour),
+        None => match playback_info.thumbnail() {
+            Some(thumbnail) => embed.thumbnail(thumbnail).color(Colors::Info),
+            None => embed.color(Colors::Info),
+        },
+    }
 }
 
-fn build_buttons(id: u64, playing: bool) -> CreateActionRow {
-    let prev_button_id = format!("{id}-prev");
-    let next_button_id = format!("{id}-next");
-    let pause_button_id = format!("{id}-pause");
+fn build_buttons(guild_id: GuildId, playing: bool, locale: Locale) -> CreateActionRow {
+    let prev_button_id = format!("playback:prev:{guild_id}");
+    let next_button_id = format!("playback:next:{guild_id}");
+    let pause_button_id = format!("playback:pause:{guild_id}");
 
     let prev_button = CreateButton::new(prev_button_id)
         .style(ButtonStyle::Primary)
@@ -471,7 +788,11 @@ fn build_buttons(id: u64, playing: bool) -> CreateActionRow {
         } else {
             ButtonStyle::Success
         })
-        .label(if playing { "Pause" } else { "Play" });
+        .label(if playing {
+            t!(locale, "playback-pause")
+        } else {
+            t!(locale, "playback-play")
+        });
 
     CreateActionRow::Buttons(vec![prev_button, pause_button, next_button])
 }
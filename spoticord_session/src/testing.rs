@@ -0,0 +1,96 @@
+//! In-memory sessions, for running command logic without Discord or Spotify.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use serenity::all::{ChannelId, GuildId, UserId};
+use spoticord_database::memory::MemoryDatabase;
+
+use crate::manager::{SessionQuery, SessionState, Sessions};
+
+/// A session that only consists of its state
+#[derive(Debug, Clone, Copy)]
+pub struct FakeSession {
+    pub guild: GuildId,
+    pub voice_channel: ChannelId,
+    pub owner: UserId,
+
+    /// Whether the session has an active player
+    pub active: bool,
+}
+
+impl SessionState for FakeSession {
+    fn guild(&self) -> GuildId {
+        self.guild
+    }
+
+    fn voice_channel(&self) -> ChannelId {
+        self.voice_channel
+    }
+
+    async fn owner(&self) -> anyhow::Result<UserId> {
+        Ok(self.owner)
+    }
+
+    async fn active(&self) -> anyhow::Result<bool> {
+        Ok(self.active)
+    }
+}
+
+/// Keeps track of fake sessions the same way the session manager keeps track of real ones.
+///
+/// Clones share the same sessions and database.
+#[derive(Clone, Default)]
+pub struct MemorySessions {
+    database: MemoryDatabase,
+    sessions: Arc<Mutex<HashMap<GuildId, FakeSession>>>,
+}
+
+impl MemorySessions {
+    pub fn new(database: MemoryDatabase) -> Self {
+        Self {
+            database,
+            sessions: Arc::default(),
+        }
+    }
+
+    /// Add a session, replacing the session that was in the same guild
+    pub fn insert(&self, session: FakeSession) {
+        self.sessions
+            .lock()
+            .expect("Mutex was poisoned")
+            .insert(session.guild, session);
+    }
+
+    pub fn remove(&self, guild: GuildId) -> Option<FakeSession> {
+        self.sessions
+            .lock()
+            .expect("Mutex was poisoned")
+            .remove(&guild)
+    }
+}
+
+impl Sessions for MemorySessions {
+    type Session = FakeSession;
+    type Database = MemoryDatabase;
+
+    fn get_session(&self, query: SessionQuery) -> Option<FakeSession> {
+        let sessions = self.sessions.lock().expect("Mutex was poisoned");
+
+        match query {
+            SessionQuery::Guild(guild) => sessions.get(&guild).copied(),
+
+            // Owners are removed from the session manager once their player shuts down
+            SessionQuery::Owner(owner) => sessions
+                .values()
+                .find(|session| session.active && session.owner == owner)
+                .copied(),
+        }
+    }
+
+    fn database(&self) -> MemoryDatabase {
+        self.database.clone()
+    }
+}
@@ -0,0 +1,400 @@
+//! Offline transliteration of Japanese kana, Korean hangul and Cyrillic to the Latin alphabet.
+//!
+//! Kana is romanized using Hepburn, hangul using the Revised Romanization of Korean. Kanji and other scripts are
+//! left as they are, as those can't be transliterated without a dictionary.
+
+const HANGUL_INITIALS: [&str; 19] = [
+    "g", "kk", "n", "d", "tt", "r", "m", "b", "pp", "s", "ss", "", "j", "jj", "ch", "k", "t", "p",
+    "h",
+];
+
+const HANGUL_VOWELS: [&str; 21] = [
+    "a", "ae", "ya", "yae", "eo", "e", "yeo", "ye", "o", "wa", "wae", "oe", "yo", "u", "wo", "we",
+    "wi", "yu", "eu", "ui", "i",
+];
+
+const HANGUL_FINALS: [&str; 28] = [
+    "", "k", "k", "k", "n", "n", "n", "t", "l", "k", "m", "p", "l", "l", "p", "l", "m", "p", "p",
+    "t", "t", "ng", "t", "t", "k", "t", "p", "t",
+];
+
+/// Transliterate kana, hangul and Cyrillic in `text` to the Latin alphabet
+pub fn romanize(text: &str) -> String {
+    transliterate(text, text.len()).0
+}
+
+/// Transliterate `text`, and split the result where byte `split` of `text` ends up.
+///
+/// Syllables are transliterated as a whole, so a split in the middle of one, like between き and ゃ, is moved to after
+/// it. A small tsu (っ) is silent on its own, so it always belongs to the syllable after it.
+pub fn romanize_split(text: &str, split: usize) -> (String, String) {
+    let (mut result, index) = transliterate(text, split);
+    let rest = result.split_off(index);
+
+    (result, rest)
+}
+
+/// Transliterate `text`, also returning the length of the transliteration of everything before byte `split`
+fn transliterate(text: &str, split: usize) -> (String, usize) {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text
+        .char_indices()
+        .map(|(position, c)| (position, katakana_to_hiragana(c)))
+        .peekable();
+
+    // Set by a small tsu (っ), which doubles the consonant that follows it
+    let mut double_next = false;
+    let mut index = None;
+
+    while let Some((position, c)) = chars.next() {
+        // The first syllable that starts after the split marks where it ends up, where a small tsu starts a syllable
+        if position >= split && !(double_next && kana(c).is_some()) {
+            index.get_or_insert(result.len());
+        }
+
+        if let Some(latin) = hangul(c).or_else(|| cyrillic(c)) {
+            result.push_str(&latin);
+            continue;
+        }
+
+        match c {
+            'っ' => {
+                double_next = true;
+                continue;
+            }
+            // The long vowel mark repeats the vowel before it
+            'ー' => {
+                if let Some(vowel) = result.chars().rev().find(|c| is_vowel(*c)) {
+                    result.push(vowel);
+                }
+                continue;
+            }
+            _ => {}
+        }
+
+        let Some(base) = kana(c) else {
+            result.push(c);
+            double_next = false;
+            continue;
+        };
+
+        let mut romaji = base.to_string();
+
+        // Combine with a small kana that follows, like きゃ (kya) or ファ (fa)
+        if let Some(combined) = chars.peek().and_then(|(_, next)| combine(base, *next)) {
+            romaji = combined;
+            chars.next();
+        }
+
+        // Keep ん from merging with the syllable after it, like in きんえん (kin'en)
+        if c == 'ん'
+            && chars
+                .peek()
+                .and_then(|(_, next)| kana(*next))
+                .is_some_and(|next| next.starts_with(['a', 'i', 'u', 'e', 'o', 'y']))
+        {
+            romaji.push('\'');
+        }
+
+        if std::mem::take(&mut double_next) {
+            if romaji.starts_with("ch") {
+                result.push('t');
+            } else if let Some(first) = romaji.chars().next().filter(|c| !is_vowel(*c)) {
+                result.push(first);
+            }
+        }
+
+        result.push_str(&romaji);
+    }
+
+    let index = index.unwrap_or(result.len());
+
+    (result, index)
+}
+
+/// Whether `text` contains anything that [`romanize`] would transliterate
+pub fn can_romanize(text: &str) -> bool {
+    text.chars()
+        .map(katakana_to_hiragana)
+        .any(|c| kana(c).is_some() || c == 'っ' || hangul(c).is_some() || cyrillic(c).is_some())
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'i' | 'u' | 'e' | 'o')
+}
+
+fn katakana_to_hiragana(c: char) -> char {
+    match c {
+        // Katakana are laid out exactly like hiragana, 0x60 code points further
+        '\u{30A1}'..='\u{30F6}' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+        c => c,
+    }
+}
+
+fn combine(base: &str, small: char) -> Option<String> {
+    let vowel = match small {
+        'ゃ' => Some("a"),
+        'ゅ' => Some("u"),
+        'ょ' => Some("o"),
+        _ => None,
+    };
+
+    if let Some(vowel) = vowel {
+        let stem = base.strip_suffix('i').filter(|stem| !stem.is_empty())?;
+
+        // し, ち and じ already end in a y sound
+        return Some(match stem {
+            "sh" | "ch" | "j" => format!("{stem}{vowel}"),
+            _ => format!("{stem}y{vowel}"),
+        });
+    }
+
+    let vowel = match small {
+        'ぁ' => 'a',
+        'ぃ' => 'i',
+        'ぅ' => 'u',
+        'ぇ' => 'e',
+        'ぉ' => 'o',
+        _ => return None,
+    };
+
+    match &base[..base.len() - 1] {
+        "" if base == "u" => Some(format!("w{vowel}")),
+        "" => None,
+        stem => Some(format!("{stem}{vowel}")),
+    }
+}
+
+fn kana(c: char) -> Option<&'static str> {
+    Some(match c {
+        'あ' => "a",
+        'い' => "i",
+        'う' => "u",
+        'え' => "e",
+        'お' => "o",
+        'か' | 'ゕ' => "ka",
+        'き' => "ki",
+        'く' => "ku",
+        'け' | 'ゖ' => "ke",
+        'こ' => "ko",
+        'が' => "ga",
+        'ぎ' => "gi",
+        'ぐ' => "gu",
+        'げ' => "ge",
+        'ご' => "go",
+        'さ' => "sa",
+        'し' => "shi",
+        'す' => "su",
+        'せ' => "se",
+        'そ' => "so",
+        'ざ' => "za",
+        'じ' | 'ぢ' => "ji",
+        'ず' | 'づ' => "zu",
+        'ぜ' => "ze",
+        'ぞ' => "zo",
+        'た' => "ta",
+        'ち' => "chi",
+        'つ' => "tsu",
+        'て' => "te",
+        'と' => "to",
+        'だ' => "da",
+        'で' => "de",
+        'ど' => "do",
+        'な' => "na",
+        'に' => "ni",
+        'ぬ' => "nu",
+        'ね' => "ne",
+        'の' => "no",
+        'は' => "ha",
+        'ひ' => "hi",
+        'ふ' => "fu",
+        'へ' => "he",
+        'ほ' => "ho",
+        'ば' => "ba",
+        'び' => "bi",
+        'ぶ' => "bu",
+        'べ' => "be",
+        'ぼ' => "bo",
+        'ぱ' => "pa",
+        'ぴ' => "pi",
+        'ぷ' => "pu",
+        'ぺ' => "pe",
+        'ぽ' => "po",
+        'ま' => "ma",
+        'み' => "mi",
+        'む' => "mu",
+        'め' => "me",
+        'も' => "mo",
+        'や' | 'ゃ' => "ya",
+        'ゆ' | 'ゅ' => "yu",
+        'よ' | 'ょ' => "yo",
+        'ら' => "ra",
+        'り' => "ri",
+        'る' => "ru",
+        'れ' => "re",
+        'ろ' => "ro",
+        'わ' | 'ゎ' => "wa",
+        'ゐ' => "wi",
+        'ゑ' => "we",
+        'を' => "wo",
+        'ん' => "n",
+        'ゔ' => "vu",
+        'ぁ' => "a",
+        'ぃ' => "i",
+        'ぅ' => "u",
+        'ぇ' => "e",
+        'ぉ' => "o",
+        _ => return None,
+    })
+}
+
+fn hangul(c: char) -> Option<String> {
+    // Hangul syllables are composed of an initial, a vowel and an optional final, in that order
+    let index = (c as u32)
+        .checked_sub(0xAC00)
+        .filter(|index| *index < 11172)? as usize;
+
+    Some(format!(
+        "{}{}{}",
+        HANGUL_INITIALS[index / 588],
+        HANGUL_VOWELS[index % 588 / 28],
+        HANGUL_FINALS[index % 28]
+    ))
+}
+
+fn cyrillic(c: char) -> Option<String> {
+    let latin = match c.to_lowercase().next()? {
+        'а' => "a",
+        'б' => "b",
+        'в' => "v",
+        'г' | 'ґ' => "g",
+        'д' => "d",
+        'е' => "e",
+        'ё' => "yo",
+        'є' => "ye",
+        'ж' => "zh",
+        'з' => "z",
+        'и' | 'і' => "i",
+        'ї' => "yi",
+        'й' => "y",
+        'ј' => "j",
+        'к' => "k",
+        'л' => "l",
+        'љ' => "lj",
+        'м' => "m",
+        'н' => "n",
+        'њ' => "nj",
+        'о' => "o",
+        'п' => "p",
+        'р' => "r",
+        'с' => "s",
+        'т' => "t",
+        'ћ' => "c",
+        'ђ' => "dj",
+        'у' => "u",
+        'ў' => "w",
+        'ф' => "f",
+        'х' => "kh",
+        'ц' => "ts",
+        'ч' => "ch",
+        'џ' => "dz",
+        'ш' => "sh",
+        'щ' => "shch",
+        'ъ' | 'ь' => "",
+        'ы' => "y",
+        'э' => "e",
+        'ю' => "yu",
+        'я' => "ya",
+        _ => return None,
+    };
+
+    if !c.is_uppercase() {
+        return Some(latin.to_string());
+    }
+
+    let mut chars = latin.chars();
+
+    Some(match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => String::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn romanizes() {
+        let cases = [
+            // Hiragana and katakana
+            ("ありがとう", "arigatou"),
+            ("カタカナ", "katakana"),
+            ("東京タワー", "東京tawaa"),
+            // Combined syllables
+            ("きゃく", "kyaku"),
+            ("しょうじょ", "shoujo"),
+            ("チャンス", "chansu"),
+            ("ファン", "fan"),
+            ("ウィキ", "wiki"),
+            // A small tsu doubles the consonant after it
+            ("ずっと", "zutto"),
+            ("まっちゃ", "matcha"),
+            ("ちょっと", "chotto"),
+            // ん is kept apart from a vowel or y after it
+            ("きんえん", "kin'en"),
+            ("こんや", "kon'ya"),
+            ("ほんとう", "hontou"),
+            // The long vowel mark repeats the vowel before it
+            ("ラーメン", "raamen"),
+            // Hangul, with and without a final consonant
+            ("한국", "hanguk"),
+            ("서울", "seoul"),
+            ("사랑해", "saranghae"),
+            // Cyrillic keeps its case
+            ("Москва", "Moskva"),
+            ("щука", "shchuka"),
+            ("Щука", "Shchuka"),
+            ("Юля", "Yulya"),
+            ("Hello, world!", "Hello, world!"),
+        ];
+
+        for (text, expected) in cases {
+            assert_eq!(romanize(text), expected, "{text}");
+        }
+    }
+
+    #[test]
+    fn splits_between_syllables() {
+        let cases = [
+            ("한국", "한".len(), ("han", "guk")),
+            ("きゃく", "き".len(), ("kya", "ku")),
+            ("まっちゃ", "ま".len(), ("ma", "tcha")),
+            ("まっちゃ", "まっ".len(), ("matcha", "")),
+            ("あっ!", "あっ".len(), ("a", "!")),
+            ("きんえん", "きん".len(), ("kin'", "en")),
+            ("ラーメン", "ラー".len(), ("raa", "men")),
+            ("Москва", "Мос".len(), ("Mos", "kva")),
+            ("あい", 0, ("", "ai")),
+            ("あい", "あい".len(), ("ai", "")),
+        ];
+
+        for (text, split, (sung, rest)) in cases {
+            assert_eq!(
+                romanize_split(text, split),
+                (sung.to_string(), rest.to_string()),
+                "{text} at {split}"
+            );
+        }
+    }
+
+    #[test]
+    fn detects_romanizable_text() {
+        assert!(can_romanize("東京タワー"));
+        assert!(can_romanize("ずっ"));
+        assert!(can_romanize("서울"));
+        assert!(can_romanize("Москва"));
+        assert!(!can_romanize("東京"));
+        assert!(!can_romanize("Hello, world!"));
+    }
+}
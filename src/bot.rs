@@ -1,11 +1,13 @@
 use std::sync::Arc;
 
 use anyhow::{anyhow, Result};
-use log::{debug, info};
+use log::{debug, error, info, warn};
 use poise::{serenity_prelude, Framework, FrameworkContext, FrameworkOptions};
-use serenity::all::{ActivityData, FullEvent, Ready, ShardManager};
+use serenity::all::{ActivityData, FullEvent, Interaction, Ready, ShardManager};
+use spoticord_config::LyricsCacheBacking;
 use spoticord_database::Database;
-use spoticord_session::manager::SessionManager;
+use spoticord_i18n::Locale;
+use spoticord_session::{manager::SessionManager, playback_embed};
 
 use crate::commands;
 
@@ -18,23 +20,34 @@ pub type FrameworkError<'a> = poise::FrameworkError<'a, Data, anyhow::Error>;
 type Data = SessionManager;
 
 pub fn framework_opts() -> FrameworkOptions<Data, anyhow::Error> {
+    let mut commands = vec![
+        #[cfg(debug_assertions)]
+        commands::debug::ping(),
+        #[cfg(debug_assertions)]
+        commands::debug::token(),
+        commands::debug::audiostats(),
+        commands::core::help(),
+        commands::core::version(),
+        commands::core::rename(),
+        commands::core::audio(),
+        commands::core::server(),
+        commands::core::link(),
+        commands::core::unlink(),
+        commands::music::join(),
+        commands::music::disconnect(),
+        commands::music::stop(),
+        commands::music::playing(),
+        commands::music::lyrics(),
+        commands::music::effects(),
+        commands::music::record(),
+        commands::music::announce(),
+        commands::music::speed(),
+    ];
+
+    spoticord_i18n::localize_commands(&mut commands);
+
     poise::FrameworkOptions {
-        commands: vec![
-            #[cfg(debug_assertions)]
-            commands::debug::ping(),
-            #[cfg(debug_assertions)]
-            commands::debug::token(),
-            commands::core::help(),
-            commands::core::version(),
-            commands::core::rename(),
-            commands::core::link(),
-            commands::core::unlink(),
-            commands::music::join(),
-            commands::music::disconnect(),
-            commands::music::stop(),
-            commands::music::playing(),
-            commands::music::lyrics(),
-        ],
+        commands,
         event_handler: |ctx, event, framework, data| {
             Box::pin(event_handler(ctx, event, framework, data))
         },
@@ -42,6 +55,11 @@ pub fn framework_opts() -> FrameworkOptions<Data, anyhow::Error> {
     }
 }
 
+/// The language to respond to a command in
+pub async fn locale(ctx: Context<'_>) -> Locale {
+    ctx.data().locale(ctx.guild_id(), ctx.locale()).await
+}
+
 pub async fn setup(
     ctx: &serenity_prelude::Context,
     ready: &Ready,
@@ -68,7 +86,10 @@ pub async fn setup(
     let manager = SessionManager::new(songbird, database);
 
     #[cfg(feature = "stats")]
-    let stats = StatsManager::new(spoticord_config::kv_url())?;
+    let stats =
+        StatsManager::new(spoticord_config::kv_url().ok_or_else(|| {
+            anyhow!("KV_URL is required when compiled with the `stats` feature")
+        })?)?;
 
     tokio::spawn(background_loop(
         manager.clone(),
@@ -84,17 +105,30 @@ async fn event_handler(
     ctx: &serenity_prelude::Context,
     event: &FullEvent,
     _framework: FrameworkContext<'_, Data, anyhow::Error>,
-    _data: &Data,
+    data: &Data,
 ) -> Result<()> {
-    if let FullEvent::Ready { data_about_bot } = event {
-        if let Some(shard) = data_about_bot.shard {
-            debug!(
-                "Shard {} logged in (total shards: {})",
-                shard.id.0, shard.total
-            );
+    match event {
+        FullEvent::Ready { data_about_bot } => {
+            if let Some(shard) = data_about_bot.shard {
+                debug!(
+                    "Shard {} logged in (total shards: {})",
+                    shard.id.0, shard.total
+                );
+            }
+
+            ctx.set_activity(Some(ActivityData::listening(spoticord_config::motd())));
         }
 
-        ctx.set_activity(Some(ActivityData::listening(spoticord_config::MOTD)));
+        // Playback embed buttons are handled here instead of by the embeds themselves, so they keep working after restarts
+        FullEvent::InteractionCreate {
+            interaction: Interaction::Component(press),
+        } => {
+            if let Err(why) = playback_embed::handle_button(ctx, data, press).await {
+                error!("Failed to handle playback button press: {why}");
+            }
+        }
+
+        _ => {}
     }
 
     Ok(())
@@ -105,12 +139,27 @@ async fn background_loop(
     shard_manager: Arc<ShardManager>,
     #[cfg(feature = "stats")] mut stats_manager: spoticord_stats::StatsManager,
 ) {
-    #[cfg(feature = "stats")]
-    use log::error;
+    let mut config = spoticord_config::subscribe();
 
     loop {
         tokio::select! {
             _ = tokio::time::sleep(std::time::Duration::from_secs(60)) => {
+                let lyrics = spoticord_player::lyrics::cache().stats();
+
+                debug!(
+                    "Lyrics cache: {}/{} entries, {:.1}% hit rate, {} evictions",
+                    lyrics.entries,
+                    lyrics.capacity,
+                    lyrics.hit_rate() * 100.0,
+                    lyrics.evictions
+                );
+
+                if spoticord_config::get().lyrics.cache_backing == LyricsCacheBacking::Postgres {
+                    if let Err(why) = session_manager.database().delete_expired_lyrics().await {
+                        warn!("Failed to remove expired lyrics from the database: {why}");
+                    }
+                }
+
                 #[cfg(feature = "stats")]
                 {
                     debug!("Retrieving active sessions count for stats");
@@ -131,6 +180,18 @@ async fn background_loop(
                 }
             }
 
+            Ok(()) = config.changed() => {
+                let motd = config.borrow_and_update().bot.motd.clone();
+
+                debug!("Configuration changed, updating activity to: {motd}");
+
+                for runner in shard_manager.runners.lock().await.values() {
+                    runner
+                        .runner_tx
+                        .set_activity(Some(ActivityData::listening(&motd)));
+                }
+            }
+
             _ = tokio::signal::ctrl_c() => {
                 info!("Received interrupt signal, shutting down...");
 
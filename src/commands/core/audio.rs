@@ -0,0 +1,205 @@
+use anyhow::Result;
+use log::error;
+use poise::{ChoiceParameter, CreateReply};
+use serenity::all::{CreateEmbed, CreateEmbedFooter};
+use spoticord_database::models::{AudioPreferences, User};
+use spoticord_i18n::{t, Locale};
+use spoticord_session::manager::SessionQuery;
+use spoticord_utils::discord::Colors;
+
+use crate::bot::{self, Context};
+
+#[derive(Debug, ChoiceParameter)]
+pub enum Bitrate {
+    #[name = "160 kbps"]
+    Normal,
+
+    #[name = "320 kbps (Spotify Premium only)"]
+    High,
+}
+
+#[derive(Debug, ChoiceParameter)]
+pub enum Normalization {
+    #[name = "Off"]
+    Off,
+
+    #[name = "Automatic"]
+    Auto,
+
+    #[name = "Album"]
+    Album,
+
+    #[name = "Track"]
+    Track,
+}
+
+/// Change the audio settings of your Spoticord player
+#[poise::command(slash_command)]
+pub async fn audio(
+    ctx: Context<'_>,
+
+    #[description = "The quality at which music is streamed from Spotify"] bitrate: Option<Bitrate>,
+
+    #[description = "Play every track at a similar volume"] normalization: Option<Normalization>,
+
+    #[description = "Volume boost (in dB, between -10 and 10) applied when normalizing"]
+    #[max = 10.0]
+    pregain: Option<f64>,
+) -> Result<()> {
+    let locale = bot::locale(ctx).await;
+    let db = ctx.data().database();
+
+    let user = match db.get_or_create_user(ctx.author().id.to_string()).await {
+        Ok(user) => user,
+        Err(why) => {
+            error!("Error fetching user: {why}");
+
+            ctx.send(
+                CreateReply::default()
+                    .embed(
+                        CreateEmbed::new()
+                            .description(t!(locale, "audio-fetch-failed"))
+                            .color(Colors::Error),
+                    )
+                    .ephemeral(true),
+            )
+            .await?;
+
+            return Ok(());
+        }
+    };
+
+    // Nothing to change, just show the current settings
+    if bitrate.is_none() && normalization.is_none() && pregain.is_none() {
+        ctx.send(
+            CreateReply::default()
+                .embed(settings_embed(&user, locale).title(t!(locale, "audio-title")))
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    // Poise does not support negative bounds, so the lower end of the pregain range is checked manually
+    if pregain.is_some_and(|pregain| !(-10.0..=10.0).contains(&pregain)) {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title(t!(locale, "audio-pregain-invalid-title"))
+                        .description(t!(locale, "audio-pregain-invalid-description"))
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    if let Some(Bitrate::High) = bitrate {
+        // Only allow the high bitrate if the account is known to be premium
+        if !matches!(db.is_premium(&user.id).await, Ok(Some(true))) {
+            ctx.send(
+                CreateReply::default()
+                    .embed(
+                        CreateEmbed::new()
+                            .title(t!(locale, "audio-bitrate-premium-title"))
+                            .description(t!(locale, "audio-bitrate-premium-description"))
+                            .color(Colors::Error),
+                    )
+                    .ephemeral(true),
+            )
+            .await?;
+
+            return Ok(());
+        }
+    }
+
+    let preferences = AudioPreferences {
+        bitrate: bitrate.map(|bitrate| match bitrate {
+            Bitrate::Normal => Some(160),
+            Bitrate::High => Some(320),
+        }),
+        normalization: normalization
+            .as_ref()
+            .map(|normalization| !matches!(normalization, Normalization::Off)),
+        normalization_type: normalization.and_then(|normalization| match normalization {
+            Normalization::Off => None,
+            Normalization::Auto => Some("auto".to_string()),
+            Normalization::Album => Some("album".to_string()),
+            Normalization::Track => Some("track".to_string()),
+        }),
+        normalization_pregain: pregain.map(|pregain| pregain as f32),
+    };
+
+    let user = match db.update_audio_preferences(&user.id, preferences).await {
+        Ok(()) => db.get_user(&user.id).await?,
+        Err(why) => {
+            error!("Error updating user audio preferences: {why}");
+
+            ctx.send(
+                CreateReply::default()
+                    .embed(
+                        CreateEmbed::new()
+                            .description(t!(locale, "audio-update-failed"))
+                            .color(Colors::Error),
+                    )
+                    .ephemeral(true),
+            )
+            .await?;
+
+            return Ok(());
+        }
+    };
+
+    let has_session = ctx
+        .data()
+        .get_session(SessionQuery::Owner(ctx.author().id))
+        .is_some();
+
+    let mut embed = settings_embed(&user, locale)
+        .title(t!(locale, "audio-updated-title"))
+        .color(Colors::Success);
+
+    if has_session {
+        embed = embed.footer(CreateEmbedFooter::new(t!(locale, "audio-reconnect")));
+    }
+
+    ctx.send(CreateReply::default().embed(embed).ephemeral(true))
+        .await?;
+
+    Ok(())
+}
+
+fn settings_embed(user: &User, locale: Locale) -> CreateEmbed {
+    let bitrate = match user.bitrate {
+        Some(bitrate) => t!(locale, "audio-bitrate", bitrate = bitrate),
+        None => t!(
+            locale,
+            "audio-bitrate-default",
+            bitrate = spoticord_config::get().audio.bitrate
+        ),
+    };
+
+    let normalization = if user.normalization {
+        match user.normalization_type.as_str() {
+            "album" => t!(locale, "audio-normalization-album"),
+            "track" => t!(locale, "audio-normalization-track"),
+            _ => t!(locale, "audio-normalization-auto"),
+        }
+    } else {
+        t!(locale, "audio-normalization-off")
+    };
+
+    CreateEmbed::new()
+        .description(t!(
+            locale,
+            "audio-settings",
+            bitrate = bitrate,
+            normalization = normalization,
+            pregain = format!("{:+.1}", user.normalization_pregain)
+        ))
+        .color(Colors::Info)
+}
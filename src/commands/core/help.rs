@@ -1,23 +1,24 @@
 use anyhow::Result;
 use poise::CreateReply;
 use serenity::all::{CreateEmbed, CreateEmbedAuthor};
+use spoticord_i18n::t;
 use spoticord_utils::discord::Colors;
 
-use crate::bot::Context;
-
-const HELP_MESSAGE: &str = include_str!("help.md");
+use crate::bot::{self, Context};
 
 /// Displays the help message
 #[poise::command(slash_command)]
 pub async fn help(ctx: Context<'_>) -> Result<()> {
+    let locale = bot::locale(ctx).await;
+
     ctx.send(
         CreateReply::default().embed(
             CreateEmbed::new()
                 .author(
-                    CreateEmbedAuthor::new("Spoticord Help")
+                    CreateEmbedAuthor::new(t!(locale, "help-title"))
                         .icon_url("https://spoticord.com/logo-standard.webp"),
                 )
-                .description(HELP_MESSAGE)
+                .description(locale.help())
                 .color(Colors::Info),
         ),
     )
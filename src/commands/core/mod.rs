@@ -1,11 +1,15 @@
+mod audio;
 mod help;
 mod link;
 mod rename;
+mod server;
 mod unlink;
 mod version;
 
+pub use audio::*;
 pub use help::*;
 pub use link::*;
 pub use rename::*;
+pub use server::*;
 pub use unlink::*;
 pub use version::*;
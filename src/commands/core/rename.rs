@@ -2,10 +2,11 @@ use anyhow::Result;
 use log::error;
 use poise::CreateReply;
 use serenity::all::{CreateEmbed, CreateEmbedFooter};
+use spoticord_i18n::t;
 use spoticord_session::manager::SessionQuery;
 use spoticord_utils::discord::Colors;
 
-use crate::bot::Context;
+use crate::bot::{self, Context};
 
 #[poise::command(slash_command)]
 pub async fn rename(
@@ -16,6 +17,7 @@ pub async fn rename(
     #[min_length = 1]
     name: String,
 ) -> Result<()> {
+    let locale = bot::locale(ctx).await;
     let db = ctx.data().database();
 
     let user = match db.get_or_create_user(ctx.author().id.to_string()).await {
@@ -27,7 +29,7 @@ pub async fn rename(
                 CreateReply::default()
                     .embed(
                         CreateEmbed::new()
-                            .description("Something went wrong whilst trying to rename your Spoticord device.")
+                            .description(t!(locale, "rename-fetch-failed"))
                             .color(Colors::Error),
                     )
                     .ephemeral(true),
@@ -45,9 +47,7 @@ pub async fn rename(
             CreateReply::default()
                 .embed(
                     CreateEmbed::new()
-                        .description(
-                            "Something went wrong while trying to rename your Spoticord device.",
-                        )
+                        .description(t!(locale, "rename-failed"))
                         .color(Colors::Error),
                 )
                 .ephemeral(true),
@@ -66,16 +66,15 @@ pub async fn rename(
         CreateReply::default()
             .embed({
                 let mut embed = CreateEmbed::new()
-                    .description(format!(
-                        "Successfully changed the Spotify device name to **{}**",
-                        spoticord_utils::discord::escape(name)
+                    .description(t!(
+                        locale,
+                        "rename-success",
+                        name = spoticord_utils::discord::escape(name)
                     ))
                     .color(Colors::Success);
 
                 if has_session {
-                    embed = embed.footer(CreateEmbedFooter::new(
-                        "You must reconnect the player for the new name to show up",
-                    ));
+                    embed = embed.footer(CreateEmbedFooter::new(t!(locale, "rename-reconnect")));
                 }
 
                 embed
@@ -0,0 +1,388 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use log::error;
+use poise::CreateReply;
+use serenity::all::{AutocompleteChoice, CreateEmbed};
+use spoticord_database::models::GuildSettings;
+use spoticord_i18n::{t, Locale};
+use spoticord_session::manager::SessionQuery;
+use spoticord_utils::discord::Colors;
+
+use crate::bot::{self, Context};
+
+/// The value of `/server language` that lets every user see their own language
+const AUTO_LANGUAGE: &str = "auto";
+
+/// The longest crossfade that can be configured, in seconds
+const MAX_CROSSFADE: u8 = 12;
+
+/// Change the Spoticord settings of this server
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("crossfade", "recording", "language"),
+    subcommand_required,
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn server(_ctx: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// Blend the end of every track into the start of the next one
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD")]
+pub async fn crossfade(
+    ctx: Context<'_>,
+
+    #[description = "The length of the crossfade in seconds, 0 to turn crossfading off"]
+    #[min = 0]
+    #[max = 12]
+    seconds: Option<u8>,
+) -> Result<()> {
+    let locale = bot::locale(ctx).await;
+    let db = ctx.data().database();
+    let guild = ctx.guild_id().expect("poise lied to me");
+
+    let settings = match db.get_or_create_guild(guild.to_string()).await {
+        Ok(settings) => settings,
+        Err(why) => {
+            error!("Error fetching guild: {why}");
+
+            ctx.send(
+                CreateReply::default()
+                    .embed(
+                        CreateEmbed::new()
+                            .description(t!(locale, "settings-fetch-failed"))
+                            .color(Colors::Error),
+                    )
+                    .ephemeral(true),
+            )
+            .await?;
+
+            return Ok(());
+        }
+    };
+
+    // Nothing to change, just show the current setting
+    let Some(seconds) = seconds else {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title(t!(locale, "server-crossfade-title"))
+                        .description(describe_crossfade(settings.crossfade, locale))
+                        .color(Colors::Info),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    let seconds = seconds.min(MAX_CROSSFADE);
+
+    if let Err(why) = db
+        .update_guild_settings(
+            &settings.id,
+            GuildSettings {
+                crossfade: Some(seconds as i16),
+                ..Default::default()
+            },
+        )
+        .await
+    {
+        error!("Error updating guild settings: {why}");
+
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .description(t!(locale, "settings-update-failed"))
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    // Apply the new crossfade to the music that is currently playing
+    if let Some(session) = ctx.data().get_session(SessionQuery::Guild(guild)) {
+        if let Ok(true) = session.active().await {
+            if let Ok(player) = session.player().await {
+                player
+                    .set_crossfade(Duration::from_secs(seconds as u64))
+                    .await;
+            }
+        }
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::new()
+                .title(t!(locale, "server-crossfade-updated-title"))
+                .description(describe_crossfade(seconds as i16, locale))
+                .color(Colors::Success),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Allow moderators to record the music that is played in this server
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD")]
+pub async fn recording(
+    ctx: Context<'_>,
+
+    #[description = "Whether moderators may use /record in this server"] enabled: Option<bool>,
+) -> Result<()> {
+    let locale = bot::locale(ctx).await;
+    let db = ctx.data().database();
+    let guild = ctx.guild_id().expect("poise lied to me");
+
+    let settings = match db.get_or_create_guild(guild.to_string()).await {
+        Ok(settings) => settings,
+        Err(why) => {
+            error!("Error fetching guild: {why}");
+
+            ctx.send(
+                CreateReply::default()
+                    .embed(
+                        CreateEmbed::new()
+                            .description(t!(locale, "settings-fetch-failed"))
+                            .color(Colors::Error),
+                    )
+                    .ephemeral(true),
+            )
+            .await?;
+
+            return Ok(());
+        }
+    };
+
+    // Nothing to change, just show the current setting
+    let Some(enabled) = enabled else {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title(t!(locale, "server-recording-title"))
+                        .description(describe_recording(settings.recording, locale))
+                        .color(Colors::Info),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    if let Err(why) = db
+        .update_guild_settings(
+            &settings.id,
+            GuildSettings {
+                recording: Some(enabled),
+                ..Default::default()
+            },
+        )
+        .await
+    {
+        error!("Error updating guild settings: {why}");
+
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .description(t!(locale, "settings-update-failed"))
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    // A recording that is in progress may not outlive the permission to record
+    if !enabled {
+        if let Some(session) = ctx.data().get_session(SessionQuery::Guild(guild)) {
+            if let Ok(player) = session.player().await {
+                _ = player.stop_recording().await;
+            }
+        }
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::new()
+                .title(t!(locale, "server-recording-updated-title"))
+                .description(describe_recording(enabled, locale))
+                .color(Colors::Success),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Change the language that Spoticord speaks in this server
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD")]
+pub async fn language(
+    ctx: Context<'_>,
+
+    #[description = "The language to use, or automatic to use the language of every user"]
+    #[autocomplete = "autocomplete_language"]
+    language: Option<String>,
+) -> Result<()> {
+    let locale = bot::locale(ctx).await;
+    let db = ctx.data().database();
+    let guild = ctx.guild_id().expect("poise lied to me");
+
+    let settings = match db.get_or_create_guild(guild.to_string()).await {
+        Ok(settings) => settings,
+        Err(why) => {
+            error!("Error fetching guild: {why}");
+
+            ctx.send(
+                CreateReply::default()
+                    .embed(
+                        CreateEmbed::new()
+                            .description(t!(locale, "settings-fetch-failed"))
+                            .color(Colors::Error),
+                    )
+                    .ephemeral(true),
+            )
+            .await?;
+
+            return Ok(());
+        }
+    };
+
+    let current = settings.locale.as_deref().and_then(Locale::exact);
+
+    // Nothing to change, just show the current setting
+    let Some(language) = language else {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title(t!(locale, "server-language-title"))
+                        .description(describe_language(current, locale))
+                        .color(Colors::Info),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    let new_locale = if language.eq_ignore_ascii_case(AUTO_LANGUAGE) {
+        None
+    } else {
+        match Locale::exact(&language) {
+            Some(locale) => Some(locale),
+            None => {
+                ctx.send(
+                    CreateReply::default()
+                        .embed(
+                            CreateEmbed::new()
+                                .title(t!(locale, "server-language-unknown-title"))
+                                .description(t!(
+                                    locale,
+                                    "server-language-unknown-description",
+                                    language = spoticord_utils::discord::escape(language)
+                                ))
+                                .color(Colors::Error),
+                        )
+                        .ephemeral(true),
+                )
+                .await?;
+
+                return Ok(());
+            }
+        }
+    };
+
+    if let Err(why) = db
+        .update_guild_settings(
+            &settings.id,
+            GuildSettings {
+                locale: Some(new_locale.map(|locale| locale.code().to_string())),
+                ..Default::default()
+            },
+        )
+        .await
+    {
+        error!("Error updating guild settings: {why}");
+
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .description(t!(locale, "settings-update-failed"))
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    // Confirm the change in the language that will be used from now on
+    let locale = bot::locale(ctx).await;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::new()
+                .title(t!(locale, "server-language-updated-title"))
+                .description(describe_language(new_locale, locale))
+                .color(Colors::Success),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn autocomplete_language(ctx: Context<'_>, partial: &str) -> Vec<AutocompleteChoice> {
+    let locale = bot::locale(ctx).await;
+    let partial = partial.to_lowercase();
+
+    std::iter::once((t!(locale, "server-language-auto-choice"), AUTO_LANGUAGE))
+        .chain(Locale::all().map(|locale| {
+            (
+                format!("{} ({})", locale.name(), locale.code()),
+                locale.code(),
+            )
+        }))
+        .filter(|(name, _)| name.to_lowercase().contains(&partial))
+        .map(|(name, value)| AutocompleteChoice::new(name, value))
+        .collect()
+}
+
+fn describe_language(language: Option<Locale>, locale: Locale) -> String {
+    match language {
+        Some(language) => t!(locale, "server-language-server", language = language.name()),
+        None => t!(locale, "server-language-auto"),
+    }
+}
+
+fn describe_recording(enabled: bool, locale: Locale) -> String {
+    if enabled {
+        t!(locale, "server-recording-allowed")
+    } else {
+        t!(locale, "server-recording-denied")
+    }
+}
+
+fn describe_crossfade(seconds: i16, locale: Locale) -> String {
+    match seconds {
+        0 => t!(locale, "server-crossfade-off"),
+        seconds => t!(locale, "server-crossfade-seconds", seconds = seconds),
+    }
+}
@@ -2,10 +2,11 @@ use anyhow::Result;
 use log::error;
 use poise::CreateReply;
 use serenity::all::{CreateEmbed, CreateEmbedFooter};
+use spoticord_i18n::t;
 use spoticord_session::manager::SessionQuery;
 use spoticord_utils::discord::Colors;
 
-use crate::bot::{Context, FrameworkError};
+use crate::bot::{self, Context, FrameworkError};
 
 /// Unlink your Spotify account from Spoticord
 #[poise::command(slash_command, on_error = on_error)]
@@ -14,6 +15,7 @@ pub async fn unlink(
 
     #[description = "Also delete Discord account information"] user_data: Option<bool>,
 ) -> Result<()> {
+    let locale = bot::locale(ctx).await;
     let manager = ctx.data();
     let db = manager.database();
     let user_id = ctx.author().id.to_string();
@@ -35,13 +37,12 @@ pub async fn unlink(
             CreateReply::default()
                 .embed(
                     CreateEmbed::new()
-                        .title("No Spotify account linked")
-                        .description(
-                            "You cannot unlink your Spotify account if you haven't linked one.",
-                        )
-                        .footer(CreateEmbedFooter::new(
-                            "You can use /link to link a new Spotify account.",
-                        ))
+                        .title(t!(locale, "unlink-not-linked-title"))
+                        .description(t!(locale, "unlink-not-linked-description"))
+                        .footer(CreateEmbedFooter::new(t!(
+                            locale,
+                            "unlink-not-linked-footer"
+                        )))
                         .color(Colors::Error),
                 )
                 .ephemeral(true),
@@ -55,11 +56,9 @@ pub async fn unlink(
         CreateReply::default()
             .embed(
                 CreateEmbed::new()
-                    .title("Account unlinked")
-                    .description("You have unlinked your Spotify account from Spoticord.")
-                    .footer(CreateEmbedFooter::new(
-                        "Changed your mind? You can use /link to link a new Spotify account.",
-                    ))
+                    .title(t!(locale, "unlink-success-title"))
+                    .description(t!(locale, "unlink-success-description"))
+                    .footer(CreateEmbedFooter::new(t!(locale, "unlink-success-footer")))
                     .color(Colors::Success),
             )
             .ephemeral(true),
@@ -73,12 +72,14 @@ async fn on_error(error: FrameworkError<'_>) {
     if let FrameworkError::Command { error, ctx, .. } = error {
         error!("An error occured during linking of new account: {error}");
 
+        let locale = bot::locale(ctx).await;
+
         _ = ctx
             .send(
                 CreateReply::default()
                     .embed(
                         CreateEmbed::new()
-                            .description("An error occured whilst trying to unlink your account.")
+                            .description(t!(locale, "unlink-failed"))
                             .color(Colors::Error),
                     )
                     .ephemeral(true),
@@ -2,28 +2,28 @@ use anyhow::Result;
 use poise::CreateReply;
 use serenity::all::{CreateEmbed, CreateEmbedAuthor};
 use spoticord_config::VERSION;
+use spoticord_i18n::t;
 use spoticord_utils::discord::Colors;
 
-use crate::bot::Context;
+use crate::bot::{self, Context};
 
 const IMAGE_URL: &str = "https://cdn.discordapp.com/avatars/389786424142200835/6bfe3840b0aa6a1baf432bb251b70c9f.webp?size=128";
 
 /// Shows the current active version of Spoticord
 #[poise::command(slash_command)]
 pub async fn version(ctx: Context<'_>) -> Result<()> {
-    // Had to pull this from the builder as rustfmt refused to format the file
-    let description = format!("Current version: {}\n\nSpoticord is open source, check it out [on GitHub](https://github.com/SpoticordMusic)", VERSION);
+    let locale = bot::locale(ctx).await;
 
     ctx.send(
         CreateReply::default().embed(
             CreateEmbed::default()
-                .title("Spoticord Version")
+                .title(t!(locale, "version-title"))
                 .author(
-                    CreateEmbedAuthor::new("Maintained by: DaXcess (@daxcess)")
+                    CreateEmbedAuthor::new(t!(locale, "version-maintainer"))
                         .url("https://github.com/DaXcess")
                         .icon_url(IMAGE_URL),
                 )
-                .description(description)
+                .description(t!(locale, "version-description", version = VERSION))
                 .color(Colors::Info),
         ),
     )
@@ -0,0 +1,107 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use log::error;
+use poise::CreateReply;
+use serenity::all::CreateEmbed;
+use spoticord_i18n::t;
+use spoticord_session::manager::SessionQuery;
+use spoticord_utils::discord::Colors;
+
+use crate::bot::{self, Context};
+
+/// Show diagnostics about the audio buffer and levels of the player in this server
+#[poise::command(slash_command, guild_only, owners_only, hide_in_help)]
+pub async fn audiostats(ctx: Context<'_>) -> Result<()> {
+    let locale = bot::locale(ctx).await;
+    let manager = ctx.data();
+    let guild = ctx.guild_id().expect("poise lied to me");
+
+    let Some(session) = manager.get_session(SessionQuery::Guild(guild)) else {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title(t!(locale, "audiostats-failed-title"))
+                        .description(t!(locale, "not-playing"))
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    let stats = match session.player().await {
+        Ok(player) => match player.audio_stats().await {
+            Ok(stats) => player.loudness().await.map(|loudness| (stats, loudness)),
+            Err(why) => Err(why),
+        },
+        Err(why) => Err(why),
+    };
+
+    let (stats, loudness) = match stats {
+        Ok(stats) => stats,
+        Err(why) => {
+            error!("Failed to retrieve audio stats: {why}");
+
+            ctx.send(
+                CreateReply::default()
+                    .embed(
+                        CreateEmbed::new()
+                            .description(t!(locale, "audiostats-failed"))
+                            .color(Colors::Error),
+                    )
+                    .ephemeral(true),
+            )
+            .await?;
+
+            return Ok(());
+        }
+    };
+
+    let fill = if stats.capacity.is_zero() {
+        0.0
+    } else {
+        stats.buffered.as_secs_f64() / stats.capacity.as_secs_f64() * 100.0
+    };
+
+    ctx.send(
+        CreateReply::default()
+            .embed(
+                CreateEmbed::new()
+                    .title(t!(locale, "audiostats-title"))
+                    .description(t!(
+                        locale,
+                        "audiostats-description",
+                        capacity = millis(stats.capacity),
+                        buffered = millis(stats.buffered),
+                        fill = format!("{fill:.0}"),
+                        underruns = stats.underruns.to_string(),
+                        silence = millis(stats.silence),
+                        overruns = stats.overruns.to_string(),
+                        loudness = level(loudness.short_term, "LUFS"),
+                        peak = level(loudness.peak, "dBFS"),
+                        gain_reduction = format!("{:.1}", loudness.gain_reduction),
+                    ))
+                    .color(Colors::Info),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{} ms", duration.as_millis())
+}
+
+fn level(value: f32, unit: &str) -> String {
+    if value.is_finite() {
+        format!("{value:.1} {unit}")
+    } else {
+        format!("-∞ {unit}")
+    }
+}
@@ -1,5 +1,11 @@
+mod audiostats;
+#[cfg(debug_assertions)]
 mod ping;
+#[cfg(debug_assertions)]
 mod token;
 
+pub use audiostats::*;
+#[cfg(debug_assertions)]
 pub use ping::*;
+#[cfg(debug_assertions)]
 pub use token::*;
@@ -0,0 +1,118 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use poise::CreateReply;
+use serenity::all::CreateEmbed;
+use spoticord_i18n::t;
+use spoticord_session::manager::SessionQuery;
+use spoticord_utils::discord::Colors;
+
+use crate::bot::{self, Context};
+
+/// Play a sound over the music, which is temporarily turned down
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn announce(
+    ctx: Context<'_>,
+
+    #[description = "The announcement to play"]
+    #[autocomplete = "autocomplete_file"]
+    file: String,
+) -> Result<()> {
+    let locale = bot::locale(ctx).await;
+    let guild = ctx.guild_id().expect("poise lied to me");
+
+    let Some(session) = ctx.data().get_session(SessionQuery::Guild(guild)) else {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title(t!(locale, "announce-failed-title"))
+                        .description(t!(locale, "announce-not-connected"))
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    let directory = PathBuf::from(&spoticord_config::get().audio.announcement_dir);
+
+    let Some(path) = resolve(&directory, &file).await else {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title(t!(locale, "announce-failed-title"))
+                        .description(t!(locale, "announce-unknown", file = file.as_str()))
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    session.announce(path).await?;
+
+    ctx.send(
+        CreateReply::default()
+            .embed(
+                CreateEmbed::new()
+                    .description(t!(locale, "announce-playing", file = file.as_str()))
+                    .color(Colors::Info),
+            )
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Find the announcement with the provided name, which must be a file directly inside of the announcement directory
+async fn resolve(directory: &Path, name: &str) -> Option<PathBuf> {
+    let directory = tokio::fs::canonicalize(directory).await.ok()?;
+    let path = tokio::fs::canonicalize(directory.join(name)).await.ok()?;
+
+    // Prevent escaping the announcement directory using `..` or symlinks
+    if path.parent() != Some(&directory) {
+        return None;
+    }
+
+    tokio::fs::metadata(&path)
+        .await
+        .is_ok_and(|metadata| metadata.is_file())
+        .then_some(path)
+}
+
+async fn autocomplete_file(_ctx: Context<'_>, partial: &str) -> impl Iterator<Item = String> {
+    let directory = spoticord_config::get().audio.announcement_dir.clone();
+    let partial = partial.to_lowercase();
+    let mut files = vec![];
+
+    if let Ok(mut entries) = tokio::fs::read_dir(directory).await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            if !entry.file_type().await.is_ok_and(|kind| kind.is_file()) {
+                continue;
+            }
+
+            if let Ok(name) = entry.file_name().into_string() {
+                if name.to_lowercase().contains(&partial) {
+                    files.push(name);
+                }
+            }
+        }
+    }
+
+    files.sort();
+
+    // Discord shows at most 25 suggestions
+    files.into_iter().take(25)
+}
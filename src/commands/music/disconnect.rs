@@ -1,13 +1,16 @@
 use anyhow::Error;
 use poise::CreateReply;
 use serenity::all::CreateEmbed;
+use spoticord_i18n::t;
 use spoticord_session::manager::SessionQuery;
 use spoticord_utils::discord::Colors;
 
-use crate::bot::Context;
+use super::rules::may_stop;
+use crate::bot::{self, Context};
 
 #[poise::command(slash_command, guild_only)]
 pub async fn disconnect(ctx: Context<'_>) -> Result<(), Error> {
+    let locale = bot::locale(ctx).await;
     let manager = ctx.data();
     let guild = ctx.guild_id().expect("poise lied to me");
 
@@ -16,8 +19,8 @@ pub async fn disconnect(ctx: Context<'_>) -> Result<(), Error> {
             CreateReply::default()
                 .embed(
                     CreateEmbed::new()
-                        .title("Cannot disconnect bot")
-                        .description("I'm currently not connected to any voice channel.")
+                        .title(t!(locale, "disconnect-failed-title"))
+                        .description(t!(locale, "not-connected"))
                         .color(Colors::Error),
                 )
                 .ephemeral(true),
@@ -27,13 +30,13 @@ pub async fn disconnect(ctx: Context<'_>) -> Result<(), Error> {
         return Ok(());
     };
 
-    if session.active().await? && session.owner().await? != ctx.author().id {
+    if !may_stop(&session, ctx.author().id).await? {
         ctx.send(
             CreateReply::default()
                 .embed(
                     CreateEmbed::new()
-                        .title("Cannot disconnect bot")
-                        .description("Only the host may disconnect the bot.")
+                        .title(t!(locale, "disconnect-failed-title"))
+                        .description(t!(locale, "disconnect-not-host"))
                         .color(Colors::Error),
                 )
                 .ephemeral(true),
@@ -48,8 +51,8 @@ pub async fn disconnect(ctx: Context<'_>) -> Result<(), Error> {
     ctx.send(
         CreateReply::default().embed(
             CreateEmbed::new()
-                .title("Goodbye, for now!")
-                .description("I have left the voice channel, goodbye for now.")
+                .title(t!(locale, "disconnect-title"))
+                .description(t!(locale, "disconnect-description"))
                 .color(Colors::Info),
         ),
     )
@@ -0,0 +1,194 @@
+use anyhow::Result;
+use poise::{ChoiceParameter, CreateReply};
+use serenity::all::CreateEmbed;
+use spoticord_i18n::{t, Locale};
+use spoticord_player::{BassBoost, Effects, Equalizer};
+use spoticord_session::manager::SessionQuery;
+use spoticord_utils::discord::Colors;
+
+use crate::bot::{self, Context};
+
+#[derive(Debug, ChoiceParameter)]
+pub enum EqualizerChoice {
+    Flat,
+    Pop,
+    Rock,
+    Jazz,
+    Classical,
+    Electronic,
+    Vocal,
+}
+
+#[derive(Debug, ChoiceParameter)]
+pub enum BassBoostChoice {
+    Off,
+    Low,
+    Medium,
+    High,
+}
+
+/// Apply audio effects to the music that is being played
+#[poise::command(slash_command, guild_only)]
+pub async fn effects(
+    ctx: Context<'_>,
+
+    #[description = "The equalizer preset to use"] equalizer: Option<EqualizerChoice>,
+
+    #[description = "How much to boost the bass"] bass_boost: Option<BassBoostChoice>,
+
+    #[description = "Speed up the music, which also raises the pitch"] nightcore: Option<bool>,
+
+    #[rename = "8d"]
+    #[description = "Slowly rotate the music around the listener"]
+    rotate: Option<bool>,
+
+    #[description = "Play the music in mono"] mono: Option<bool>,
+
+    #[description = "Turn off all effects"] reset: Option<bool>,
+) -> Result<()> {
+    let locale = bot::locale(ctx).await;
+    let manager = ctx.data();
+    let guild = ctx.guild_id().expect("poise lied to me");
+
+    let Some(session) = manager.get_session(SessionQuery::Guild(guild)) else {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title(t!(locale, "effects-failed-title"))
+                        .description(t!(locale, "not-playing"))
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    if !session.active().await? {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title(t!(locale, "effects-failed-title"))
+                        .description(t!(locale, "not-playing"))
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    let player = session.player().await?;
+    let current = player.effects().await?;
+
+    let changed = equalizer.is_some()
+        || bass_boost.is_some()
+        || nightcore.is_some()
+        || rotate.is_some()
+        || mono.is_some()
+        || reset.unwrap_or(false);
+
+    // Nothing to change, just show the current effects
+    if !changed {
+        ctx.send(
+            CreateReply::default()
+                .embed(effects_embed(&current, locale).title(t!(locale, "effects-title")))
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    if session.owner().await? != ctx.author().id {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title(t!(locale, "effects-failed-title"))
+                        .description(t!(locale, "effects-not-host"))
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    let mut effects = if reset.unwrap_or(false) {
+        Effects::default()
+    } else {
+        current
+    };
+
+    if let Some(equalizer) = equalizer {
+        effects.equalizer = match equalizer {
+            EqualizerChoice::Flat => Equalizer::Flat,
+            EqualizerChoice::Pop => Equalizer::Pop,
+            EqualizerChoice::Rock => Equalizer::Rock,
+            EqualizerChoice::Jazz => Equalizer::Jazz,
+            EqualizerChoice::Classical => Equalizer::Classical,
+            EqualizerChoice::Electronic => Equalizer::Electronic,
+            EqualizerChoice::Vocal => Equalizer::Vocal,
+        };
+    }
+
+    if let Some(bass_boost) = bass_boost {
+        effects.bass_boost = match bass_boost {
+            BassBoostChoice::Off => BassBoost::Off,
+            BassBoostChoice::Low => BassBoost::Low,
+            BassBoostChoice::Medium => BassBoost::Medium,
+            BassBoostChoice::High => BassBoost::High,
+        };
+    }
+
+    effects.nightcore = nightcore.unwrap_or(effects.nightcore);
+    effects.rotate = rotate.unwrap_or(effects.rotate);
+    effects.mono = mono.unwrap_or(effects.mono);
+
+    player.set_effects(effects).await;
+
+    ctx.send(
+        CreateReply::default().embed(
+            effects_embed(&effects, locale)
+                .title(t!(locale, "effects-updated-title"))
+                .color(Colors::Success),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn effects_embed(effects: &Effects, locale: Locale) -> CreateEmbed {
+    if effects.is_default() {
+        return CreateEmbed::new()
+            .description(t!(locale, "effects-none"))
+            .color(Colors::Info);
+    }
+
+    let toggle = |enabled: bool| {
+        if enabled {
+            t!(locale, "effects-on")
+        } else {
+            t!(locale, "effects-off")
+        }
+    };
+
+    CreateEmbed::new()
+        .description(t!(
+            locale,
+            "effects-settings",
+            equalizer = format!("{:?}", effects.equalizer),
+            bass_boost = format!("{:?}", effects.bass_boost),
+            nightcore = toggle(effects.nightcore),
+            rotate = toggle(effects.rotate),
+            mono = toggle(effects.mono),
+        ))
+        .color(Colors::Info)
+}
@@ -6,15 +6,16 @@ use poise::CreateReply;
 use serenity::all::{
     Channel, ChannelId, CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter, UserId,
 };
-use spoticord_database::error::DatabaseError;
-use spoticord_session::manager::SessionQuery;
+use spoticord_i18n::t;
 use spoticord_utils::discord::Colors;
 
-use crate::bot::Context;
+use super::rules::{check_join, Join, JoinDenied, JoinRequest};
+use crate::bot::{self, Context};
 
 /// Join the current voice channel
 #[poise::command(slash_command, guild_only)]
 pub async fn join(ctx: Context<'_>) -> Result<()> {
+    let locale = bot::locale(ctx).await;
     let guild = ctx.guild_id().expect("poise lied to me");
     let manager = ctx.data();
 
@@ -28,8 +29,8 @@ pub async fn join(ctx: Context<'_>) -> Result<()> {
             CreateReply::default()
                 .embed(
                     CreateEmbed::new()
-                        .title("An error occured")
-                        .description("This server hasn't been cached yet?")
+                        .title(t!(locale, "error-title"))
+                        .description(t!(locale, "join-not-cached"))
                         .color(Colors::Error),
                 )
                 .ephemeral(true),
@@ -39,97 +40,68 @@ pub async fn join(ctx: Context<'_>) -> Result<()> {
         return Ok(());
     };
 
-    let Some(channel) = guild
+    let voice_channel = guild
         .voice_states
         .get(&ctx.author().id)
-        .and_then(|state| state.channel_id)
-    else {
-        ctx.send(
-            CreateReply::default()
-                .embed(
-                    CreateEmbed::new()
-                        .title("Cannot join voice channel")
-                        .description("You need to connect to a voice channel before running /join")
-                        .color(Colors::Error),
-                )
-                .ephemeral(true),
-        )
-        .await?;
+        .and_then(|state| state.channel_id);
 
-        return Ok(());
+    let voice_permissions = match voice_channel {
+        Some(channel) => has_voice_permissions(ctx, channel).await?,
+        None => false,
     };
 
-    if !has_voice_permissions(ctx, channel).await? {
-        ctx.send(
-            CreateReply::default()
-                .embed(
-                    CreateEmbed::new()
-                        .title("Cannot join voice channel")
-                        .description(
-                            "The voice channel you are in is not available.
-                                    I might not have the permissions to see this channel.",
-                        )
-                        .color(Colors::Error),
-                )
-                .ephemeral(true),
-        )
-        .await?;
-
-        return Ok(());
-    }
-
-    if !has_text_permissions(ctx, ctx.channel_id()).await? {
-        ctx.send(
-            CreateReply::default()
-                .embed(
-                    CreateEmbed::new()
-                        .title("Cannot join voice channel")
-                        .description(
-                            "I do not have permissions to send messages / links in this text channel.",
-                        )
-                        .color(Colors::Error),
-                )
-                .ephemeral(true),
-        )
-        .await?;
-
-        return Ok(());
-    }
-
-    // Check whether the user has linked their Spotify account
-    if let Err(DatabaseError::NotFound) = manager
-        .database()
-        .get_account(ctx.author().id.to_string())
-        .await
-    {
-        ctx.send(
-            CreateReply::default()
-                .embed(
-                    CreateEmbed::new()
-                        .title("No Spotify account")
-                        .description(
-                            "You need to link your Spotify account to Spoticord before being able to use it.\nUse the `/link` command to link your account.",
-                        )
-                        .color(Colors::Error),
-                )
-                .ephemeral(true),
-        )
-        .await?;
-
-        return Ok(());
-    }
+    let request = JoinRequest {
+        guild: guild.id,
+        user: ctx.author().id,
+        voice_channel,
+        voice_permissions,
+        text_permissions: has_text_permissions(ctx, ctx.channel_id()).await?,
+    };
 
-    let mut session_opt = manager.get_session(SessionQuery::Guild(guild.id));
+    let join = match check_join(manager, request).await? {
+        Ok(join) => join,
+        Err(denied) => {
+            let (title, description) = match denied {
+                JoinDenied::NotInVoiceChannel => (
+                    t!(locale, "join-denied-title"),
+                    t!(locale, "join-not-in-voice-channel"),
+                ),
+                JoinDenied::NoVoicePermissions => (
+                    t!(locale, "join-denied-title"),
+                    t!(locale, "join-no-voice-permissions"),
+                ),
+                JoinDenied::NoTextPermissions => (
+                    t!(locale, "join-denied-title"),
+                    t!(locale, "join-no-text-permissions"),
+                ),
+                JoinDenied::NoAccount => (
+                    t!(locale, "join-no-account-title"),
+                    t!(locale, "join-no-account-description"),
+                ),
+                JoinDenied::Busy => (
+                    t!(locale, "join-busy-title"),
+                    t!(locale, "join-busy-description"),
+                ),
+                JoinDenied::AlreadyPlaying(other) => {
+                    let server_name = other.to_partial_guild(&ctx).await?.name;
+
+                    (
+                        t!(locale, "join-already-playing-title"),
+                        t!(
+                            locale,
+                            "join-already-playing-description",
+                            server = spoticord_utils::discord::escape(server_name)
+                        ),
+                    )
+                }
+            };
 
-    // Check if this server already has a session active
-    if let Some(session) = &session_opt {
-        if session.active().await? {
             ctx.send(
                 CreateReply::default()
                     .embed(
                         CreateEmbed::new()
-                            .title("Spoticord is busy")
-                            .description("Spoticord is already being used in this server.")
+                            .title(title)
+                            .description(description)
                             .color(Colors::Error),
                     )
                     .ephemeral(true),
@@ -138,107 +110,95 @@ pub async fn join(ctx: Context<'_>) -> Result<()> {
 
             return Ok(());
         }
-    }
-
-    // Prevent the user from using Spoticord simultaneously in multiple servers
-    if let Some(session) = manager.get_session(SessionQuery::Owner(ctx.author().id)) {
-        let server_name = session.guild().to_partial_guild(&ctx).await?.name;
-
-        ctx.send(
-            CreateReply::default()
-                .embed(
-                    CreateEmbed::new()
-                        .title("You are already using Spoticord")
-                        .description(format!(
-                            "You are already using Spoticord in `{}`\n\n\
-                            Stop playing in that server first before starting a new session.",
-                            spoticord_utils::discord::escape(server_name)
-                        ))
-                        .color(Colors::Error),
-                )
-                .ephemeral(true),
-        )
-        .await?;
-
-        return Ok(());
-    }
+    };
 
     ctx.defer().await?;
 
-    if let Some(session) = &session_opt {
-        if session.voice_channel() != channel {
-            session.disconnect().await;
-            session_opt = None;
-
-            // Give serenity/songbird some time to register the disconnect
-            tokio::time::sleep(Duration::from_secs(1)).await;
-        }
-    }
-
-    if let Some(session) = session_opt {
-        if let Err(why) = session.reactivate(ctx.author().id).await {
-            error!("Failed to reactivate session: {why}");
+    let channel = match join {
+        Join::Reactivate(session) => {
+            if let Err(why) = session.reactivate(ctx.author().id).await {
+                error!("Failed to reactivate session: {why}");
+
+                ctx.send(
+                    CreateReply::default()
+                        .embed(
+                            CreateEmbed::new()
+                                .title(t!(locale, "join-reactivate-failed-title"))
+                                .description(t!(locale, "join-reactivate-failed-description"))
+                                .color(Colors::Error),
+                        )
+                        .ephemeral(true),
+                )
+                .await?;
 
-            ctx.send(
-                CreateReply::default()
-                    .embed(
-                        CreateEmbed::new()
-                            .title("Failed to reactivate session")
-                            .description(
-                                "An error occured whilst trying to reactivate the session. Please try again.",
-                            )
-                            .color(Colors::Error),
-                    )
-                    .ephemeral(true),
-            )
-            .await?;
+                return Ok(());
+            }
 
-            return Ok(());
+            session.voice_channel()
         }
-    } else if let Err(why) = manager
-        .create_session(
-            ctx.serenity_context(),
-            guild.id,
+        Join::Create {
             channel,
-            ctx.channel_id(),
-            ctx.author().id,
-        )
-        .await
-    {
-        error!("Failed to create session: {why}");
-
-        let description = if matches!(why, spoticord_session::error::Error::AuthenticationFailed) {
-            "Unable to authenticate with Spotify. Did you change your password?\n\nThe broken credentials used have been deleted.\n\nYou might need to relink your account using `/link`."
-        } else {
-            "An error occured whilst trying to create a session. Please try again."
-        };
-
-        ctx.send(
-            CreateReply::default()
-                .embed(
-                    CreateEmbed::new()
-                        .title("Failed to create session")
-                        .description(description)
-                        .color(Colors::Error),
+            disconnect,
+        } => {
+            if let Some(session) = disconnect {
+                session.disconnect().await;
+
+                // Give serenity/songbird some time to register the disconnect
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+
+            if let Err(why) = manager
+                .create_session(
+                    ctx.serenity_context(),
+                    guild.id,
+                    channel,
+                    ctx.channel_id(),
+                    ctx.author().id,
+                    locale,
                 )
-                .ephemeral(true),
-        )
-        .await?;
+                .await
+            {
+                error!("Failed to create session: {why}");
+
+                let description =
+                    if matches!(why, spoticord_session::error::Error::AuthenticationFailed) {
+                        t!(locale, "join-authentication-failed")
+                    } else {
+                        t!(locale, "join-create-failed-description")
+                    };
+
+                ctx.send(
+                    CreateReply::default()
+                        .embed(
+                            CreateEmbed::new()
+                                .title(t!(locale, "join-create-failed-title"))
+                                .description(description)
+                                .color(Colors::Error),
+                        )
+                        .ephemeral(true),
+                )
+                .await?;
 
-        return Ok(());
-    }
+                return Ok(());
+            }
+
+            channel
+        }
+    };
 
     ctx.send(
         CreateReply::default().embed(
             CreateEmbed::new()
                 .author(
-                    CreateEmbedAuthor::new("Connected to voice channel")
+                    CreateEmbedAuthor::new(t!(locale, "join-connected"))
                         .icon_url("https://spoticord.com/speaker.png"),
                 )
-                .description(format!("Come listen along in <#{}>", channel))
-                .footer(CreateEmbedFooter::new(
-                    "You must manually select your device in Spotify",
+                .description(t!(
+                    locale,
+                    "join-listen-along",
+                    channel = channel.to_string()
                 ))
+                .footer(CreateEmbedFooter::new(t!(locale, "join-select-device")))
                 .color(Colors::Info),
         ),
     )
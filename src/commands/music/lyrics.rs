@@ -1,14 +1,23 @@
 use anyhow::Result;
 use poise::CreateReply;
 use serenity::all::CreateEmbed;
-use spoticord_session::manager::SessionQuery;
+use spoticord_i18n::{t, Locale};
+use spoticord_session::{lyrics_embed::lyrics_file, manager::SessionQuery, SessionHandle};
 use spoticord_utils::discord::Colors;
 
-use crate::bot::Context;
+use crate::bot::{self, Context};
 
 /// Show the lyrics of the current song that is being played
 #[poise::command(slash_command, guild_only)]
-pub async fn lyrics(ctx: Context<'_>) -> Result<()> {
+pub async fn lyrics(
+    ctx: Context<'_>,
+
+    #[description = "Follow along with the song, highlighting the line that is being sung"]
+    karaoke: Option<bool>,
+
+    #[description = "Send the full lyrics as a file only you can see"] export: Option<bool>,
+) -> Result<()> {
+    let locale = bot::locale(ctx).await;
     let manager = ctx.data();
     let guild = ctx.guild_id().expect("poise lied to me");
 
@@ -17,8 +26,8 @@ pub async fn lyrics(ctx: Context<'_>) -> Result<()> {
             CreateReply::default()
                 .embed(
                     CreateEmbed::new()
-                        .title("Cannot get lyrics")
-                        .description("I'm currently not playing any music in this server.")
+                        .title(t!(locale, "lyrics-not-playing-title"))
+                        .description(t!(locale, "not-playing"))
                         .color(Colors::Error),
                 )
                 .ephemeral(true),
@@ -28,13 +37,56 @@ pub async fn lyrics(ctx: Context<'_>) -> Result<()> {
         return Ok(());
     };
 
+    if export.unwrap_or(false) {
+        return export_lyrics(ctx, session, locale).await;
+    }
+
     let Context::Application(context) = ctx else {
         panic!("Slash command is a prefix command?");
     };
 
     session
-        .create_lyrics_embed(context.interaction.clone())
+        .create_lyrics_embed(
+            context.interaction.clone(),
+            karaoke.unwrap_or(false),
+            locale,
+        )
+        .await?;
+
+    Ok(())
+}
+
+async fn export_lyrics(ctx: Context<'_>, session: SessionHandle, locale: Locale) -> Result<()> {
+    // Finding the lyrics can take longer than Discord waits for a reply
+    ctx.defer_ephemeral().await?;
+
+    let player = session.player().await?;
+
+    let (Some(playback_info), Some(lyrics)) =
+        (player.playback_info().await?, player.get_lyrics().await?)
+    else {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title(t!(locale, "lyrics-unavailable-title"))
+                        .description(t!(locale, "lyrics-unavailable-description"))
+                        .color(Colors::Info),
+                )
+                .ephemeral(true),
+        )
         .await?;
 
+        return Ok(());
+    };
+
+    ctx.send(
+        CreateReply::default()
+            .content(t!(locale, "lyrics-file", track = playback_info.name()))
+            .attachment(lyrics_file(&lyrics, &playback_info))
+            .ephemeral(true),
+    )
+    .await?;
+
     Ok(())
 }
@@ -1,11 +1,20 @@
+mod announce;
 mod disconnect;
+mod effects;
 mod join;
 mod lyrics;
 mod playing;
+mod record;
+pub mod rules;
+mod speed;
 mod stop;
 
+pub use announce::*;
 pub use disconnect::*;
+pub use effects::*;
 pub use join::*;
 pub use lyrics::*;
 pub use playing::*;
+pub use record::*;
+pub use speed::*;
 pub use stop::*;
@@ -1,10 +1,11 @@
 use anyhow::Result;
 use poise::CreateReply;
 use serenity::all::CreateEmbed;
+use spoticord_i18n::t;
 use spoticord_session::{manager::SessionQuery, playback_embed::UpdateBehavior};
 use spoticord_utils::discord::Colors;
 
-use crate::bot::Context;
+use crate::bot::{self, Context};
 
 /// Show details of the current song that is being played
 #[poise::command(slash_command, guild_only)]
@@ -14,6 +15,7 @@ pub async fn playing(
         UpdateBehavior,
     >,
 ) -> Result<()> {
+    let locale = bot::locale(ctx).await;
     let manager = ctx.data();
     let guild = ctx.guild_id().expect("poise lied to me");
 
@@ -22,8 +24,8 @@ pub async fn playing(
             CreateReply::default()
                 .embed(
                     CreateEmbed::new()
-                        .title("Cannot display song details")
-                        .description("I'm currently not playing any music in this server.")
+                        .title(t!(locale, "playback-not-playing-title"))
+                        .description(t!(locale, "not-playing"))
                         .color(Colors::Error),
                 )
                 .ephemeral(true),
@@ -38,7 +40,11 @@ pub async fn playing(
     };
 
     session
-        .create_playback_embed(context.interaction, update_behavior.unwrap_or_default())
+        .create_playback_embed(
+            context.interaction,
+            update_behavior.unwrap_or_default(),
+            locale,
+        )
         .await?;
 
     Ok(())
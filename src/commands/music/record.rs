@@ -0,0 +1,216 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use log::error;
+use poise::{ChoiceParameter, CreateReply};
+use serenity::all::CreateEmbed;
+use spoticord_i18n::t;
+use spoticord_player::RecordingFormat;
+use spoticord_session::manager::SessionQuery;
+use spoticord_utils::discord::Colors;
+
+use crate::bot::{self, Context};
+
+#[derive(Debug, ChoiceParameter)]
+pub enum Format {
+    #[name = "WAV (32-bit float)"]
+    Wav,
+
+    #[name = "FLAC (16-bit, lossless)"]
+    Flac,
+}
+
+/// Record the music that is played in this server
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("record_start", "record_stop"),
+    subcommand_required,
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn record(_ctx: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// Start recording the music that is played in this server
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "start",
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn record_start(
+    ctx: Context<'_>,
+
+    #[description = "The file format of the recording, WAV by default"] format: Option<Format>,
+) -> Result<()> {
+    let locale = bot::locale(ctx).await;
+    let manager = ctx.data();
+    let guild = ctx.guild_id().expect("poise lied to me");
+
+    let allowed = match manager
+        .database()
+        .get_or_create_guild(guild.to_string())
+        .await
+    {
+        Ok(settings) => settings.recording,
+        Err(why) => {
+            error!("Error fetching guild: {why}");
+
+            ctx.send(
+                CreateReply::default()
+                    .embed(
+                        CreateEmbed::new()
+                            .description(t!(locale, "settings-fetch-failed"))
+                            .color(Colors::Error),
+                    )
+                    .ephemeral(true),
+            )
+            .await?;
+
+            return Ok(());
+        }
+    };
+
+    if !allowed {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title(t!(locale, "record-start-failed-title"))
+                        .description(t!(locale, "record-not-allowed"))
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    let session = match manager.get_session(SessionQuery::Guild(guild)) {
+        Some(session) if session.active().await? => session,
+        _ => {
+            ctx.send(
+                CreateReply::default()
+                    .embed(
+                        CreateEmbed::new()
+                            .title(t!(locale, "record-start-failed-title"))
+                            .description(t!(locale, "not-playing"))
+                            .color(Colors::Error),
+                    )
+                    .ephemeral(true),
+            )
+            .await?;
+
+            return Ok(());
+        }
+    };
+
+    let player = session.player().await?;
+
+    if player.is_recording().await? {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title(t!(locale, "record-start-failed-title"))
+                        .description(t!(locale, "record-already-recording"))
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    // Every recording gets its own directory, containing a file for every track
+    let directory = PathBuf::from(&spoticord_config::get().audio.recording_dir)
+        .join(guild.to_string())
+        .join((spoticord_utils::get_time() / 1000).to_string());
+
+    let format = match format {
+        Some(Format::Flac) => RecordingFormat::Flac,
+        Some(Format::Wav) | None => RecordingFormat::Wav,
+    };
+
+    if let Err(why) = player.start_recording(&directory, format).await {
+        error!("Failed to start recording: {why}");
+
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .description(t!(locale, "record-start-failed"))
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    // Not ephemeral, so everyone can see that they are being recorded
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::new()
+                .title(t!(locale, "record-started-title"))
+                .description(t!(
+                    locale,
+                    "record-started-description",
+                    channel = session.voice_channel().to_string()
+                ))
+                .color(Colors::Info),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Stop recording the music that is played in this server
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "stop",
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn record_stop(ctx: Context<'_>) -> Result<()> {
+    let locale = bot::locale(ctx).await;
+    let guild = ctx.guild_id().expect("poise lied to me");
+
+    let stopped = match ctx.data().get_session(SessionQuery::Guild(guild)) {
+        Some(session) => session.player().await?.stop_recording().await?,
+        None => false,
+    };
+
+    if !stopped {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title(t!(locale, "record-stop-failed-title"))
+                        .description(t!(locale, "record-not-recording"))
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::new()
+                .title(t!(locale, "record-stopped-title"))
+                .description(t!(locale, "record-stopped-description"))
+                .color(Colors::Success),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
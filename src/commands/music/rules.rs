@@ -0,0 +1,279 @@
+//! The rules that decide what the music commands are allowed to do.
+//!
+//! These only depend on the [`Sessions`] and [`Store`] traits, so they can be checked against in-memory sessions and
+//! databases without connecting to Discord.
+
+use anyhow::Result;
+use serenity::all::{ChannelId, GuildId, UserId};
+use spoticord_database::{error::DatabaseError, store::Store};
+use spoticord_session::manager::{SessionQuery, SessionState, Sessions};
+
+/// Everything from Discord that is needed to decide whether a user may start playing
+#[derive(Debug, Clone, Copy)]
+pub struct JoinRequest {
+    pub guild: GuildId,
+    pub user: UserId,
+
+    /// The voice channel the user is connected to
+    pub voice_channel: Option<ChannelId>,
+
+    /// Whether the bot can view, connect to and speak in the voice channel
+    pub voice_permissions: bool,
+
+    /// Whether the bot can send messages and embed links in the text channel
+    pub text_permissions: bool,
+}
+
+/// How a user can start playing
+#[derive(Debug)]
+pub enum Join<S> {
+    /// There is an inactive session in the user's voice channel, which the user can take over
+    Reactivate(S),
+
+    /// A new session must be created in `channel`, after disconnecting the inactive session in another channel
+    Create {
+        channel: ChannelId,
+        disconnect: Option<S>,
+    },
+}
+
+/// Why a user cannot start playing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinDenied {
+    NotInVoiceChannel,
+    NoVoicePermissions,
+    NoTextPermissions,
+
+    /// The user has not linked their Spotify account
+    NoAccount,
+
+    /// Someone else is already playing in this server
+    Busy,
+
+    /// The user is already playing in another server
+    AlreadyPlaying(GuildId),
+}
+
+/// Decide whether a user may start playing, and how
+pub async fn check_join<M: Sessions>(
+    manager: &M,
+    request: JoinRequest,
+) -> Result<Result<Join<M::Session>, JoinDenied>> {
+    let Some(channel) = request.voice_channel else {
+        return Ok(Err(JoinDenied::NotInVoiceChannel));
+    };
+
+    if !request.voice_permissions {
+        return Ok(Err(JoinDenied::NoVoicePermissions));
+    }
+
+    if !request.text_permissions {
+        return Ok(Err(JoinDenied::NoTextPermissions));
+    }
+
+    // Check whether the user has linked their Spotify account
+    if let Err(DatabaseError::NotFound) = manager
+        .database()
+        .get_account(&request.user.to_string())
+        .await
+    {
+        return Ok(Err(JoinDenied::NoAccount));
+    }
+
+    let session = manager.get_session(SessionQuery::Guild(request.guild));
+
+    // Check if this server already has a session active
+    if let Some(session) = &session {
+        if session.active().await? {
+            return Ok(Err(JoinDenied::Busy));
+        }
+    }
+
+    // Prevent the user from using Spoticord simultaneously in multiple servers
+    if let Some(session) = manager.get_session(SessionQuery::Owner(request.user)) {
+        return Ok(Err(JoinDenied::AlreadyPlaying(session.guild())));
+    }
+
+    Ok(Ok(match session {
+        Some(session) if session.voice_channel() == channel => Join::Reactivate(session),
+        disconnect => Join::Create {
+            channel,
+            disconnect,
+        },
+    }))
+}
+
+/// Whether a user may stop or disconnect a session, which only the host may do while music is playing
+pub async fn may_stop(session: &impl SessionState, user: UserId) -> Result<bool> {
+    Ok(!session.active().await? || session.owner().await? == user)
+}
+
+#[cfg(test)]
+mod tests {
+    use spoticord_database::memory::MemoryDatabase;
+    use spoticord_session::testing::{FakeSession, MemorySessions};
+
+    use super::*;
+
+    const GUILD: GuildId = GuildId::new(1);
+    const OTHER_GUILD: GuildId = GuildId::new(2);
+    const CHANNEL: ChannelId = ChannelId::new(10);
+    const OTHER_CHANNEL: ChannelId = ChannelId::new(11);
+    const USER: UserId = UserId::new(100);
+    const HOST: UserId = UserId::new(101);
+
+    /// Sessions in which `USER` has linked their Spotify account
+    fn sessions() -> MemorySessions {
+        let database = MemoryDatabase::new();
+        database.link_account(USER.to_string(), "user");
+
+        MemorySessions::new(database)
+    }
+
+    fn request() -> JoinRequest {
+        JoinRequest {
+            guild: GUILD,
+            user: USER,
+            voice_channel: Some(CHANNEL),
+            voice_permissions: true,
+            text_permissions: true,
+        }
+    }
+
+    fn session(
+        guild: GuildId,
+        voice_channel: ChannelId,
+        owner: UserId,
+        active: bool,
+    ) -> FakeSession {
+        FakeSession {
+            guild,
+            voice_channel,
+            owner,
+            active,
+        }
+    }
+
+    async fn denied(sessions: &MemorySessions, request: JoinRequest) -> Option<JoinDenied> {
+        check_join(sessions, request).await.unwrap().err()
+    }
+
+    #[tokio::test]
+    async fn join_requires_voice_channel_and_permissions() {
+        let sessions = sessions();
+
+        let cases = [
+            (
+                JoinRequest {
+                    voice_channel: None,
+                    ..request()
+                },
+                JoinDenied::NotInVoiceChannel,
+            ),
+            (
+                JoinRequest {
+                    voice_permissions: false,
+                    ..request()
+                },
+                JoinDenied::NoVoicePermissions,
+            ),
+            (
+                JoinRequest {
+                    text_permissions: false,
+                    ..request()
+                },
+                JoinDenied::NoTextPermissions,
+            ),
+        ];
+
+        for (request, reason) in cases {
+            assert_eq!(denied(&sessions, request).await, Some(reason));
+        }
+    }
+
+    #[tokio::test]
+    async fn join_requires_linked_account() {
+        let sessions = MemorySessions::new(MemoryDatabase::new());
+
+        assert_eq!(
+            denied(&sessions, request()).await,
+            Some(JoinDenied::NoAccount)
+        );
+    }
+
+    #[tokio::test]
+    async fn join_is_denied_while_guild_is_busy() {
+        let sessions = sessions();
+        sessions.insert(session(GUILD, OTHER_CHANNEL, HOST, true));
+
+        assert_eq!(denied(&sessions, request()).await, Some(JoinDenied::Busy));
+    }
+
+    #[tokio::test]
+    async fn join_is_denied_while_playing_elsewhere() {
+        let sessions = sessions();
+        sessions.insert(session(OTHER_GUILD, OTHER_CHANNEL, USER, true));
+
+        assert_eq!(
+            denied(&sessions, request()).await,
+            Some(JoinDenied::AlreadyPlaying(OTHER_GUILD))
+        );
+    }
+
+    #[tokio::test]
+    async fn join_creates_session() {
+        let sessions = sessions();
+
+        let join = check_join(&sessions, request()).await.unwrap().unwrap();
+
+        assert!(matches!(
+            join,
+            Join::Create {
+                channel,
+                disconnect: None,
+            } if channel == CHANNEL
+        ));
+    }
+
+    #[tokio::test]
+    async fn join_reactivates_inactive_session_in_same_channel() {
+        let sessions = sessions();
+        sessions.insert(session(GUILD, CHANNEL, HOST, false));
+
+        let join = check_join(&sessions, request()).await.unwrap().unwrap();
+
+        assert!(matches!(join, Join::Reactivate(session) if session.voice_channel == CHANNEL));
+    }
+
+    #[tokio::test]
+    async fn join_replaces_inactive_session_in_other_channel() {
+        let sessions = sessions();
+        sessions.insert(session(GUILD, OTHER_CHANNEL, HOST, false));
+
+        let join = check_join(&sessions, request()).await.unwrap().unwrap();
+
+        assert!(matches!(
+            join,
+            Join::Create {
+                channel,
+                disconnect: Some(session),
+            } if channel == CHANNEL && session.voice_channel == OTHER_CHANNEL
+        ));
+    }
+
+    #[tokio::test]
+    async fn only_host_may_stop_active_session() {
+        let active = session(GUILD, CHANNEL, HOST, true);
+
+        assert!(may_stop(&active, HOST).await.unwrap());
+        assert!(!may_stop(&active, USER).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn anyone_may_stop_inactive_session() {
+        let inactive = session(GUILD, CHANNEL, HOST, false);
+
+        assert!(may_stop(&inactive, HOST).await.unwrap());
+        assert!(may_stop(&inactive, USER).await.unwrap());
+    }
+}
@@ -0,0 +1,106 @@
+use anyhow::Result;
+use poise::CreateReply;
+use serenity::all::CreateEmbed;
+use spoticord_i18n::t;
+use spoticord_session::manager::SessionQuery;
+use spoticord_utils::discord::Colors;
+
+use crate::bot::{self, Context};
+
+/// Change how fast podcast episodes are played, without changing the pitch
+#[poise::command(slash_command, guild_only)]
+pub async fn speed(
+    ctx: Context<'_>,
+
+    #[description = "The playback speed, from 0.5x to 2x"]
+    #[min = 0.5]
+    #[max = 2.0]
+    speed: Option<f64>,
+) -> Result<()> {
+    let locale = bot::locale(ctx).await;
+    let manager = ctx.data();
+    let guild = ctx.guild_id().expect("poise lied to me");
+
+    let Some(session) = manager.get_session(SessionQuery::Guild(guild)) else {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title(t!(locale, "speed-failed-title"))
+                        .description(t!(locale, "not-playing"))
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    if !session.active().await? {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title(t!(locale, "speed-failed-title"))
+                        .description(t!(locale, "not-playing"))
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    let player = session.player().await?;
+
+    // Nothing to change, just show the current speed
+    let Some(speed) = speed else {
+        let current = player.speed().await?;
+
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title(t!(locale, "speed-title"))
+                        .description(t!(locale, "speed-current", speed = current.to_string()))
+                        .color(Colors::Info),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    };
+
+    if session.owner().await? != ctx.author().id {
+        ctx.send(
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title(t!(locale, "speed-failed-title"))
+                        .description(t!(locale, "speed-not-host"))
+                        .color(Colors::Error),
+                )
+                .ephemeral(true),
+        )
+        .await?;
+
+        return Ok(());
+    }
+
+    player.set_speed(speed as f32).await;
+
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::new()
+                .title(t!(locale, "speed-updated-title"))
+                .description(t!(locale, "speed-updated", speed = speed.to_string()))
+                .color(Colors::Success),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
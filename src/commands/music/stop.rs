@@ -1,13 +1,16 @@
 use anyhow::Error;
 use poise::CreateReply;
 use serenity::all::CreateEmbed;
+use spoticord_i18n::t;
 use spoticord_session::manager::SessionQuery;
 use spoticord_utils::discord::Colors;
 
-use crate::bot::Context;
+use super::rules::may_stop;
+use crate::bot::{self, Context};
 
 #[poise::command(slash_command, guild_only)]
 pub async fn stop(ctx: Context<'_>) -> Result<(), Error> {
+    let locale = bot::locale(ctx).await;
     let manager = ctx.data();
     let guild = ctx.guild_id().expect("poise lied to me");
 
@@ -16,8 +19,8 @@ pub async fn stop(ctx: Context<'_>) -> Result<(), Error> {
             CreateReply::default()
                 .embed(
                     CreateEmbed::new()
-                        .title("Cannot stop playback")
-                        .description("I'm currently not connected to any voice channel.")
+                        .title(t!(locale, "stop-failed-title"))
+                        .description(t!(locale, "not-connected"))
                         .color(Colors::Error),
                 )
                 .ephemeral(true),
@@ -27,13 +30,13 @@ pub async fn stop(ctx: Context<'_>) -> Result<(), Error> {
         return Ok(());
     };
 
-    if session.active().await? && session.owner().await? != ctx.author().id {
+    if !may_stop(&session, ctx.author().id).await? {
         ctx.send(
             CreateReply::default()
                 .embed(
                     CreateEmbed::new()
-                        .title("Cannot stop playback")
-                        .description("Only the host may stop playback.")
+                        .title(t!(locale, "stop-failed-title"))
+                        .description(t!(locale, "stop-not-host"))
                         .color(Colors::Error),
                 )
                 .ephemeral(true),
@@ -48,8 +51,8 @@ pub async fn stop(ctx: Context<'_>) -> Result<(), Error> {
     ctx.send(
         CreateReply::default().embed(
             CreateEmbed::new()
-                .title("Stopped playback")
-                .description("I have stopped playing for now. To resume playback, please run the /join command again.")
+                .title(t!(locale, "stop-title"))
+                .description(t!(locale, "stop-description"))
                 .color(Colors::Info),
         ),
     )
@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+use spoticord_config::LyricsCacheBacking;
+use spoticord_database::{error::DatabaseResultExt, Database};
+use spoticord_player::lyrics::{self, BackingFuture, LyricsBacking, RedisLyrics, StoredLyrics};
+
+/// Caches lyrics in the `lyrics_cache` table of the database
+struct PostgresLyrics(Database);
+
+impl LyricsBacking for PostgresLyrics {
+    fn get<'a>(&'a self, track_id: &'a str) -> BackingFuture<'a, Option<StoredLyrics>> {
+        Box::pin(async move {
+            let cached = self.0.get_cached_lyrics(track_id).await.optional()?;
+
+            Ok(cached.map(|cached| match cached.lyrics {
+                Some(data) => StoredLyrics::Found(data),
+                None => StoredLyrics::Missing,
+            }))
+        })
+    }
+
+    fn set<'a>(
+        &'a self,
+        track_id: &'a str,
+        lyrics: &'a StoredLyrics,
+        ttl: Duration,
+    ) -> BackingFuture<'a, ()> {
+        Box::pin(async move {
+            let data = match lyrics {
+                StoredLyrics::Found(data) => Some(data.as_slice()),
+                StoredLyrics::Missing => None,
+            };
+
+            self.0.save_cached_lyrics(track_id, data, ttl).await?;
+
+            Ok(())
+        })
+    }
+}
+
+/// Set up the shared lyrics cache with the backing that was configured
+pub fn init(database: &Database) -> anyhow::Result<()> {
+    let backing: Option<Box<dyn LyricsBacking>> = match spoticord_config::get().lyrics.cache_backing
+    {
+        LyricsCacheBacking::None => None,
+        LyricsCacheBacking::Redis => {
+            let url = spoticord_config::kv_url()
+                .ok_or_else(|| anyhow::anyhow!("KV_URL is required to cache lyrics in redis"))?;
+
+            Some(Box::new(RedisLyrics::new(url)?))
+        }
+        LyricsCacheBacking::Postgres => Some(Box::new(PostgresLyrics(database.clone()))),
+    };
+
+    lyrics::init(backing);
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 mod bot;
 mod commands;
+mod lyrics;
 
 use log::{error, info};
 use poise::Framework;
@@ -15,12 +16,9 @@ async fn main() {
     _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
 
     // Setup logging
+    // The actual verbosity is controlled by `bot.log_level`, so it can be changed without a restart
     if std::env::var("RUST_LOG").is_err() {
-        #[cfg(debug_assertions)]
-        std::env::set_var("RUST_LOG", "spoticord");
-
-        #[cfg(not(debug_assertions))]
-        std::env::set_var("RUST_LOG", "spoticord=info");
+        std::env::set_var("RUST_LOG", "spoticord=trace");
     }
 
     env_logger::init();
@@ -30,6 +28,18 @@ async fn main() {
 
     dotenvy::dotenv().ok();
 
+    // Load configuration
+    if let Err(errors) = spoticord_config::load() {
+        for why in errors.iter() {
+            error!("{why}");
+        }
+
+        error!("Failed to load configuration, bot will now shut down!");
+        return;
+    }
+
+    tokio::spawn(spoticord_config::watch());
+
     // Set up database
     let database = match Database::connect().await {
         Ok(db) => db,
@@ -39,6 +49,12 @@ async fn main() {
         }
     };
 
+    // Set up the lyrics cache that is shared between all sessions
+    if let Err(why) = lyrics::init(&database) {
+        error!("Failed to set up the lyrics cache: {why}");
+        return;
+    }
+
     // Set up bot
     let framework = Framework::builder()
         .setup(|ctx, ready, framework| Box::pin(bot::setup(ctx, ready, framework, database)))
@@ -50,6 +66,7 @@ async fn main() {
         spoticord_config::discord_intents(),
     )
     .framework(framework)
+    // Clipping is prevented by the limiter in the audio sink (see `audio.limiter`)
     .register_songbird_from_config(songbird::Config::default().use_softclip(false))
     .await
     {